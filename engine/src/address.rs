@@ -0,0 +1,112 @@
+use crate::hash::keccak;
+use crate::signer::{rlp_bytes, rlp_list, rlp_u256};
+use aurora_engine_types::types::Address;
+use aurora_engine_types::U256;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// Separator of the engine account id and the address in the deposit recipients.
+const RECIPIENT_SEPARATOR: char = ':';
+
+/// Returns the EVM address of the NEAR account: the last 20 bytes of the keccak hash of
+/// the account id. The engine uses it for the senders of `call` and the NEP-141 transfers.
+pub fn near_account_to_evm_address(account_id: impl AsRef<str>) -> Address {
+    let hash = keccak(account_id.as_ref().as_bytes());
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&hash.as_bytes()[12..]);
+    Address::from_array(address)
+}
+
+/// Returns the address of the contract deployed by the transaction of the sender with
+/// the nonce: the last 20 bytes of the keccak hash of the RLP encoded pair.
+pub fn create_address(sender: Address, nonce: U256) -> Address {
+    let hash = keccak(&rlp_list(&[rlp_bytes(sender.as_bytes()), rlp_u256(nonce)]));
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&hash.as_bytes()[12..]);
+    Address::from_array(address)
+}
+
+/// Parses the hex encoded address, the `0x` prefix is optional. The addresses in
+/// mixed case must have the valid EIP-55 checksum.
+pub fn parse_address(address: &str) -> anyhow::Result<Address> {
+    let hex = address.strip_prefix("0x").unwrap_or(address);
+    let parsed =
+        Address::decode(hex).map_err(|e| anyhow::anyhow!("invalid address `{address}`: {e:?}"))?;
+
+    let has_lower = hex.chars().any(|c| c.is_ascii_lowercase());
+    let has_upper = hex.chars().any(|c| c.is_ascii_uppercase());
+    if has_lower && has_upper && to_checksum_address(&parsed)[2..] != *hex {
+        anyhow::bail!("invalid checksum of address `{address}`");
+    }
+
+    Ok(parsed)
+}
+
+/// Encodes the address with the `0x` prefix and the EIP-55 checksum.
+pub fn to_checksum_address(address: &Address) -> String {
+    let hex = address.encode();
+    let hash = keccak(hex.as_bytes());
+    let checksummed: String = hex
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let byte = hash.as_bytes()[i / 2];
+            let nibble = if i % 2 == 0 { byte >> 4 } else { byte & 0x0f };
+            if nibble >= 8 {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        })
+        .collect();
+    format!("0x{checksummed}")
+}
+
+/// EVM recipient of the bridged tokens in the `<engine id>:<address hex>` format,
+/// e.g. `aurora:5e3d...`. The address part is the message of `ft_transfer_call`
+/// which the engine expects in `ft_on_transfer`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvmRecipient {
+    pub engine_id: String,
+    pub address: Address,
+}
+
+impl EvmRecipient {
+    pub fn new(engine_id: impl AsRef<str>, address: Address) -> Self {
+        Self {
+            engine_id: engine_id.as_ref().to_string(),
+            address,
+        }
+    }
+
+    /// Returns the message of `ft_transfer_call` to the engine which deposits the tokens
+    /// to the address.
+    pub fn message(&self) -> String {
+        self.address.encode()
+    }
+}
+
+impl Display for EvmRecipient {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}{RECIPIENT_SEPARATOR}{}",
+            self.engine_id,
+            self.address.encode()
+        )
+    }
+}
+
+impl FromStr for EvmRecipient {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (engine_id, address) = s
+            .split_once(RECIPIENT_SEPARATOR)
+            .ok_or_else(|| anyhow::anyhow!("missing `{RECIPIENT_SEPARATOR}` in recipient `{s}`"))?;
+        if engine_id.is_empty() {
+            anyhow::bail!("missing engine account id in recipient `{s}`");
+        }
+        Ok(Self::new(engine_id, parse_address(address)?))
+    }
+}
@@ -0,0 +1,579 @@
+use crate::address::create_address;
+use crate::hash::keccak;
+use crate::operation::{CallCall, ViewView};
+use crate::signer::{EvmSigner, LegacyTransaction};
+use crate::types::{Address, TransactionStatus};
+use crate::EngineContract;
+use aurora_engine_types::{H256, U256};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Length of the library address placeholder in the hex encoded bytecode.
+const PLACEHOLDER_LEN: usize = 40;
+
+/// Compiled EVM contract: the creation and the runtime bytecode with the ABI.
+///
+/// Loads the Foundry (`out/<File>.sol/<Name>.json`) and Hardhat artifacts by `from_file`,
+/// and the output of `solc --combined-json abi,bin,bin-runtime` and `vyper -f combined_json`
+/// by `from_combined_json`. The bytecode referencing libraries has to be linked by `link`
+/// before it's deployed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Artifact {
+    pub contract_name: String,
+    pub abi: Abi,
+    pub bytecode: Bytecode,
+    pub deployed_bytecode: Bytecode,
+}
+
+impl Artifact {
+    /// Loads the Foundry or Hardhat artifact. The contract name is the `contractName`
+    /// field of the Hardhat artifacts, or the file name for Foundry.
+    pub fn from_file(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let json = read_json(path)?;
+        let contract_name = match json.get("contractName").and_then(Value::as_str) {
+            Some(name) => name.to_string(),
+            None => file_stem(path),
+        };
+        Self::from_json(contract_name, &json)
+            .map_err(|e| anyhow::anyhow!("error while loading artifact {}: {e}", path.display()))
+    }
+
+    /// Loads the Foundry artifact of the contract from the output directory, e.g. `out`.
+    pub fn from_foundry_out(
+        out_dir: impl AsRef<Path>,
+        contract_name: &str,
+    ) -> anyhow::Result<Self> {
+        let path = out_dir
+            .as_ref()
+            .join(format!("{contract_name}.sol"))
+            .join(format!("{contract_name}.json"));
+        Self::from_file(path)
+    }
+
+    /// Parses the artifact with the bytecode as the hex string (Hardhat) or the object
+    /// with the link references (Foundry).
+    pub fn from_json(contract_name: impl Into<String>, json: &Value) -> anyhow::Result<Self> {
+        let bytecode = field(json, "bytecode")?;
+        let deployed_bytecode = field(json, "deployedBytecode")?;
+        Ok(Self {
+            contract_name: contract_name.into(),
+            abi: Abi::from_json(field(json, "abi")?)?,
+            bytecode: Bytecode::from_json(bytecode, json.get("linkReferences"))?,
+            deployed_bytecode: Bytecode::from_json(
+                deployed_bytecode,
+                json.get("deployedLinkReferences"),
+            )?,
+        })
+    }
+
+    /// Loads the contract from the combined JSON output of `solc` or `vyper`. The name is
+    /// the contract name or the fully qualified `<file>:<name>` for `solc`, and the file
+    /// name without the extension or the file path for `vyper`.
+    pub fn from_combined_json(path: impl AsRef<Path>, contract_name: &str) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let mut artifacts = Self::all_from_combined_json(path)?;
+        let names: Vec<_> = artifacts
+            .keys()
+            .filter(|name| {
+                *name == contract_name
+                    || name.rsplit(':').next() == Some(contract_name)
+                    || file_stem(Path::new(name.as_str())) == contract_name
+            })
+            .cloned()
+            .collect();
+        match names.as_slice() {
+            [name] => Ok(artifacts.remove(name).unwrap()),
+            [] => anyhow::bail!("no contract `{contract_name}` in {}", path.display()),
+            _ => anyhow::bail!(
+                "ambiguous contract `{contract_name}` in {}: {}",
+                path.display(),
+                names.join(", ")
+            ),
+        }
+    }
+
+    /// Loads all the contracts of the combined JSON output by their fully qualified names.
+    pub fn all_from_combined_json(
+        path: impl AsRef<Path>,
+    ) -> anyhow::Result<BTreeMap<String, Self>> {
+        let path = path.as_ref();
+        let json = read_json(path)?;
+        // `solc` nests the contracts, `vyper` puts them at the top level next to the version.
+        let contracts = json
+            .get("contracts")
+            .unwrap_or(&json)
+            .as_object()
+            .ok_or_else(|| anyhow::anyhow!("invalid combined JSON {}", path.display()))?;
+
+        contracts
+            .iter()
+            .filter(|(_, contract)| contract.is_object())
+            .map(|(name, contract)| {
+                Self::from_combined_contract(name, contract)
+                    .map(|artifact| (name.clone(), artifact))
+                    .map_err(|e| anyhow::anyhow!("error while loading `{name}`: {e}"))
+            })
+            .collect()
+    }
+
+    fn from_combined_contract(name: &str, contract: &Value) -> anyhow::Result<Self> {
+        let (bytecode, deployed_bytecode) = if contract.get("bin").is_some() {
+            (field(contract, "bin")?, field(contract, "bin-runtime")?)
+        } else {
+            (
+                field(contract, "bytecode")?,
+                field(contract, "bytecode_runtime")?,
+            )
+        };
+        // The older `solc` versions encode the ABI as the JSON string.
+        let abi = match field(contract, "abi")? {
+            Value::String(abi) => serde_json::from_str(abi)?,
+            abi => abi.clone(),
+        };
+        let contract_name = match name.rsplit_once(':') {
+            Some((_, contract_name)) => contract_name.to_string(),
+            None => file_stem(Path::new(name)),
+        };
+
+        Ok(Self {
+            contract_name,
+            abi: Abi::from_json(&abi)?,
+            bytecode: Bytecode::from_json(bytecode, None)?,
+            deployed_bytecode: Bytecode::from_json(deployed_bytecode, None)?,
+        })
+    }
+
+    /// Links the library, by the name or the fully qualified `<file>:<name>`, to the
+    /// creation and the runtime bytecode.
+    pub fn link(mut self, library: &str, address: Address) -> anyhow::Result<Self> {
+        let linked = self.bytecode.link(library, address)?;
+        let deployed = self.deployed_bytecode.link(library, address)?;
+        if !linked && !deployed {
+            anyhow::bail!(
+                "library `{library}` is not referenced by contract `{}`",
+                self.contract_name
+            );
+        }
+        Ok(self)
+    }
+
+    /// Returns the input of the deployment: the creation bytecode followed by the ABI
+    /// encoded constructor arguments.
+    pub fn deploy_input(&self, constructor_args: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let mut input = self.bytecode.to_bytes()?;
+        input.extend_from_slice(constructor_args);
+        Ok(input)
+    }
+
+    /// Deploys the contract by `deploy_code`, signed by the engine contract account.
+    pub async fn deploy(
+        &self,
+        engine: &EngineContract,
+        constructor_args: &[u8],
+    ) -> anyhow::Result<EvmContract> {
+        let result = engine
+            .deploy_code(self.deploy_input(constructor_args)?)
+            .max_gas()
+            .transact()
+            .await
+            .map_err(|e| anyhow::anyhow!("error while deploying {}: {e}", self.contract_name))?
+            .into_value();
+        let address = match result.status {
+            TransactionStatus::Succeed(bytes) => Address::try_from_slice(&bytes)
+                .map_err(|e| anyhow::anyhow!("invalid deployed address: {e:?}"))?,
+            status => anyhow::bail!("deployment of {} failed: {status:?}", self.contract_name),
+        };
+        Ok(EvmContract::new(engine, address, self.abi.clone()))
+    }
+
+    /// Deploys the contract by the transaction signed by the signer and submitted by `submit`.
+    /// The address is derived from the address and the nonce of the signer.
+    pub async fn deploy_with_signer(
+        &self,
+        engine: &EngineContract,
+        signer: &EvmSigner,
+        constructor_args: &[u8],
+    ) -> anyhow::Result<EvmContract> {
+        // The views of the deployment aren't recorded in the trace, unlike its transaction.
+        let views = engine.with_recorder(None);
+        let nonce = views
+            .get_nonce(signer.address())
+            .await
+            .map_err(|e| anyhow::anyhow!("error while getting nonce: {e}"))?
+            .result;
+        let chain_id = views
+            .get_chain_id()
+            .await
+            .map_err(|e| anyhow::anyhow!("error while getting chain id: {e}"))?
+            .result;
+        let transaction = LegacyTransaction::new(
+            nonce,
+            None,
+            U256::zero(),
+            self.deploy_input(constructor_args)?,
+        );
+
+        let result = engine
+            .submit(signer.sign(&transaction, chain_id.as_u64()))
+            .max_gas()
+            .transact()
+            .await
+            .map_err(|e| anyhow::anyhow!("error while deploying {}: {e}", self.contract_name))?
+            .into_value();
+        if !matches!(result.status, TransactionStatus::Succeed(_)) {
+            anyhow::bail!(
+                "deployment of {} failed: {:?}",
+                self.contract_name,
+                result.status
+            );
+        }
+        let address = create_address(signer.address(), nonce);
+        Ok(EvmContract::new(engine, address, self.abi.clone()))
+    }
+}
+
+/// Hex encoded bytecode which may hold the placeholders of the library addresses.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Bytecode {
+    hex: String,
+    /// Byte offsets of the library addresses by the fully qualified library names.
+    link_references: BTreeMap<String, Vec<usize>>,
+}
+
+impl Bytecode {
+    pub fn new(hex: &str, link_references: BTreeMap<String, Vec<usize>>) -> Self {
+        Self {
+            hex: hex.strip_prefix("0x").unwrap_or(hex).to_string(),
+            link_references,
+        }
+    }
+
+    /// Parses the bytecode as the hex string, or the Foundry object with the `object`
+    /// and `linkReferences` fields.
+    fn from_json(bytecode: &Value, link_references: Option<&Value>) -> anyhow::Result<Self> {
+        let (hex, link_references) = match bytecode {
+            Value::String(hex) => (hex.as_str(), link_references),
+            Value::Object(object) => (
+                object
+                    .get("object")
+                    .and_then(Value::as_str)
+                    .ok_or_else(|| anyhow::anyhow!("missing bytecode object"))?,
+                object.get("linkReferences"),
+            ),
+            _ => anyhow::bail!("invalid bytecode {bytecode}"),
+        };
+        let link_references = match link_references {
+            Some(references) => parse_link_references(references)?,
+            None => BTreeMap::new(),
+        };
+        Ok(Self::new(hex, link_references))
+    }
+
+    pub fn is_linked(&self) -> bool {
+        !self.hex.contains("__")
+    }
+
+    /// Replaces the placeholders of the library by its address. The library is matched by
+    /// the link references, and by the placeholders of `solc`: the hash of the fully
+    /// qualified name, or the name itself for the versions before 0.5. Returns whether
+    /// the bytecode references the library.
+    pub fn link(&mut self, library: &str, address: Address) -> anyhow::Result<bool> {
+        let address = address.encode();
+        let mut linked = false;
+
+        for (name, offsets) in &self.link_references {
+            if name != library && name.rsplit(':').next() != Some(library) {
+                continue;
+            }
+            for offset in offsets {
+                let range = offset * 2..offset * 2 + PLACEHOLDER_LEN;
+                if self.hex.get(range.clone()).is_none() {
+                    anyhow::bail!("link reference of `{name}` at {offset} is out of bytecode");
+                }
+                self.hex.replace_range(range, &address);
+                linked = true;
+            }
+        }
+
+        let hash = keccak(library.as_bytes());
+        // The legacy placeholder keeps the first 36 bytes of the name; cut on a char
+        // boundary so that a non-ASCII name can't panic.
+        let end = (0..=library.len().min(36))
+            .rev()
+            .find(|end| library.is_char_boundary(*end))
+            .unwrap_or_default();
+        let placeholders = [
+            format!("__${}$__", hex::encode(&hash.as_bytes()[..17])),
+            format!("__{:_<38}", &library[..end]),
+        ];
+        for placeholder in placeholders {
+            if self.hex.contains(&placeholder) {
+                self.hex = self.hex.replace(&placeholder, &address);
+                linked = true;
+            }
+        }
+
+        Ok(linked)
+    }
+
+    /// Decodes the bytecode, which must have all the libraries linked.
+    pub fn to_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        if let Some(start) = self.hex.find("__") {
+            let end = (start + PLACEHOLDER_LEN).min(self.hex.len());
+            anyhow::bail!("unlinked library placeholder `{}`", &self.hex[start..end]);
+        }
+        hex::decode(&self.hex).map_err(|e| anyhow::anyhow!("invalid bytecode: {e}"))
+    }
+}
+
+/// Parses the link references of the `{"<file>": {"<library>": [{"start": .., "length": 20}]}}`
+/// format into the offsets by the fully qualified library names.
+fn parse_link_references(references: &Value) -> anyhow::Result<BTreeMap<String, Vec<usize>>> {
+    #[derive(Deserialize)]
+    struct Reference {
+        start: usize,
+    }
+
+    let references: BTreeMap<String, BTreeMap<String, Vec<Reference>>> =
+        serde_json::from_value(references.clone())
+            .map_err(|e| anyhow::anyhow!("invalid link references: {e}"))?;
+    Ok(references
+        .into_iter()
+        .flat_map(|(file, libraries)| {
+            libraries.into_iter().map(move |(library, references)| {
+                let offsets = references.iter().map(|reference| reference.start).collect();
+                (format!("{file}:{library}"), offsets)
+            })
+        })
+        .collect())
+}
+
+/// Functions, events and the constructor of the contract ABI.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Abi {
+    pub constructor: Option<AbiFunction>,
+    pub functions: Vec<AbiFunction>,
+    pub events: Vec<AbiEvent>,
+}
+
+impl Abi {
+    /// Parses the JSON ABI. The errors, the fallback and the receive functions are skipped.
+    pub fn from_json(json: &Value) -> anyhow::Result<Self> {
+        let items = json
+            .as_array()
+            .ok_or_else(|| anyhow::anyhow!("ABI is not an array: {json}"))?;
+        let mut abi = Self::default();
+
+        for item in items {
+            // The type defaults to `function` by the ABI specification.
+            match item
+                .get("type")
+                .and_then(Value::as_str)
+                .unwrap_or("function")
+            {
+                "function" => abi.functions.push(parse_abi_item(item)?),
+                "constructor" => abi.constructor = Some(parse_abi_item(item)?),
+                "event" => abi.events.push(parse_abi_item(item)?),
+                _ => {}
+            }
+        }
+
+        Ok(abi)
+    }
+
+    /// Finds the function by the name, or by the signature if the function is overloaded,
+    /// e.g. `transfer(address,uint256)`.
+    pub fn function(&self, name: &str) -> anyhow::Result<&AbiFunction> {
+        if name.contains('(') {
+            return self
+                .functions
+                .iter()
+                .find(|function| function.signature() == name)
+                .ok_or_else(|| anyhow::anyhow!("no function `{name}` in ABI"));
+        }
+
+        let mut functions = self
+            .functions
+            .iter()
+            .filter(|function| function.name == name);
+        match (functions.next(), functions.next()) {
+            (Some(function), None) => Ok(function),
+            (None, _) => anyhow::bail!("no function `{name}` in ABI"),
+            (Some(_), Some(_)) => {
+                anyhow::bail!("function `{name}` is overloaded, use the signature instead")
+            }
+        }
+    }
+
+    pub fn event(&self, name: &str) -> anyhow::Result<&AbiEvent> {
+        self.events
+            .iter()
+            .find(|event| event.name == name || event.signature() == name)
+            .ok_or_else(|| anyhow::anyhow!("no event `{name}` in ABI"))
+    }
+}
+
+fn parse_abi_item<T: for<'de> Deserialize<'de>>(item: &Value) -> anyhow::Result<T> {
+    serde_json::from_value(item.clone())
+        .map_err(|e| anyhow::anyhow!("invalid ABI item {item}: {e}"))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AbiFunction {
+    /// Empty for the constructor.
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub inputs: Vec<AbiParam>,
+    #[serde(default)]
+    pub outputs: Vec<AbiParam>,
+    #[serde(default)]
+    pub state_mutability: Option<String>,
+}
+
+impl AbiFunction {
+    /// Returns the canonical signature, e.g. `transfer(address,uint256)`.
+    pub fn signature(&self) -> String {
+        signature(&self.name, &self.inputs)
+    }
+
+    /// Returns the first 4 bytes of the keccak hash of the signature.
+    pub fn selector(&self) -> [u8; 4] {
+        let mut selector = [0u8; 4];
+        selector.copy_from_slice(&keccak(self.signature().as_bytes()).as_bytes()[..4]);
+        selector
+    }
+
+    /// Returns the input of the call: the selector followed by the ABI encoded arguments.
+    pub fn encode_input(&self, args: &[u8]) -> Vec<u8> {
+        [&self.selector()[..], args].concat()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct AbiEvent {
+    pub name: String,
+    #[serde(default)]
+    pub inputs: Vec<AbiParam>,
+    #[serde(default)]
+    pub anonymous: bool,
+}
+
+impl AbiEvent {
+    pub fn signature(&self) -> String {
+        signature(&self.name, &self.inputs)
+    }
+
+    /// Returns the first topic of the event logs: the keccak hash of the signature.
+    pub fn topic(&self) -> H256 {
+        keccak(self.signature().as_bytes())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct AbiParam {
+    #[serde(default)]
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: String,
+    /// Fields of the tuples.
+    #[serde(default)]
+    pub components: Vec<AbiParam>,
+    #[serde(default)]
+    pub indexed: bool,
+}
+
+impl AbiParam {
+    /// Returns the type of the signatures, which spells out the fields of the tuples,
+    /// e.g. `(address,uint256)[]`.
+    pub fn canonical_type(&self) -> String {
+        match self.ty.strip_prefix("tuple") {
+            Some(suffix) => {
+                let components: Vec<_> = self
+                    .components
+                    .iter()
+                    .map(AbiParam::canonical_type)
+                    .collect();
+                format!("({}){suffix}", components.join(","))
+            }
+            None => self.ty.clone(),
+        }
+    }
+}
+
+fn signature(name: &str, inputs: &[AbiParam]) -> String {
+    let types: Vec<_> = inputs.iter().map(AbiParam::canonical_type).collect();
+    format!("{name}({})", types.join(","))
+}
+
+/// Deployed EVM contract with the ABI of its artifact, which builds the calls to the
+/// engine by the function names.
+#[derive(Debug, Clone)]
+pub struct EvmContract {
+    engine: EngineContract,
+    address: Address,
+    abi: Abi,
+}
+
+impl EvmContract {
+    pub fn new(engine: &EngineContract, address: Address, abi: Abi) -> Self {
+        Self {
+            engine: engine.clone(),
+            address,
+            abi,
+        }
+    }
+
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    pub fn abi(&self) -> &Abi {
+        &self.abi
+    }
+
+    /// Calls the function with the ABI encoded arguments by the engine `call`.
+    pub fn call(&self, function: &str, args: &[u8]) -> anyhow::Result<CallCall> {
+        self.call_with_value(function, U256::zero(), args)
+    }
+
+    pub fn call_with_value(
+        &self,
+        function: &str,
+        value: U256,
+        args: &[u8],
+    ) -> anyhow::Result<CallCall> {
+        let input = self.abi.function(function)?.encode_input(args);
+        Ok(self.engine.call(self.address, value, input))
+    }
+
+    /// Runs the function by the engine `view` on behalf of the sender.
+    pub fn view(&self, sender: Address, function: &str, args: &[u8]) -> anyhow::Result<ViewView> {
+        let input = self.abi.function(function)?.encode_input(args);
+        Ok(self
+            .engine
+            .get_view(sender, self.address, U256::zero(), input))
+    }
+}
+
+fn read_json(path: &Path) -> anyhow::Result<Value> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("error while reading {}: {e}", path.display()))?;
+    serde_json::from_str(&content)
+        .map_err(|e| anyhow::anyhow!("error while parsing {}: {e}", path.display()))
+}
+
+fn field<'a>(json: &'a Value, name: &str) -> anyhow::Result<&'a Value> {
+    json.get(name)
+        .ok_or_else(|| anyhow::anyhow!("missing `{name}` field"))
+}
+
+fn file_stem(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
@@ -0,0 +1,433 @@
+use crate::operation::{ViewFtBalanceOf, ViewFtTotalSupply, ViewNep141StorageBalanceOf};
+use crate::types::{
+    to_aurora_account_id, to_near_account_id, AccountId, Address, TransactionStatus,
+};
+use crate::{ContractId, EngineContract};
+use aurora_engine_types::U256;
+use aurora_workspace_utils::Contract;
+use near_sdk::json_types::U128;
+use near_workspaces::types::NearToken;
+use near_workspaces::Account;
+use serde::Deserialize;
+use serde_json::json;
+use std::fmt::{Display, Formatter};
+use std::future::Future;
+
+/// Default tolerance of the NEAR balance changes, which covers the gas of a few transactions.
+pub const DEFAULT_GAS_ALLOWANCE: NearToken = NearToken::from_millinear(100);
+/// Selector of the ERC-20 `balanceOf(address)`.
+const BALANCE_OF_SELECTOR: [u8; 4] = [0x70, 0xa0, 0x82, 0x31];
+
+/// Storage balance of NEP-145, which the bridged tokens return.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct FtStorageBalance {
+    pub total: U128,
+    pub available: U128,
+}
+
+/// Balance tracked by `BalanceTracker`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Balance {
+    /// NEAR balance of the account in yocto.
+    Near(AccountId),
+    /// Balance of the account on the NEP-141 token. The engine is the token of ETH.
+    Nep141 {
+        token: AccountId,
+        account_id: AccountId,
+    },
+    /// Storage deposit of the account on the NEP-141 token.
+    StorageDeposit {
+        token: AccountId,
+        account_id: AccountId,
+    },
+    Nep141TotalSupply(AccountId),
+    /// ETH balance of the EVM address in wei.
+    Eth(Address),
+    Erc20 {
+        token: Address,
+        holder: Address,
+    },
+    TotalEthSupplyOnAurora,
+    TotalEthSupplyOnNear,
+}
+
+impl Balance {
+    pub fn near(account_id: impl AsRef<str>) -> anyhow::Result<Self> {
+        Ok(Self::Near(parse_account_id(account_id)?))
+    }
+
+    pub fn nep141(token: impl AsRef<str>, account_id: impl AsRef<str>) -> anyhow::Result<Self> {
+        Ok(Self::Nep141 {
+            token: parse_account_id(token)?,
+            account_id: parse_account_id(account_id)?,
+        })
+    }
+
+    pub fn storage_deposit(
+        token: impl AsRef<str>,
+        account_id: impl AsRef<str>,
+    ) -> anyhow::Result<Self> {
+        Ok(Self::StorageDeposit {
+            token: parse_account_id(token)?,
+            account_id: parse_account_id(account_id)?,
+        })
+    }
+}
+
+impl Display for Balance {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Near(account_id) => write!(f, "NEAR balance of {account_id}"),
+            Self::Nep141 { token, account_id } => write!(f, "{token} balance of {account_id}"),
+            Self::StorageDeposit { token, account_id } => {
+                write!(f, "{token} storage deposit of {account_id}")
+            }
+            Self::Nep141TotalSupply(token) => write!(f, "{token} total supply"),
+            Self::Eth(address) => write!(f, "ETH balance of 0x{}", address.encode()),
+            Self::Erc20 { token, holder } => write!(
+                f,
+                "ERC-20 0x{} balance of 0x{}",
+                token.encode(),
+                holder.encode()
+            ),
+            Self::TotalEthSupplyOnAurora => write!(f, "total ETH supply on Aurora"),
+            Self::TotalEthSupplyOnNear => write!(f, "total ETH supply on NEAR"),
+        }
+    }
+}
+
+/// Snapshots the balances on the NEAR and the EVM sides before and after the transactions,
+/// e.g. the bridge operations, to assert their changes.
+///
+/// Tracks the balances of the declared NEAR accounts and EVM addresses on the declared tokens,
+/// the total supplies of the tokens and the total supplies of ETH. The engine is tracked as
+/// the NEP-141 token of ETH.
+#[derive(Debug, Clone)]
+pub struct BalanceTracker {
+    engine: EngineContract,
+    near_accounts: Vec<Account>,
+    evm_addresses: Vec<Address>,
+    nep141_tokens: Vec<(AccountId, Contract)>,
+    erc20_tokens: Vec<Address>,
+    gas_allowance: NearToken,
+}
+
+impl BalanceTracker {
+    pub fn new(engine: &EngineContract) -> Self {
+        let engine_id = to_aurora_account_id(engine.id());
+        // The snapshots are views of the tracker, so they aren't recorded in the trace.
+        let engine = engine.with_recorder(None);
+        Self {
+            near_accounts: vec![],
+            evm_addresses: vec![],
+            nep141_tokens: vec![(engine_id, engine.as_contract().clone())],
+            erc20_tokens: vec![],
+            gas_allowance: DEFAULT_GAS_ALLOWANCE,
+            engine,
+        }
+    }
+
+    pub fn near_account(mut self, account: &Account) -> Self {
+        self.near_accounts.push(account.clone());
+        self
+    }
+
+    pub fn evm_address(mut self, address: Address) -> Self {
+        self.evm_addresses.push(address);
+        self
+    }
+
+    pub fn nep141(mut self, token: AccountId) -> Self {
+        let contract = self
+            .engine
+            .as_contract()
+            .with_contract_id(to_near_account_id(&token));
+        self.nep141_tokens.push((token, contract));
+        self
+    }
+
+    pub fn erc20(mut self, token: Address) -> Self {
+        self.erc20_tokens.push(token);
+        self
+    }
+
+    /// Sets the tolerance of the NEAR balance changes for the gas of the transactions.
+    pub fn gas_allowance(mut self, gas_allowance: NearToken) -> Self {
+        self.gas_allowance = gas_allowance;
+        self
+    }
+
+    /// Runs the transactions between the snapshots and returns their output with the changes
+    /// of the balances.
+    pub async fn track<F, Fut, T>(&self, transactions: F) -> anyhow::Result<(T, BalanceDiff)>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = anyhow::Result<T>>,
+    {
+        let before = self.snapshot().await?;
+        let output = transactions().await?;
+        let after = self.snapshot().await?;
+        Ok((output, self.diff(before, after)))
+    }
+
+    pub async fn snapshot(&self) -> anyhow::Result<BalanceSnapshot> {
+        let mut balances = Vec::new();
+        for balance in self.balances() {
+            let value = self
+                .read(&balance)
+                .await
+                .map_err(|e| anyhow::anyhow!("error while reading {balance}: {e}"))?;
+            balances.push((balance, value));
+        }
+        Ok(BalanceSnapshot { balances })
+    }
+
+    pub fn diff(&self, before: BalanceSnapshot, after: BalanceSnapshot) -> BalanceDiff {
+        BalanceDiff {
+            before,
+            after,
+            gas_allowance: self.gas_allowance.as_yoctonear(),
+        }
+    }
+
+    fn balances(&self) -> Vec<Balance> {
+        let mut balances = vec![
+            Balance::TotalEthSupplyOnAurora,
+            Balance::TotalEthSupplyOnNear,
+        ];
+        for (token, _) in &self.nep141_tokens {
+            balances.push(Balance::Nep141TotalSupply(token.clone()));
+        }
+        for account in &self.near_accounts {
+            let account_id = to_aurora_account_id(account.id());
+            balances.push(Balance::Near(account_id.clone()));
+            for (token, _) in &self.nep141_tokens {
+                balances.push(Balance::Nep141 {
+                    token: token.clone(),
+                    account_id: account_id.clone(),
+                });
+                balances.push(Balance::StorageDeposit {
+                    token: token.clone(),
+                    account_id: account_id.clone(),
+                });
+            }
+        }
+        for address in &self.evm_addresses {
+            balances.push(Balance::Eth(*address));
+            for token in &self.erc20_tokens {
+                balances.push(Balance::Erc20 {
+                    token: *token,
+                    holder: *address,
+                });
+            }
+        }
+        balances
+    }
+
+    async fn read(&self, balance: &Balance) -> anyhow::Result<U256> {
+        let value = match balance {
+            Balance::Near(account_id) => {
+                let account = self
+                    .near_accounts
+                    .iter()
+                    .find(|account| account.id().as_str() == account_id.as_ref())
+                    .ok_or_else(|| anyhow::anyhow!("{account_id} is not tracked"))?;
+                account.view_account().await?.balance.as_yoctonear().into()
+            }
+            Balance::Nep141 { token, account_id } => ViewFtBalanceOf::view(self.token(token)?)
+                .args_json(json!({ "account_id": account_id }))
+                .await?
+                .result
+                .0
+                .into(),
+            // The engine reports the storage balance in its own format.
+            Balance::StorageDeposit { token, account_id } if self.is_engine(token) => self
+                .engine
+                .storage_balance_of(account_id)
+                .await?
+                .result
+                .total
+                .as_u128()
+                .into(),
+            Balance::StorageDeposit { token, account_id } => {
+                ViewNep141StorageBalanceOf::view(self.token(token)?)
+                    .args_json(json!({ "account_id": account_id }))
+                    .await?
+                    .result
+                    .map_or(0, |storage| storage.total.0)
+                    .into()
+            }
+            Balance::Nep141TotalSupply(token) => ViewFtTotalSupply::view(self.token(token)?)
+                .await?
+                .result
+                .0
+                .into(),
+            Balance::Eth(address) => self.engine.get_balance(*address).await?.result,
+            Balance::Erc20 { token, holder } => {
+                let input = [&BALANCE_OF_SELECTOR[..], &[0; 12], holder.as_bytes()].concat();
+                let status = self
+                    .engine
+                    .get_view(*holder, *token, U256::zero(), input)
+                    .await?
+                    .result;
+                decode_erc20_balance(status)?
+            }
+            Balance::TotalEthSupplyOnAurora => self
+                .engine
+                .ft_total_eth_supply_on_aurora()
+                .await?
+                .result
+                .0
+                .into(),
+            Balance::TotalEthSupplyOnNear => self
+                .engine
+                .ft_total_eth_supply_on_near()
+                .await?
+                .result
+                .0
+                .into(),
+        };
+        Ok(value)
+    }
+
+    fn token(&self, token: &AccountId) -> anyhow::Result<&Contract> {
+        self.nep141_tokens
+            .iter()
+            .find_map(|(id, contract)| (id == token).then_some(contract))
+            .ok_or_else(|| anyhow::anyhow!("token {token} is not tracked"))
+    }
+
+    fn is_engine(&self, token: &AccountId) -> bool {
+        self.engine.id().as_str() == token.as_ref()
+    }
+}
+
+/// The addresses without the code return the empty output, which is reported as zero.
+fn decode_erc20_balance(status: TransactionStatus) -> anyhow::Result<U256> {
+    match status {
+        TransactionStatus::Succeed(output) if output.is_empty() => Ok(U256::zero()),
+        TransactionStatus::Succeed(output) if output.len() == 32 => {
+            Ok(U256::from_big_endian(&output))
+        }
+        TransactionStatus::Succeed(output) => {
+            anyhow::bail!("invalid balanceOf output 0x{}", hex::encode(output))
+        }
+        status => anyhow::bail!("balanceOf failed: {status:?}"),
+    }
+}
+
+/// Values of the tracked balances at some point.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BalanceSnapshot {
+    balances: Vec<(Balance, U256)>,
+}
+
+impl BalanceSnapshot {
+    pub fn get(&self, balance: &Balance) -> Option<U256> {
+        self.balances
+            .iter()
+            .find_map(|(tracked, value)| (tracked == balance).then_some(*value))
+    }
+}
+
+/// Changes of the tracked balances between the snapshots.
+#[derive(Debug, Clone)]
+pub struct BalanceDiff {
+    before: BalanceSnapshot,
+    after: BalanceSnapshot,
+    gas_allowance: u128,
+}
+
+impl BalanceDiff {
+    pub fn before(&self) -> &BalanceSnapshot {
+        &self.before
+    }
+
+    pub fn after(&self) -> &BalanceSnapshot {
+        &self.after
+    }
+
+    /// Returns the change of the balance, or an error if the balance isn't tracked or
+    /// the change doesn't fit `i128`.
+    pub fn delta(&self, balance: &Balance) -> anyhow::Result<i128> {
+        let (before, after) = match (self.before.get(balance), self.after.get(balance)) {
+            (Some(before), Some(after)) => (before, after),
+            _ => anyhow::bail!("{balance} is not tracked"),
+        };
+        let (magnitude, negative) = if after >= before {
+            (after - before, false)
+        } else {
+            (before - after, true)
+        };
+        if magnitude.bits() >= 128 {
+            anyhow::bail!("change of {balance} overflows i128");
+        }
+        let magnitude = magnitude.low_u128() as i128;
+        Ok(if negative { -magnitude } else { magnitude })
+    }
+
+    /// Returns the changed balances with their changes.
+    pub fn changes(&self) -> anyhow::Result<Vec<(Balance, i128)>> {
+        let mut changes = Vec::new();
+        for (balance, _) in &self.before.balances {
+            let delta = self.delta(balance)?;
+            if delta != 0 {
+                changes.push((balance.clone(), delta));
+            }
+        }
+        Ok(changes)
+    }
+
+    /// Asserts the change of the balance.
+    ///
+    /// The changes of the NEAR balances don't include the storage deposits on the tracked tokens,
+    /// and may differ from the expected ones by the gas allowance, which covers the gas paid
+    /// by the signers and the gas rewards of the contracts.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the change differs from the expected one or can't be computed.
+    #[track_caller]
+    pub fn assert_delta(&self, balance: Balance, expected: i128) {
+        let delta = self.expect_delta(&balance);
+        let Balance::Near(account_id) = &balance else {
+            assert_eq!(delta, expected, "unexpected change of {balance}");
+            return;
+        };
+
+        let storage_delta: i128 = self
+            .before
+            .balances
+            .iter()
+            .filter(|(tracked, _)| match tracked {
+                Balance::StorageDeposit { account_id: id, .. } => id == account_id,
+                _ => false,
+            })
+            .map(|(tracked, _)| self.expect_delta(tracked))
+            .sum();
+        let delta = delta + storage_delta;
+        let allowance = self.gas_allowance as i128;
+        assert!(
+            (expected - allowance..=expected + allowance).contains(&delta),
+            "unexpected change of {balance}: {delta} without storage deposits, expected {expected} \
+             within gas allowance {allowance}"
+        );
+    }
+
+    #[track_caller]
+    pub fn assert_unchanged(&self, balance: Balance) {
+        self.assert_delta(balance, 0);
+    }
+
+    #[track_caller]
+    fn expect_delta(&self, balance: &Balance) -> i128 {
+        self.delta(balance).unwrap_or_else(|e| panic!("{e}"))
+    }
+}
+
+fn parse_account_id(account_id: impl AsRef<str>) -> anyhow::Result<AccountId> {
+    let account_id = account_id.as_ref();
+    account_id
+        .parse()
+        .map_err(|e| anyhow::anyhow!("error while parsing account id {account_id}: {e:?}"))
+}
@@ -1,40 +1,44 @@
+use crate::exit::ExitCall;
+use crate::flags::{EnginePausedFlags, PrecompileFlags};
+use crate::message::FtTransferMessage;
 use crate::operation::{
-    CallCall, CallDeployCode, CallDeployErc20Token, CallDeployUpgrade, CallDeposit,
-    CallFactorySetWNearAddress, CallFactoryUpdate, CallFactoryUpdateAddressVersion,
+    CallAddRelayerKey, CallCall, CallDeployCode, CallDeployErc20Token, CallDeployUpgrade,
+    CallDeposit, CallFactorySetWNearAddress, CallFactoryUpdate, CallFactoryUpdateAddressVersion,
     CallFtOnTransfer, CallFtTransfer, CallFtTransferCall, CallFundXccSubAccount, CallMintAccount,
     CallNew, CallNewEthConnector, CallPausePrecompiles, CallRefundOnError, CallRegisterRelayer,
-    CallResumePrecompiles, CallSetEthConnectorContractData, CallSetPausedFlags, CallStageUpgrade,
-    CallStateMigration, CallStorageDeposit, CallStorageUnregister, CallStorageWithdraw, CallSubmit,
-    CallWithdraw, ViewAccountsCounter, ViewBalance, ViewBlockHash, ViewBridgeProver, ViewChainId,
-    ViewCode, ViewErc20FromNep141, ViewFtBalanceOf, ViewFtBalanceOfEth, ViewFtMetadata,
+    CallRemoveRelayerKey, CallResumePrecompiles, CallSetEthConnectorContractData,
+    CallSetKeyManager, CallSetPausedFlags, CallStageUpgrade, CallStateMigration,
+    CallStorageDeposit, CallStorageUnregister, CallStorageWithdraw, CallSubmit, CallWithdraw,
+    ViewAccountsCounter, ViewBalance, ViewBlockHash, ViewBridgeProver, ViewChainId, ViewCode,
+    ViewErc20FromNep141, ViewFtBalanceOf, ViewFtBalanceOfEth, ViewFtMetadata,
     ViewFtTotalEthSupplyOnAurora, ViewFtTotalEthSupplyOnNear, ViewFtTotalSupply, ViewIsUsedProof,
     ViewNep141FromErc20, ViewNonce, ViewOwner, ViewPausedFlags, ViewPausedPrecompiles,
     ViewStorageAt, ViewStorageBalanceOf, ViewUpgradeIndex, ViewVersion, ViewView,
 };
-use crate::types::Account;
+use crate::signer::EvmSigner;
+use crate::types::{to_near_account_id, Account, FundXccArgs};
 use aurora_engine_types::account_id::AccountId;
-use aurora_engine_types::borsh::{BorshDeserialize, BorshSerialize};
 use aurora_engine_types::parameters::connector::{FungibleTokenMetadata, Proof};
 use aurora_engine_types::parameters::engine::{
     CallArgs, FunctionCallArgsV2, NewCallArgs, NewCallArgsV2,
 };
 use aurora_engine_types::types::{Address, RawU256, WeiU256};
 use aurora_engine_types::{H256, U256};
-use aurora_workspace_utils::{Contract, ContractId};
+#[cfg(feature = "local")]
+use aurora_workspace_utils::local::{LocalAccount, LocalContract};
+use aurora_workspace_utils::trace::TraceRecorder;
+use aurora_workspace_utils::transactions::BatchTransaction;
+use aurora_workspace_utils::{Contract, ContractBackend, ContractId};
 use near_sdk::json_types::U128;
+use near_workspaces::types::{NearToken, PublicKey};
 use serde_json::json;
 
-// TODO: import from aurora_engine_types
-#[derive(Debug, Clone, PartialEq, Eq, BorshDeserialize, BorshSerialize)]
-pub struct FundXccArgs {
-    pub target: Address,
-    pub wnear_account_id: Option<AccountId>,
-}
-
+/// Engine contract deployed to the sandbox or, with the `local` feature, executed by the
+/// in-process runtime.
 #[derive(Debug, Clone)]
-pub struct EngineContract {
-    contract: Contract,
-    root: Account,
+pub struct EngineContract<C: ContractBackend = Contract> {
+    contract: C,
+    root: C::Account,
 }
 
 impl EngineContract {
@@ -45,10 +49,56 @@ impl EngineContract {
     pub fn root(&self) -> &Account {
         &self.root
     }
+
+    /// Returns a view of the same contract which signs transactions with the given account.
+    pub fn as_account(&self, account: &Account) -> Self {
+        Self {
+            contract: self.contract.with_signer(account),
+            root: self.root.clone(),
+        }
+    }
+
+    /// Starts a transaction which may contain several calls to the contract.
+    pub fn batch(&self) -> BatchTransaction {
+        self.contract.batch()
+    }
 }
 
-impl ContractId for EngineContract {
-    fn as_contract(&self) -> &Contract {
+#[cfg(feature = "local")]
+impl EngineContract<LocalContract> {
+    /// Creates the contract executed by the in-process backend.
+    pub fn new_local(contract: LocalContract, root: LocalAccount) -> Self {
+        Self { contract, root }
+    }
+
+    /// Returns the root account of the in-process runtime.
+    pub fn root(&self) -> &LocalAccount {
+        &self.root
+    }
+
+    /// Returns a view of the same in-process contract which signs transactions with the
+    /// given account.
+    pub fn as_local_account(&self, account: &LocalAccount) -> Self {
+        Self {
+            contract: self.contract.with_signer(account),
+            root: self.root.clone(),
+        }
+    }
+}
+
+impl<C: ContractBackend> EngineContract<C> {
+    /// Returns a view of the same contract which appends the calls and views issued through
+    /// it to the trace of the recorder, or doesn't record them if the recorder is `None`.
+    pub fn with_recorder(&self, recorder: Option<TraceRecorder>) -> Self {
+        Self {
+            contract: self.contract.with_recorder(recorder),
+            root: self.root.clone(),
+        }
+    }
+}
+
+impl<C: ContractBackend> ContractId<C> for EngineContract<C> {
+    fn as_contract(&self) -> &C {
         &self.contract
     }
 
@@ -58,7 +108,7 @@ impl ContractId for EngineContract {
 }
 
 /// Callable functions
-impl EngineContract {
+impl<C: ContractBackend> EngineContract<C> {
     #[allow(clippy::new_ret_no_self)]
     pub fn new(
         &self,
@@ -113,6 +163,36 @@ impl EngineContract {
         }))
     }
 
+    /// Deposits the NEP-141 tokens of the signer to the EVM address by `ft_transfer_call`
+    /// of the token to the engine. ETH is deposited when the token is the engine itself.
+    pub fn deposit_to_aurora(
+        &self,
+        nep141: &AccountId,
+        amount: U128,
+        evm_recipient: Address,
+    ) -> anyhow::Result<CallFtTransferCall> {
+        self.deposit_to_aurora_with_message(nep141, amount, &FtTransferMessage::new(evm_recipient))
+    }
+
+    /// Deposits the NEP-141 tokens to the engine with the message, e.g. the ETH deposit
+    /// which pays the fee to the relayer.
+    pub fn deposit_to_aurora_with_message(
+        &self,
+        nep141: &AccountId,
+        amount: U128,
+        message: &FtTransferMessage,
+    ) -> anyhow::Result<CallFtTransferCall> {
+        let token = self.contract.with_contract_id(to_near_account_id(nep141));
+        Ok(CallFtTransferCall::call(&token)
+            .args_json(json!({
+               "receiver_id": self.id(),
+               "amount": amount.0.to_string(),
+               "memo": None::<String>,
+               "msg": message.to_string(),
+            }))
+            .deposit(NearToken::from_yoctonear(1)))
+    }
+
     pub fn storage_deposit(
         &self,
         account_id: Option<AccountId>,
@@ -194,10 +274,63 @@ impl EngineContract {
         CallSubmit::call(&self.contract).args(input)
     }
 
+    /// Sends the exit call to the engine with `call`, the result can be decoded by `ExitOutcome`.
+    pub fn exit(&self, exit: ExitCall) -> CallCall {
+        self.call(exit.to, exit.value, exit.input)
+    }
+
+    /// Sends the exit signed by the EVM account with `submit`, the result can be decoded by
+    /// `ExitOutcome`. The nonce and the chain id must be the ones of the engine.
+    pub fn exit_signed(
+        &self,
+        signer: &EvmSigner,
+        nonce: U256,
+        chain_id: u64,
+        exit: ExitCall,
+    ) -> CallSubmit {
+        self.submit(signer.sign(&exit.into_transaction(nonce), chain_id))
+    }
+
+    pub fn exit_to_near(&self, recipient: &str, amount: U256) -> CallCall {
+        self.exit(ExitCall::eth_to_near(recipient, amount))
+    }
+
+    pub fn exit_to_ethereum(&self, recipient: Address, amount: U256) -> CallCall {
+        self.exit(ExitCall::eth_to_ethereum(recipient, amount))
+    }
+
+    pub fn withdraw_to_near(&self, token: Address, recipient: &str, amount: U256) -> CallCall {
+        self.exit(ExitCall::erc20_to_near(token, recipient, amount))
+    }
+
+    pub fn withdraw_to_ethereum(
+        &self,
+        token: Address,
+        recipient: Address,
+        amount: U256,
+    ) -> CallCall {
+        self.exit(ExitCall::erc20_to_ethereum(token, recipient, amount))
+    }
+
     pub fn register_relayer(&self, address: Address) -> CallRegisterRelayer {
         CallRegisterRelayer::call(&self.contract).args_borsh(address)
     }
 
+    /// Sets the account allowed to add and remove the relayer keys, `None` removes it.
+    pub fn set_key_manager(&self, key_manager: Option<AccountId>) -> CallSetKeyManager {
+        CallSetKeyManager::call(&self.contract).args_json(json!({ "key_manager": key_manager }))
+    }
+
+    /// Adds the function-call access key of a relayer to the engine account. Must be called
+    /// by the key manager, the attached deposit becomes the allowance of the key.
+    pub fn add_relayer_key(&self, public_key: &PublicKey) -> CallAddRelayerKey {
+        CallAddRelayerKey::call(&self.contract).args_json(json!({ "public_key": public_key }))
+    }
+
+    pub fn remove_relayer_key(&self, public_key: &PublicKey) -> CallRemoveRelayerKey {
+        CallRemoveRelayerKey::call(&self.contract).args_json(json!({ "public_key": public_key }))
+    }
+
     pub fn ft_on_transfer(
         &self,
         sender_id: AccountId,
@@ -239,11 +372,11 @@ impl EngineContract {
         CallDeployUpgrade::call(&self.contract)
     }
 
-    pub fn pause_precompiles(&self, paused_mask: u32) -> CallPausePrecompiles {
+    pub fn pause_precompiles(&self, paused_mask: PrecompileFlags) -> CallPausePrecompiles {
         CallPausePrecompiles::call(&self.contract).args_borsh(paused_mask)
     }
 
-    pub fn resume_precompiles(&self, paused_mask: u32) -> CallResumePrecompiles {
+    pub fn resume_precompiles(&self, paused_mask: PrecompileFlags) -> CallResumePrecompiles {
         CallResumePrecompiles::call(&self.contract).args_borsh(paused_mask)
     }
 
@@ -260,13 +393,13 @@ impl EngineContract {
         CallMintAccount::call(&self.contract).args_borsh((address, init_nonce, init_balance))
     }
 
-    pub fn set_paused_flags(&self, flags: u8) -> CallSetPausedFlags {
+    pub fn set_paused_flags(&self, flags: EnginePausedFlags) -> CallSetPausedFlags {
         CallSetPausedFlags::call(&self.contract).args_borsh(flags)
     }
 }
 
 /// View functions
-impl EngineContract {
+impl<C: ContractBackend> EngineContract<C> {
     pub fn ft_total_supply(&self) -> ViewFtTotalSupply {
         ViewFtTotalSupply::view(&self.contract)
     }
@@ -0,0 +1,458 @@
+use crate::types::{Address, SubmitResult, TransactionStatus};
+use crate::{ContractId, EngineContract, EngineContractBuilder};
+use aurora_engine_types::parameters::engine::ResultLog;
+use aurora_engine_types::{H256, U256};
+use std::fmt::{Display, Formatter};
+
+/// Runs the same scenario against two engines, e.g. the current release and the new one,
+/// and reports where their results diverge.
+///
+/// Transactions are signed by the root accounts of the engines, so both engines must be
+/// deployed the same way for the results to be comparable.
+#[derive(Debug, Clone)]
+pub struct DiffRunner {
+    baseline: EngineContract,
+    candidate: EngineContract,
+    slots: Vec<(Address, H256)>,
+    compare_gas_used: bool,
+}
+
+impl DiffRunner {
+    pub fn new(baseline: EngineContract, candidate: EngineContract) -> Self {
+        Self {
+            baseline,
+            candidate,
+            slots: vec![],
+            compare_gas_used: true,
+        }
+    }
+
+    /// Deploys both WASM files with the default `EngineContractBuilder`.
+    pub async fn deploy(baseline_code: Vec<u8>, candidate_code: Vec<u8>) -> anyhow::Result<Self> {
+        let deploy = |code: Vec<u8>| async move {
+            EngineContractBuilder::new()?
+                .with_code(code)
+                .deploy_and_init()
+                .await
+        };
+        let baseline = deploy(baseline_code)
+            .await
+            .map_err(|e| anyhow::anyhow!("error while deploying baseline engine: {e}"))?;
+        let candidate = deploy(candidate_code)
+            .await
+            .map_err(|e| anyhow::anyhow!("error while deploying candidate engine: {e}"))?;
+        Ok(Self::new(baseline, candidate))
+    }
+
+    pub fn baseline(&self) -> &EngineContract {
+        &self.baseline
+    }
+
+    pub fn candidate(&self) -> &EngineContract {
+        &self.candidate
+    }
+
+    /// Compares the storage slot of the contract after every transaction of the scenario.
+    pub fn storage_slot(mut self, address: Address, key: H256) -> Self {
+        self.slots.push((address, key));
+        self
+    }
+
+    /// Doesn't report the differences of the EVM gas used, e.g. when the new release is
+    /// expected to change the gas costs.
+    pub fn ignore_gas_used(mut self) -> Self {
+        self.compare_gas_used = false;
+        self
+    }
+
+    /// Executes the steps on both engines and returns the divergences of the results.
+    pub async fn run(&self, steps: &[Step]) -> anyhow::Result<DiffReport> {
+        let mut report = DiffReport::default();
+
+        for (index, step) in steps.iter().enumerate() {
+            let baseline = execute(&self.baseline, step).await;
+            let candidate = execute(&self.candidate, step).await;
+            self.compare(&mut report, index, step, &baseline, &candidate);
+
+            if step.is_transaction() {
+                for (address, key) in &self.slots {
+                    let baseline = self.storage_at(&self.baseline, *address, *key).await?;
+                    let candidate = self.storage_at(&self.candidate, *address, *key).await?;
+                    report.push(
+                        index,
+                        step,
+                        Field::StorageSlot(*address, *key),
+                        &format!("{baseline:?}"),
+                        &format!("{candidate:?}"),
+                    );
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
+    fn compare(
+        &self,
+        report: &mut DiffReport,
+        index: usize,
+        step: &Step,
+        baseline: &Outcome,
+        candidate: &Outcome,
+    ) {
+        match (baseline, candidate) {
+            (Outcome::Submit(baseline), Outcome::Submit(candidate)) => {
+                report.push(
+                    index,
+                    step,
+                    Field::Status,
+                    &render_status(&baseline.status),
+                    &render_status(&candidate.status),
+                );
+                if self.compare_gas_used {
+                    report.push(
+                        index,
+                        step,
+                        Field::GasUsed,
+                        &baseline.gas_used.to_string(),
+                        &candidate.gas_used.to_string(),
+                    );
+                }
+                report.push(
+                    index,
+                    step,
+                    Field::Logs,
+                    &render_logs(&baseline.logs),
+                    &render_logs(&candidate.logs),
+                );
+            }
+            _ => {
+                let field = if baseline.is_view() && candidate.is_view() {
+                    Field::ViewOutput
+                } else {
+                    Field::Outcome
+                };
+                report.push(
+                    index,
+                    step,
+                    field,
+                    &baseline.to_string(),
+                    &candidate.to_string(),
+                );
+            }
+        }
+    }
+
+    async fn storage_at(
+        &self,
+        engine: &EngineContract,
+        address: Address,
+        key: H256,
+    ) -> anyhow::Result<H256> {
+        // The comparison of the storage isn't a step of the scenario, so it isn't recorded.
+        let view = engine.with_recorder(None).get_storage_at(address, key);
+        let result = view.await.map_err(|e| {
+            anyhow::anyhow!(
+                "error while reading storage of 0x{} from {}: {e}",
+                address.encode(),
+                engine.id()
+            )
+        })?;
+        Ok(result.result)
+    }
+}
+
+/// Call or view executed by `DiffRunner` on both engines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Step {
+    Call {
+        contract: Address,
+        value: U256,
+        input: Vec<u8>,
+    },
+    /// `submit` of the signed transaction.
+    Submit(Vec<u8>),
+    DeployCode(Vec<u8>),
+    View {
+        sender: Address,
+        address: Address,
+        value: U256,
+        input: Vec<u8>,
+    },
+    StorageAt {
+        address: Address,
+        key: H256,
+    },
+    Balance(Address),
+    Nonce(Address),
+    Code(Address),
+}
+
+impl Step {
+    /// Whether the step changes the state of the engine.
+    pub const fn is_transaction(&self) -> bool {
+        matches!(
+            self,
+            Self::Call { .. } | Self::Submit(_) | Self::DeployCode(_)
+        )
+    }
+}
+
+impl Display for Step {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Call {
+                contract,
+                value,
+                input,
+            } => write!(
+                f,
+                "call 0x{} value {value} input 0x{}",
+                contract.encode(),
+                hex::encode(input)
+            ),
+            Self::Submit(raw_tx) => write!(f, "submit 0x{}", hex::encode(raw_tx)),
+            Self::DeployCode(code) => write!(f, "deploy_code ({} bytes)", code.len()),
+            Self::View {
+                sender,
+                address,
+                value,
+                input,
+            } => write!(
+                f,
+                "get_view 0x{} -> 0x{} value {value} input 0x{}",
+                sender.encode(),
+                address.encode(),
+                hex::encode(input)
+            ),
+            Self::StorageAt { address, key } => {
+                write!(f, "get_storage_at 0x{} {key:?}", address.encode())
+            }
+            Self::Balance(address) => write!(f, "get_balance 0x{}", address.encode()),
+            Self::Nonce(address) => write!(f, "get_nonce 0x{}", address.encode()),
+            Self::Code(address) => write!(f, "get_code 0x{}", address.encode()),
+        }
+    }
+}
+
+/// Result of the step on one of the engines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Outcome {
+    Submit(SubmitResult),
+    View(TransactionStatus),
+    Storage(H256),
+    Value(U256),
+    Code(Vec<u8>),
+    /// The NEAR transaction or view failed.
+    Failed(String),
+}
+
+impl Outcome {
+    const fn is_view(&self) -> bool {
+        !matches!(self, Self::Submit(_) | Self::Failed(_))
+    }
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Submit(result) => write!(
+                f,
+                "{}, gas used {}, {} logs",
+                render_status(&result.status),
+                result.gas_used,
+                result.logs.len()
+            ),
+            Self::View(status) => f.write_str(&render_status(status)),
+            Self::Storage(value) => write!(f, "{value:?}"),
+            Self::Value(value) => write!(f, "{value}"),
+            Self::Code(code) => write!(f, "0x{}", hex::encode(code)),
+            Self::Failed(error) => write!(f, "failed: {error}"),
+        }
+    }
+}
+
+async fn execute(engine: &EngineContract, step: &Step) -> Outcome {
+    let result = match step {
+        Step::Call {
+            contract,
+            value,
+            input,
+        } => engine
+            .call(*contract, *value, input.clone())
+            .max_gas()
+            .transact()
+            .await
+            .map(|result| Outcome::Submit(result.into_value())),
+        Step::Submit(raw_tx) => engine
+            .submit(raw_tx.clone())
+            .max_gas()
+            .transact()
+            .await
+            .map(|result| Outcome::Submit(result.into_value())),
+        Step::DeployCode(code) => engine
+            .deploy_code(code.clone())
+            .max_gas()
+            .transact()
+            .await
+            .map(|result| Outcome::Submit(result.into_value())),
+        Step::View {
+            sender,
+            address,
+            value,
+            input,
+        } => engine
+            .get_view(*sender, *address, *value, input.clone())
+            .await
+            .map(|view| Outcome::View(view.result)),
+        Step::StorageAt { address, key } => engine
+            .get_storage_at(*address, *key)
+            .await
+            .map(|view| Outcome::Storage(view.result)),
+        Step::Balance(address) => engine
+            .get_balance(*address)
+            .await
+            .map(|view| Outcome::Value(view.result)),
+        Step::Nonce(address) => engine
+            .get_nonce(*address)
+            .await
+            .map(|view| Outcome::Value(view.result)),
+        Step::Code(address) => engine
+            .get_code(*address)
+            .await
+            .map(|view| Outcome::Code(view.result)),
+    };
+    result.unwrap_or_else(|e| Outcome::Failed(e.to_string()))
+}
+
+fn render_status(status: &TransactionStatus) -> String {
+    match status {
+        TransactionStatus::Succeed(output) => format!("Succeed(0x{})", hex::encode(output)),
+        TransactionStatus::Revert(output) => format!("Revert(0x{})", hex::encode(output)),
+        other => format!("{other:?}"),
+    }
+}
+
+fn render_logs(logs: &[ResultLog]) -> String {
+    let logs: Vec<_> = logs
+        .iter()
+        .map(|log| {
+            let topics: Vec<_> = log.topics.iter().map(hex::encode).collect();
+            format!(
+                "0x{} [{}] 0x{}",
+                log.address.encode(),
+                topics.join(", "),
+                hex::encode(&log.data)
+            )
+        })
+        .collect();
+    format!("[{}]", logs.join("; "))
+}
+
+/// Part of the results compared by `DiffRunner`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    /// Status of the `SubmitResult`.
+    Status,
+    /// EVM gas used reported by the `SubmitResult`.
+    GasUsed,
+    /// Logs of the `SubmitResult`.
+    Logs,
+    /// Storage slot of the contract read after the transaction.
+    StorageSlot(Address, H256),
+    /// Output of the view.
+    ViewOutput,
+    /// The step failed on one of the engines, or both failed differently.
+    Outcome,
+}
+
+impl Display for Field {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Status => f.write_str("status"),
+            Self::GasUsed => f.write_str("gas used"),
+            Self::Logs => f.write_str("logs"),
+            Self::StorageSlot(address, key) => {
+                write!(f, "storage slot {key:?} of 0x{}", address.encode())
+            }
+            Self::ViewOutput => f.write_str("view output"),
+            Self::Outcome => f.write_str("outcome"),
+        }
+    }
+}
+
+/// Different results of the step on the baseline and candidate engines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Divergence {
+    /// Index of the step in the scenario.
+    pub step: usize,
+    /// Description of the step.
+    pub description: String,
+    pub field: Field,
+    pub baseline: String,
+    pub candidate: String,
+}
+
+impl Display for Divergence {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "step {} ({}): {} differs",
+            self.step, self.description, self.field
+        )?;
+        writeln!(f, "  baseline:  {}", self.baseline)?;
+        write!(f, "  candidate: {}", self.candidate)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DiffReport {
+    pub divergences: Vec<Divergence>,
+}
+
+impl DiffReport {
+    pub fn is_ok(&self) -> bool {
+        self.divergences.is_empty()
+    }
+
+    pub fn divergences_of(&self, step: usize) -> Vec<&Divergence> {
+        self.divergences
+            .iter()
+            .filter(|divergence| divergence.step == step)
+            .collect()
+    }
+
+    /// Panics with the list of the divergences, if any.
+    #[track_caller]
+    pub fn assert_ok(&self) {
+        assert!(self.is_ok(), "engines diverged:\n{self}");
+    }
+
+    fn push(
+        &mut self,
+        step: usize,
+        description: &Step,
+        field: Field,
+        baseline: &str,
+        candidate: &str,
+    ) {
+        if baseline != candidate {
+            self.divergences.push(Divergence {
+                step,
+                description: description.to_string(),
+                field,
+                baseline: baseline.to_string(),
+                candidate: candidate.to_string(),
+            });
+        }
+    }
+}
+
+impl Display for DiffReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for divergence in &self.divergences {
+            writeln!(f, "{divergence}")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,79 @@
+use crate::types::{Address, SubmitResult, TransactionStatus};
+#[cfg(feature = "local")]
+use aurora_engine_types::borsh::BorshDeserialize;
+#[cfg(feature = "local")]
+use aurora_workspace_utils::local::LocalOutcome;
+use near_workspaces::types::Gas;
+
+/// Gas charged by the protocol for every trie node touched by a storage operation
+/// (`touching_trie_node`). The in-process simulation doesn't charge it.
+pub const TOUCHING_TRIE_NODE_GAS: Gas = Gas::from_gas(16_101_955_926);
+/// Trie nodes touched by a storage operation of the engine: the depth of the trie of the
+/// engine account with up to a million keys, each level of the path being one node.
+pub const TRIE_NODES_PER_STORAGE_OPERATION: u64 = 20;
+/// Maximum NEAR gas which can be attached to a function call.
+pub const MAX_NEAR_GAS: Gas = Gas::from_tgas(300);
+/// EVM gas charged for every transaction.
+pub const TX_BASE_EVM_GAS: u64 = 21_000;
+/// EVM gas charged for the creation of a contract.
+pub const TX_CREATE_EVM_GAS: u64 = 32_000;
+/// EVM gas charged per zero byte of the transaction data.
+pub const TX_DATA_ZERO_EVM_GAS: u64 = 4;
+/// EVM gas charged per non-zero byte of the transaction data.
+pub const TX_DATA_NON_ZERO_EVM_GAS: u64 = 16;
+
+/// EVM gas of the transaction and the NEAR gas to attach to the function call executing it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GasEstimate {
+    pub evm_gas: u64,
+    pub near_gas: Gas,
+}
+
+impl GasEstimate {
+    /// Estimates the gas by the engine call simulated in-process. The EVM gas is the one
+    /// reported in the `SubmitResult` of the simulation. The NEAR gas is the gas used by
+    /// the simulation plus the trie nodes touched by its storage operations on the node.
+    #[cfg(feature = "local")]
+    pub fn from_simulation(outcome: &LocalOutcome) -> anyhow::Result<Self> {
+        let value = outcome
+            .status
+            .as_deref()
+            .map_err(|e| anyhow::anyhow!("the simulation of the call has failed: {e}"))?;
+        let result = SubmitResult::try_from_slice(value)
+            .map_err(|e| anyhow::anyhow!("error while decoding simulation result: {e}"))?;
+        let trie_gas = TOUCHING_TRIE_NODE_GAS
+            .as_gas()
+            .saturating_mul(TRIE_NODES_PER_STORAGE_OPERATION)
+            .saturating_mul(outcome.storage_operations);
+        Self::from_submit_result(&result, outcome.gas_used.as_gas().saturating_add(trie_gas))
+    }
+
+    /// Checks that the simulated EVM transaction has succeeded and that the NEAR gas it
+    /// needs can be attached to a function call.
+    pub fn from_submit_result(result: &SubmitResult, near_gas: u64) -> anyhow::Result<Self> {
+        match &result.status {
+            TransactionStatus::Succeed(_) => {}
+            status => anyhow::bail!("the simulated transaction has failed: {status:?}"),
+        }
+        anyhow::ensure!(
+            near_gas <= MAX_NEAR_GAS.as_gas(),
+            "the call needs {near_gas} gas, more than the maximum of {MAX_NEAR_GAS}"
+        );
+        Ok(Self {
+            evm_gas: result.gas_used,
+            near_gas: Gas::from_gas(near_gas),
+        })
+    }
+}
+
+/// Returns the EVM gas charged for the transaction before executing any code.
+pub fn intrinsic_gas(to: Option<Address>, data: &[u8]) -> u64 {
+    let create = if to.is_none() { TX_CREATE_EVM_GAS } else { 0 };
+    data.iter().fold(TX_BASE_EVM_GAS + create, |gas, byte| {
+        gas + if *byte == 0 {
+            TX_DATA_ZERO_EVM_GAS
+        } else {
+            TX_DATA_NON_ZERO_EVM_GAS
+        }
+    })
+}
@@ -0,0 +1,200 @@
+use crate::signer::LegacyTransaction;
+use crate::types::{Address, SubmitResult, TransactionStatus};
+use aurora_engine_types::parameters::connector::WithdrawResult;
+use aurora_engine_types::U256;
+use aurora_workspace_utils::receipts::ReceiptNode;
+use aurora_workspace_utils::results::ExecutionResult;
+use near_sdk::json_types::U128;
+use near_workspaces::result::ValueOrReceiptId;
+use near_workspaces::AccountId;
+use serde::Deserialize;
+
+/// Address of the `exitToNear` precompile: `keccak("exitToNear")[12..]`.
+const EXIT_TO_NEAR_ADDRESS: &str = "e9217bc70b7ed1f598ddd3199e80b093fa71124f";
+/// Address of the `exitToEthereum` precompile: `keccak("exitToEthereum")[12..]`.
+const EXIT_TO_ETHEREUM_ADDRESS: &str = "b0bd02f6a392af548bdf1cfaee5dfa0eefcc8eab";
+/// Selector of `withdrawToNear(bytes,uint256)` of the bridged ERC-20 tokens.
+const WITHDRAW_TO_NEAR_SELECTOR: [u8; 4] = [0x6b, 0x35, 0x18, 0x48];
+/// Selector of `withdrawToEthereum(address,uint256)` of the bridged ERC-20 tokens.
+const WITHDRAW_TO_ETHEREUM_SELECTOR: [u8; 4] = [0x8d, 0x32, 0xca, 0xf4];
+/// Flag of the precompile input which exits base ETH, the amount is the attached value.
+const FLAG_ETH: u8 = 0;
+const EVENT_JSON_PREFIX: &str = "EVENT_JSON:";
+
+pub fn exit_to_near_address() -> Address {
+    Address::decode(EXIT_TO_NEAR_ADDRESS).expect("valid precompile address")
+}
+
+pub fn exit_to_ethereum_address() -> Address {
+    Address::decode(EXIT_TO_ETHEREUM_ADDRESS).expect("valid precompile address")
+}
+
+/// EVM call which bridges funds out of Aurora.
+///
+/// Base ETH is sent directly to the exit precompiles. Bridged ERC-20 tokens are exited by
+/// calling the token contract, which burns the tokens and calls the precompile itself.
+/// The exit is sent by `EngineContract::exit` with `call`, or signed by an EVM account
+/// with `EngineContract::exit_signed` and sent with `submit`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExitCall {
+    pub to: Address,
+    pub value: U256,
+    pub input: Vec<u8>,
+}
+
+impl ExitCall {
+    /// Transfers the ETH to the NEAR account with `ft_transfer`.
+    pub fn eth_to_near(recipient: &str, amount: U256) -> Self {
+        let mut input = vec![FLAG_ETH];
+        input.extend_from_slice(recipient.as_bytes());
+        Self {
+            to: exit_to_near_address(),
+            value: amount,
+            input,
+        }
+    }
+
+    /// Withdraws the ETH to the Ethereum address through the connector.
+    pub fn eth_to_ethereum(recipient: Address, amount: U256) -> Self {
+        let mut input = vec![FLAG_ETH];
+        input.extend_from_slice(recipient.as_bytes());
+        Self {
+            to: exit_to_ethereum_address(),
+            value: amount,
+            input,
+        }
+    }
+
+    /// Calls `withdrawToNear` of the bridged ERC-20 token, which transfers the NEP-141
+    /// counterpart to the NEAR account.
+    pub fn erc20_to_near(token: Address, recipient: &str, amount: U256) -> Self {
+        let recipient = recipient.as_bytes();
+        let mut input = WITHDRAW_TO_NEAR_SELECTOR.to_vec();
+        input.extend_from_slice(&U256::from(64).to_big_endian());
+        input.extend_from_slice(&amount.to_big_endian());
+        input.extend_from_slice(&U256::from(recipient.len()).to_big_endian());
+        input.extend_from_slice(recipient);
+        input.resize(input.len() + (32 - recipient.len() % 32) % 32, 0);
+        Self {
+            to: token,
+            value: U256::zero(),
+            input,
+        }
+    }
+
+    /// Calls `withdrawToEthereum` of the bridged ERC-20 token, which withdraws the NEP-141
+    /// counterpart to the Ethereum address.
+    pub fn erc20_to_ethereum(token: Address, recipient: Address, amount: U256) -> Self {
+        let mut input = WITHDRAW_TO_ETHEREUM_SELECTOR.to_vec();
+        input.extend_from_slice(&[0u8; 12]);
+        input.extend_from_slice(recipient.as_bytes());
+        input.extend_from_slice(&amount.to_big_endian());
+        Self {
+            to: token,
+            value: U256::zero(),
+            input,
+        }
+    }
+
+    /// Returns the legacy transaction sending the exit, to be signed for `submit`.
+    pub fn into_transaction(self, nonce: U256) -> LegacyTransaction {
+        LegacyTransaction::new(nonce, Some(self.to), self.value, self.input)
+    }
+}
+
+/// NEP-141 transfer emitted by a receipt of the exit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FtTransfer {
+    /// Account of the NEP-141 token which executed the transfer.
+    pub token_id: AccountId,
+    pub old_owner_id: AccountId,
+    pub new_owner_id: AccountId,
+    pub amount: U128,
+    pub memo: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct FtEvent {
+    standard: String,
+    event: String,
+    data: Vec<FtTransferData>,
+}
+
+#[derive(Deserialize)]
+struct FtTransferData {
+    old_owner_id: AccountId,
+    new_owner_id: AccountId,
+    amount: U128,
+    memo: Option<String>,
+}
+
+/// Result of the EVM exit call with the NEAR receipts which released the funds.
+#[derive(Debug)]
+pub struct ExitOutcome {
+    result: ExecutionResult<SubmitResult>,
+    tree: ReceiptNode,
+}
+
+impl ExitOutcome {
+    pub fn new(result: ExecutionResult<SubmitResult>) -> Self {
+        let tree = result.receipt_tree();
+        Self { result, tree }
+    }
+
+    pub fn result(&self) -> &ExecutionResult<SubmitResult> {
+        &self.result
+    }
+
+    pub fn receipt_tree(&self) -> &ReceiptNode {
+        &self.tree
+    }
+
+    /// Returns `true` if the EVM call and all receipts spawned by it have succeeded.
+    pub fn is_success(&self) -> bool {
+        self.result.is_success()
+            && matches!(self.result.value().status, TransactionStatus::Succeed(_))
+            && self.tree.failures().is_empty()
+    }
+
+    /// NEP-141 transfers made by the receipts, decoded from the `ft_transfer` events.
+    pub fn ft_transfers(&self) -> Vec<FtTransfer> {
+        self.tree
+            .iter()
+            .filter(|node| node.is_success())
+            .flat_map(|node| {
+                node.logs
+                    .iter()
+                    .filter_map(|log| log.strip_prefix(EVENT_JSON_PREFIX))
+                    .filter_map(|json| serde_json::from_str::<FtEvent>(json).ok())
+                    .filter(|event| event.standard == "nep141" && event.event == "ft_transfer")
+                    .flat_map(|event| event.data)
+                    .map(|data| FtTransfer {
+                        token_id: node.executor_id.clone(),
+                        old_owner_id: data.old_owner_id,
+                        new_owner_id: data.new_owner_id,
+                        amount: data.amount,
+                        memo: data.memo,
+                    })
+            })
+            .collect()
+    }
+
+    /// Decodes the result of the `withdraw` receipt executed by the connector.
+    pub fn withdraw_result(&self, connector_id: &AccountId) -> anyhow::Result<WithdrawResult> {
+        self.result
+            .receipt_outcomes()
+            .iter()
+            .filter(|outcome| &outcome.executor_id == connector_id)
+            .find_map(|outcome| match outcome.clone().into_result() {
+                Ok(ValueOrReceiptId::Value(value)) => value.borsh().ok(),
+                _ => None,
+            })
+            .ok_or_else(|| anyhow::anyhow!("no withdraw result from {connector_id}"))
+    }
+}
+
+impl From<ExecutionResult<SubmitResult>> for ExitOutcome {
+    fn from(result: ExecutionResult<SubmitResult>) -> Self {
+        Self::new(result)
+    }
+}
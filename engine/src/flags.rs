@@ -0,0 +1,118 @@
+use aurora_engine_types::borsh::{BorshDeserialize, BorshSerialize};
+use std::fmt::{Display, Formatter};
+use std::ops::{BitAnd, BitOr, BitOrAssign, Not};
+
+/// Implements the set operations, the conversions from and to the raw integer and `Display`
+/// listing the names of the set flags, e.g. `EXIT_TO_NEAR | EXIT_TO_ETHEREUM`.
+macro_rules! impl_flags {
+    ($name:ident: $bits:ty { $($flag:ident = $value:expr),+ $(,)? }) => {
+        impl $name {
+            $(pub const $flag: Self = Self($value);)+
+
+            const NAMED: &'static [(&'static str, Self)] = &[$((stringify!($flag), Self::$flag)),+];
+
+            pub const fn empty() -> Self {
+                Self(0)
+            }
+
+            pub const fn all() -> Self {
+                Self(0 $(| $value)+)
+            }
+
+            /// Keeps the unknown bits, so the flags read from a newer engine are not lost.
+            pub const fn from_bits(bits: $bits) -> Self {
+                Self(bits)
+            }
+
+            pub const fn bits(self) -> $bits {
+                self.0
+            }
+
+            pub const fn is_empty(self) -> bool {
+                self.0 == 0
+            }
+
+            pub const fn contains(self, other: Self) -> bool {
+                self.0 & other.0 == other.0
+            }
+        }
+
+        impl BitOr for $name {
+            type Output = Self;
+
+            fn bitor(self, rhs: Self) -> Self {
+                Self(self.0 | rhs.0)
+            }
+        }
+
+        impl BitOrAssign for $name {
+            fn bitor_assign(&mut self, rhs: Self) {
+                self.0 |= rhs.0;
+            }
+        }
+
+        impl BitAnd for $name {
+            type Output = Self;
+
+            fn bitand(self, rhs: Self) -> Self {
+                Self(self.0 & rhs.0)
+            }
+        }
+
+        impl Not for $name {
+            type Output = Self;
+
+            fn not(self) -> Self {
+                Self(!self.0)
+            }
+        }
+
+        impl From<$bits> for $name {
+            fn from(bits: $bits) -> Self {
+                Self::from_bits(bits)
+            }
+        }
+
+        impl From<$name> for $bits {
+            fn from(flags: $name) -> Self {
+                flags.bits()
+            }
+        }
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+                if self.is_empty() {
+                    return f.write_str("NONE");
+                }
+                let mut names = Self::NAMED
+                    .iter()
+                    .filter(|(_, flag)| self.contains(*flag))
+                    .map(|(name, _)| (*name).to_string())
+                    .collect::<Vec<_>>();
+                let unknown = self.0 & !Self::all().0;
+                if unknown != 0 {
+                    names.push(format!("{unknown:#x}"));
+                }
+                f.write_str(&names.join(" | "))
+            }
+        }
+    };
+}
+
+/// Precompiles paused by `pause_precompiles`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, BorshSerialize, BorshDeserialize)]
+pub struct PrecompileFlags(u32);
+
+impl_flags!(PrecompileFlags: u32 {
+    EXIT_TO_NEAR = 1 << 0,
+    EXIT_TO_ETHEREUM = 1 << 1,
+});
+
+/// Connector methods of the engine paused by `set_paused_flags`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, BorshSerialize, BorshDeserialize)]
+pub struct EnginePausedFlags(u8);
+
+impl_flags!(EnginePausedFlags: u8 {
+    PAUSE_DEPOSIT = 1 << 0,
+    PAUSE_WITHDRAW = 1 << 1,
+});
@@ -0,0 +1,182 @@
+use crate::EngineContract;
+use aurora_engine_types::storage::KeyPrefix;
+use aurora_engine_types::types::Address;
+use near_sdk::json_types::Base64VecU8;
+use near_workspaces::network::Sandbox;
+use near_workspaces::types::{AccessKey, AccountDetailsPatch, KeyType, NearToken, SecretKey};
+use near_workspaces::{AccountId, Contract, Worker};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+const ENGINE_ACCOUNT_ID: &str = "aurora";
+const ENGINE_BALANCE: NearToken = NearToken::from_near(1_000);
+/// Prefixes of the keys which start with the EVM address right after the prefix.
+const ADDRESS_PREFIXES: &[KeyPrefix] = &[
+    KeyPrefix::Nonce,
+    KeyPrefix::Balance,
+    KeyPrefix::Code,
+    KeyPrefix::Storage,
+    KeyPrefix::Generation,
+];
+
+/// Key-value pair of the contract storage as returned by the `view_state` RPC method.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateItem {
+    pub key: Base64VecU8,
+    pub value: Base64VecU8,
+}
+
+impl StateItem {
+    /// Returns the engine key prefix, which follows the version byte.
+    pub fn prefix(&self) -> Option<u8> {
+        self.key.0.get(1).copied()
+    }
+
+    /// Returns the EVM address of the account-related keys.
+    pub fn address(&self) -> Option<Address> {
+        let prefix = self.prefix()?;
+        if !ADDRESS_PREFIXES.iter().any(|p| u8::from(*p) == prefix) {
+            return None;
+        }
+        self.key.0.get(2..22).map(|bytes| {
+            let mut address = [0u8; 20];
+            address.copy_from_slice(bytes);
+            Address::from_array(address)
+        })
+    }
+}
+
+/// Either the whole `view_state` response or the list of its values.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StateDump {
+    View { values: Vec<StateItem> },
+    Items(Vec<StateItem>),
+}
+
+/// Patches exported engine code and state into the sandbox under the original account id.
+#[derive(Debug)]
+pub struct EngineForkBuilder {
+    account_id: AccountId,
+    code: Option<Vec<u8>>,
+    state: Vec<StateItem>,
+    balance: NearToken,
+    prefixes: Option<Vec<u8>>,
+    addresses: Option<Vec<Address>>,
+}
+
+impl EngineForkBuilder {
+    pub fn new() -> anyhow::Result<Self> {
+        Ok(Self {
+            account_id: ENGINE_ACCOUNT_ID.parse()?,
+            code: None,
+            state: Vec::new(),
+            balance: ENGINE_BALANCE,
+            prefixes: None,
+            addresses: None,
+        })
+    }
+
+    pub fn with_account_id(mut self, account_id: &str) -> anyhow::Result<Self> {
+        self.account_id = account_id.parse()?;
+        Ok(self)
+    }
+
+    pub fn with_code(mut self, code: Vec<u8>) -> Self {
+        self.code = Some(code);
+        self
+    }
+
+    pub fn with_code_file<P: AsRef<Path>>(self, path: P) -> anyhow::Result<Self> {
+        let code = std::fs::read(path.as_ref()).map_err(|e| {
+            anyhow::anyhow!("failed read code file {}: {e}", path.as_ref().display())
+        })?;
+        Ok(self.with_code(code))
+    }
+
+    pub fn with_state(mut self, state: Vec<StateItem>) -> Self {
+        self.state.extend(state);
+        self
+    }
+
+    /// Loads the state dump, either the `view_state` response or the array of its values.
+    pub fn with_state_file<P: AsRef<Path>>(self, path: P) -> anyhow::Result<Self> {
+        let data = std::fs::read(path.as_ref()).map_err(|e| {
+            anyhow::anyhow!("failed read state file {}: {e}", path.as_ref().display())
+        })?;
+        let state = match serde_json::from_slice(&data)? {
+            StateDump::View { values } | StateDump::Items(values) => values,
+        };
+        Ok(self.with_state(state))
+    }
+
+    pub fn with_balance(mut self, balance: NearToken) -> Self {
+        self.balance = balance;
+        self
+    }
+
+    /// Keeps only the keys with the given prefixes.
+    pub fn with_prefixes(mut self, prefixes: &[KeyPrefix]) -> Self {
+        self.prefixes = Some(prefixes.iter().map(|p| u8::from(*p)).collect());
+        self
+    }
+
+    /// Keeps only the accounts, code and storage of the given EVM addresses.
+    /// Keys which don't belong to an EVM address are kept.
+    pub fn with_addresses(mut self, addresses: &[Address]) -> Self {
+        self.addresses = Some(addresses.to_vec());
+        self
+    }
+
+    /// Returns the state which will be patched into the sandbox.
+    pub fn filtered_state(&self) -> Vec<&StateItem> {
+        self.state
+            .iter()
+            .filter(|item| {
+                self.prefixes.as_ref().map_or(true, |prefixes| {
+                    item.prefix().is_some_and(|p| prefixes.contains(&p))
+                })
+            })
+            .filter(|item| {
+                self.addresses.as_ref().map_or(true, |addresses| {
+                    item.address().map_or(true, |a| addresses.contains(&a))
+                })
+            })
+            .collect()
+    }
+
+    pub async fn deploy(self) -> anyhow::Result<EngineContract> {
+        let worker = near_workspaces::sandbox()
+            .await
+            .map_err(|err| anyhow::anyhow!("Failed init sandbox: {:?}", err))?;
+        self.deploy_to(&worker).await
+    }
+
+    /// Patches the code and state into the given sandbox, e.g. to inspect the patched
+    /// state with `view_state` or to deploy the engine next to other contracts.
+    pub async fn deploy_to(self, worker: &Worker<Sandbox>) -> anyhow::Result<EngineContract> {
+        let code = self.code.as_ref().expect("WASM wasn't set");
+        let sk = SecretKey::from_random(KeyType::ED25519);
+        let state = self.filtered_state();
+
+        worker
+            .patch(&self.account_id)
+            .account(AccountDetailsPatch::default().balance(self.balance))
+            .access_key(sk.public_key(), AccessKey::full_access())
+            .code(code)
+            .states(
+                state
+                    .iter()
+                    .map(|item| (item.key.0.as_slice(), item.value.0.as_slice())),
+            )
+            .transact()
+            .await
+            .map_err(|e| anyhow::anyhow!("error while patching state: {e}"))?;
+
+        let contract = Contract::from_secret_key(self.account_id, sk, worker);
+        Ok(EngineContract::new_from_contract(
+            contract.into(),
+            worker.root_account()?,
+        ))
+    }
+}
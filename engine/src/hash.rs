@@ -0,0 +1,7 @@
+use aurora_engine_types::H256;
+use sha3::{Digest, Keccak256};
+
+/// Keccak-256 hash of the data, as computed by the EVM.
+pub fn keccak(data: &[u8]) -> H256 {
+    H256::from_slice(&Keccak256::digest(data))
+}
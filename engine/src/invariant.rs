@@ -0,0 +1,347 @@
+use crate::operation::{ViewFtBalanceOf, ViewFtTotalSupply};
+use crate::types::{to_aurora_account_id, to_near_account_id, AccountId, Address};
+use crate::{ContractId, EngineContract};
+use aurora_engine_types::U256;
+use aurora_workspace_utils::Contract;
+use serde_json::json;
+use std::fmt::{Display, Formatter};
+
+/// Checks that the supplies of ETH declared by the engine and the eth-connector match
+/// the balances of the known accounts and addresses, e.g. after every step of the randomized
+/// tests.
+///
+/// The sums of the balances are compared with the supplies, so the known accounts and
+/// addresses must include all the holders of ETH.
+#[derive(Debug, Clone)]
+pub struct InvariantChecker {
+    engine: EngineContract,
+    connector: Option<Contract>,
+    accounts: Vec<AccountId>,
+    addresses: Vec<Address>,
+}
+
+impl InvariantChecker {
+    pub fn new(engine: &EngineContract) -> Self {
+        Self {
+            // The snapshots are views of the checker, so they aren't recorded in the trace.
+            engine: engine.with_recorder(None),
+            connector: None,
+            accounts: vec![],
+            addresses: vec![],
+        }
+    }
+
+    /// Checks the engine against the eth-connector deployed to the account.
+    pub fn connector(mut self, connector_id: &AccountId) -> Self {
+        let connector = self
+            .engine
+            .as_contract()
+            .with_contract_id(to_near_account_id(connector_id));
+        self.connector = Some(connector);
+        self
+    }
+
+    pub fn account(mut self, account_id: AccountId) -> Self {
+        self.accounts.push(account_id);
+        self
+    }
+
+    pub fn address(mut self, address: Address) -> Self {
+        self.addresses.push(address);
+        self
+    }
+
+    pub async fn check(&self) -> anyhow::Result<InvariantReport> {
+        Ok(self.snapshot().await?.check())
+    }
+
+    pub async fn snapshot(&self) -> anyhow::Result<SupplySnapshot> {
+        let engine = &self.engine;
+        let mut snapshot = SupplySnapshot {
+            engine_id: to_aurora_account_id(engine.id()),
+            ft_total_supply: engine.ft_total_supply().await?.result.0,
+            ft_total_eth_supply_on_near: engine.ft_total_eth_supply_on_near().await?.result.0,
+            ft_total_eth_supply_on_aurora: engine.ft_total_eth_supply_on_aurora().await?.result.0,
+            ft_balances: vec![],
+            eth_balances: vec![],
+            connector: None,
+        };
+
+        for account_id in &self.accounts {
+            let balance = engine
+                .ft_balance_of(account_id)
+                .await
+                .map_err(|e| anyhow::anyhow!("error while reading balance of {account_id}: {e}"))?
+                .result
+                .0;
+            snapshot.ft_balances.push((account_id.clone(), balance));
+        }
+        for address in &self.addresses {
+            let error = |e: anyhow::Error| {
+                anyhow::anyhow!("error while reading balance of 0x{}: {e}", address.encode())
+            };
+            let ft_balance_of_eth = engine.ft_balance_of_eth(*address).await.map_err(error)?;
+            let balance = engine.get_balance(*address).await.map_err(error)?;
+            snapshot.eth_balances.push(EthBalance {
+                address: *address,
+                ft_balance_of_eth: ft_balance_of_eth.result.0,
+                balance: balance.result,
+            });
+        }
+
+        if let Some(connector) = &self.connector {
+            let mut connector_snapshot = ConnectorSnapshot {
+                ft_total_supply: ViewFtTotalSupply::view(connector).await?.result.0,
+                ft_balances: vec![],
+            };
+            // The engine account holds the ETH locked on Aurora.
+            let engine_id = &snapshot.engine_id;
+            let engine_account = Some(engine_id).filter(|id| !self.accounts.contains(id));
+            for account_id in self.accounts.iter().chain(engine_account) {
+                let balance = ViewFtBalanceOf::view(connector)
+                    .args_json(json!({ "account_id": account_id }))
+                    .await
+                    .map_err(|e| {
+                        anyhow::anyhow!(
+                            "error while reading connector balance of {account_id}: {e}"
+                        )
+                    })?
+                    .result
+                    .0;
+                connector_snapshot
+                    .ft_balances
+                    .push((account_id.clone(), balance));
+            }
+            snapshot.connector = Some(connector_snapshot);
+        }
+
+        Ok(snapshot)
+    }
+}
+
+/// Supplies and balances read by `InvariantChecker`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SupplySnapshot {
+    pub engine_id: AccountId,
+    pub ft_total_supply: u128,
+    pub ft_total_eth_supply_on_near: u128,
+    pub ft_total_eth_supply_on_aurora: u128,
+    /// `ft_balance_of` of the known accounts.
+    pub ft_balances: Vec<(AccountId, u128)>,
+    pub eth_balances: Vec<EthBalance>,
+    pub connector: Option<ConnectorSnapshot>,
+}
+
+/// ETH balance of the address reported by `ft_balance_of_eth` and `get_balance`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EthBalance {
+    pub address: Address,
+    pub ft_balance_of_eth: u128,
+    pub balance: U256,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConnectorSnapshot {
+    pub ft_total_supply: u128,
+    /// `ft_balance_of` of the known accounts and the engine.
+    pub ft_balances: Vec<(AccountId, u128)>,
+}
+
+impl SupplySnapshot {
+    pub fn check(&self) -> InvariantReport {
+        let mut violations = vec![];
+        let mut check = |invariant, expected: U256, actual: U256, offenders: Vec<String>| {
+            if expected != actual {
+                violations.push(Violation {
+                    invariant,
+                    expected,
+                    actual,
+                    offenders,
+                });
+            }
+        };
+
+        check(
+            Invariant::EngineSupplyOnNear,
+            self.ft_total_eth_supply_on_near.into(),
+            self.ft_total_supply.into(),
+            vec![],
+        );
+        check(
+            Invariant::EngineBalances,
+            self.ft_total_supply.into(),
+            sum(self
+                .ft_balances
+                .iter()
+                .map(|(_, balance)| U256::from(*balance))),
+            holders(&self.ft_balances),
+        );
+        check(
+            Invariant::EthBalances,
+            self.ft_total_eth_supply_on_aurora.into(),
+            sum(self.eth_balances.iter().map(|eth| eth.balance)),
+            self.eth_balances
+                .iter()
+                .filter(|eth| !eth.balance.is_zero())
+                .map(|eth| format!("0x{}: {}", eth.address.encode(), eth.balance))
+                .collect(),
+        );
+        for eth in &self.eth_balances {
+            check(
+                Invariant::EthBalanceViews,
+                eth.balance,
+                eth.ft_balance_of_eth.into(),
+                vec![format!("0x{}", eth.address.encode())],
+            );
+        }
+
+        if let Some(connector) = &self.connector {
+            check(
+                Invariant::ConnectorBalances,
+                connector.ft_total_supply.into(),
+                sum(connector
+                    .ft_balances
+                    .iter()
+                    .map(|(_, balance)| U256::from(*balance))),
+                holders(&connector.ft_balances),
+            );
+            check(
+                Invariant::BridgedSupply,
+                connector.ft_total_supply.into(),
+                self.ft_total_supply.into(),
+                vec![],
+            );
+            for (account_id, balance) in &self.ft_balances {
+                let connector_balance = connector_balance(connector, account_id);
+                check(
+                    Invariant::BridgedBalances,
+                    connector_balance.into(),
+                    (*balance).into(),
+                    vec![account_id.to_string()],
+                );
+            }
+            check(
+                Invariant::LockedEth,
+                self.ft_total_eth_supply_on_aurora.into(),
+                connector_balance(connector, &self.engine_id).into(),
+                vec![self.engine_id.to_string()],
+            );
+        }
+
+        InvariantReport { violations }
+    }
+}
+
+fn sum(balances: impl Iterator<Item = U256>) -> U256 {
+    balances.fold(U256::zero(), |sum, balance| sum.saturating_add(balance))
+}
+
+fn holders(balances: &[(AccountId, u128)]) -> Vec<String> {
+    balances
+        .iter()
+        .filter(|(_, balance)| *balance != 0)
+        .map(|(account_id, balance)| format!("{account_id}: {balance}"))
+        .collect()
+}
+
+fn connector_balance(connector: &ConnectorSnapshot, account_id: &AccountId) -> u128 {
+    connector
+        .ft_balances
+        .iter()
+        .find_map(|(id, balance)| (id == account_id).then_some(*balance))
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Invariant {
+    /// The engine `ft_total_supply` equals `ft_total_eth_supply_on_near`.
+    EngineSupplyOnNear,
+    /// The engine balances of the known accounts sum up to `ft_total_supply`.
+    EngineBalances,
+    /// The ETH balances of the known addresses sum up to `ft_total_eth_supply_on_aurora`.
+    EthBalances,
+    /// `ft_balance_of_eth` of the address equals its `get_balance`.
+    EthBalanceViews,
+    /// The connector balances of the known accounts and the engine sum up to its
+    /// `ft_total_supply`.
+    ConnectorBalances,
+    /// The engine and the connector report the same `ft_total_supply`.
+    BridgedSupply,
+    /// The engine and the connector report the same balance of the account.
+    BridgedBalances,
+    /// The connector balance of the engine equals `ft_total_eth_supply_on_aurora`.
+    LockedEth,
+}
+
+impl Display for Invariant {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let description = match self {
+            Self::EngineSupplyOnNear => "engine ft_total_supply != ft_total_eth_supply_on_near",
+            Self::EngineBalances => "engine balances don't sum up to ft_total_supply",
+            Self::EthBalances => "EVM balances don't sum up to ft_total_eth_supply_on_aurora",
+            Self::EthBalanceViews => "ft_balance_of_eth != get_balance",
+            Self::ConnectorBalances => "connector balances don't sum up to ft_total_supply",
+            Self::BridgedSupply => "engine and connector ft_total_supply differ",
+            Self::BridgedBalances => "engine and connector balances differ",
+            Self::LockedEth => "connector balance of engine != ft_total_eth_supply_on_aurora",
+        };
+        f.write_str(description)
+    }
+}
+
+/// Broken invariant with the values which should be equal and the accounts holding
+/// the balances, or the one whose balances differ.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    pub invariant: Invariant,
+    pub expected: U256,
+    pub actual: U256,
+    pub offenders: Vec<String>,
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: expected {}, got {}",
+            self.invariant, self.expected, self.actual
+        )?;
+        if !self.offenders.is_empty() {
+            write!(f, " [{}]", self.offenders.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InvariantReport {
+    pub violations: Vec<Violation>,
+}
+
+impl InvariantReport {
+    pub fn is_ok(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    pub fn violations_of(&self, invariant: Invariant) -> Vec<&Violation> {
+        self.violations
+            .iter()
+            .filter(|violation| violation.invariant == invariant)
+            .collect()
+    }
+
+    /// Panics with the list of the violations, if any.
+    #[track_caller]
+    pub fn assert_ok(&self) {
+        assert!(self.is_ok(), "supply invariants are broken:\n{self}");
+    }
+}
+
+impl Display for InvariantReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for violation in &self.violations {
+            writeln!(f, "{violation}")?;
+        }
+        Ok(())
+    }
+}
@@ -1,21 +1,52 @@
 use aurora_engine_types::parameters::connector::FungibleTokenMetadata;
 use aurora_engine_types::types::address::Address;
 use aurora_engine_types::U256;
+use aurora_workspace_utils::account_id::to_aurora_account_id;
+#[cfg(feature = "local")]
+use aurora_workspace_utils::local::{LocalAccount, LocalContract, LocalRuntime};
 use aurora_workspace_utils::Contract;
 use near_workspaces::types::NearToken;
 use near_workspaces::{Account, AccountId};
 
 pub use aurora_workspace_utils::ContractId;
 pub use contract::EngineContract;
+pub use fork::EngineForkBuilder;
 
+pub mod address;
+pub mod artifact;
+pub mod balance;
 pub mod contract;
+pub mod diff;
+pub mod estimate;
+pub mod exit;
+pub mod flags;
+pub mod fork;
+pub mod hash;
+pub mod invariant;
+pub mod message;
+#[cfg(feature = "mock")]
+pub mod mock;
 pub mod operation;
+pub mod relayer;
+pub mod signer;
+pub mod store;
+pub mod xcc;
 
 pub mod types {
+    pub use crate::address::{
+        create_address, near_account_to_evm_address, parse_address, to_checksum_address,
+        EvmRecipient,
+    };
+    pub use crate::flags::{EnginePausedFlags, PrecompileFlags};
+    pub use crate::message::{FtTransferMessage, RelayerFee};
     pub use aurora_engine_types::account_id::AccountId;
     pub use aurora_engine_types::parameters::connector::Proof;
     pub use aurora_engine_types::parameters::engine::{SubmitResult, TransactionStatus};
+    pub use aurora_engine_types::parameters::xcc::FundXccArgs;
     pub use aurora_engine_types::types::Address;
+    pub use aurora_workspace_utils::account_id::{to_aurora_account_id, to_near_account_id};
+    #[cfg(feature = "local")]
+    pub use aurora_workspace_utils::local::{LocalAccount, LocalContract, LocalRuntime};
     pub use aurora_workspace_utils::Contract;
     pub use near_workspaces::result::ExecutionOutcome;
     pub use near_workspaces::types::KeyType;
@@ -106,15 +137,32 @@ impl EngineContractBuilder {
         self
     }
 
-    pub async fn deploy_and_init(self) -> anyhow::Result<EngineContract> {
+    pub async fn deploy_and_init(mut self) -> anyhow::Result<EngineContract> {
         let (owner_acc, root_acc) = self.create_accounts(&self.owner_id).await?;
-        let contract = Contract::deploy(&owner_acc, self.code.expect("WASM wasn't set")).await?;
+        let code = self.code.take().expect("WASM wasn't set");
+        let contract = Contract::deploy(&owner_acc, code).await?;
         let contract = EngineContract::new_from_contract(contract, root_acc);
+        self.init(contract).await
+    }
+
+    /// Deploys and initializes the contract in the in-process runtime instead of the sandbox.
+    #[cfg(feature = "local")]
+    pub async fn deploy_and_init_local(
+        mut self,
+        runtime: &LocalRuntime,
+    ) -> anyhow::Result<EngineContract<LocalContract>> {
+        let (owner_acc, root_acc) = self.create_local_accounts(runtime)?;
+        let code = self.code.take().expect("WASM wasn't set");
+        let contract = LocalContract::deploy(&owner_acc, code)?;
+        let contract = EngineContract::new_local(contract, root_acc);
+        self.init_local(contract)
+    }
 
+    async fn init(self, contract: EngineContract) -> anyhow::Result<EngineContract> {
         contract
             .new(
                 self.chain_id,
-                self.owner_id.as_str().parse().unwrap(),
+                to_aurora_account_id(&self.owner_id),
                 self.upgrade_delay_blocks,
             )
             .transact()
@@ -123,7 +171,7 @@ impl EngineContractBuilder {
 
         contract
             .new_eth_connector(
-                self.prover_id.as_str().parse().unwrap(),
+                to_aurora_account_id(&self.prover_id),
                 self.custodian_address.encode(),
                 self.ft_metadata,
             )
@@ -134,6 +182,50 @@ impl EngineContractBuilder {
         Ok(contract)
     }
 
+    #[cfg(feature = "local")]
+    fn init_local(
+        self,
+        contract: EngineContract<LocalContract>,
+    ) -> anyhow::Result<EngineContract<LocalContract>> {
+        contract
+            .new(
+                self.chain_id,
+                to_aurora_account_id(&self.owner_id),
+                self.upgrade_delay_blocks,
+            )
+            .transact_local()
+            .map_err(|e| anyhow::anyhow!("error while initialize contract: {e}"))?;
+
+        contract
+            .new_eth_connector(
+                to_aurora_account_id(&self.prover_id),
+                self.custodian_address.encode(),
+                self.ft_metadata,
+            )
+            .transact_local()
+            .map_err(|e| anyhow::anyhow!("error while initialize eth connector: {e}"))?;
+
+        Ok(contract)
+    }
+
+    #[cfg(feature = "local")]
+    fn create_local_accounts(
+        &self,
+        runtime: &LocalRuntime,
+    ) -> anyhow::Result<(LocalAccount, LocalAccount)> {
+        match self.owner_id.as_str().rsplit_once('.') {
+            Some((sub, root)) => {
+                let root = runtime.create_account(root, self.root_balance)?;
+                let owner = root.create_subaccount(sub, self.contract_balance)?;
+                Ok((owner, root))
+            }
+            None => {
+                let root = runtime.create_account(self.owner_id.as_str(), self.root_balance)?;
+                Ok((root.clone(), root))
+            }
+        }
+    }
+
     async fn create_accounts(&self, account_id: &AccountId) -> anyhow::Result<(Account, Account)> {
         let account_id_str = account_id.as_str();
         let (sub, root) = match account_id_str.rsplit_once('.') {
@@ -0,0 +1,99 @@
+use crate::address::parse_address;
+use crate::types::{AccountId, Address};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// Separator of the relayer account id and the rest of the message.
+const RELAYER_SEPARATOR: char = ':';
+/// Length of the fee encoded as the little-endian `U256`.
+const FEE_LEN: usize = 32;
+
+/// Message of `ft_transfer_call` to the engine, which deposits the tokens to the EVM.
+///
+/// The bridged NEP-141 tokens are deposited by the message with the hex encoded recipient
+/// address. The deposits of ETH may pay the fee to the relayer which has submitted them,
+/// in the `<relayer>:<fee><recipient>` format: the fee is the hex encoded little-endian
+/// `U256` followed by the hex encoded address. The fee is credited to the EVM address
+/// of the relayer account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FtTransferMessage {
+    pub recipient: Address,
+    pub relayer_fee: Option<RelayerFee>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelayerFee {
+    pub relayer_id: AccountId,
+    pub fee: u128,
+}
+
+impl FtTransferMessage {
+    pub fn new(recipient: Address) -> Self {
+        Self {
+            recipient,
+            relayer_fee: None,
+        }
+    }
+
+    /// Creates the message of the ETH deposit which pays the fee to the relayer.
+    pub fn with_fee(recipient: Address, relayer_id: AccountId, fee: u128) -> Self {
+        Self {
+            recipient,
+            relayer_fee: Some(RelayerFee { relayer_id, fee }),
+        }
+    }
+
+    /// Returns the fee paid to the relayer, zero if the message has no relayer.
+    pub fn fee(&self) -> u128 {
+        self.relayer_fee.as_ref().map_or(0, |relayer| relayer.fee)
+    }
+}
+
+impl Display for FtTransferMessage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.relayer_fee {
+            None => write!(f, "{}", self.recipient.encode()),
+            Some(RelayerFee { relayer_id, fee }) => {
+                let mut raw_fee = [0u8; FEE_LEN];
+                raw_fee[..16].copy_from_slice(&fee.to_le_bytes());
+                write!(
+                    f,
+                    "{relayer_id}{RELAYER_SEPARATOR}{}{}",
+                    hex::encode(raw_fee),
+                    self.recipient.encode()
+                )
+            }
+        }
+    }
+}
+
+impl FromStr for FtTransferMessage {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (relayer_id, data) = match s.split_once(RELAYER_SEPARATOR) {
+            Some(parts) => parts,
+            None => return Ok(Self::new(parse_address(s)?)),
+        };
+        let relayer_id = relayer_id
+            .parse()
+            .map_err(|e| anyhow::anyhow!("invalid relayer account id `{relayer_id}`: {e:?}"))?;
+        let data = hex::decode(data).map_err(|e| anyhow::anyhow!("invalid message `{s}`: {e}"))?;
+        if data.len() != FEE_LEN + 20 {
+            anyhow::bail!("invalid length of the fee and the recipient in message `{s}`");
+        }
+        if data[16..FEE_LEN].iter().any(|b| *b != 0) {
+            anyhow::bail!("fee overflows u128 in message `{s}`");
+        }
+
+        let mut fee = [0u8; 16];
+        fee.copy_from_slice(&data[..16]);
+        let mut recipient = [0u8; 20];
+        recipient.copy_from_slice(&data[FEE_LEN..]);
+        Ok(Self::with_fee(
+            Address::from_array(recipient),
+            relayer_id,
+            u128::from_le_bytes(fee),
+        ))
+    }
+}
@@ -0,0 +1,52 @@
+//! Methods of the mock engine in `res/mock_engine`, which don't exist in the real engine.
+use crate::contract::EngineContract;
+use aurora_engine_types::borsh::{self, BorshDeserialize, BorshSerialize};
+use aurora_workspace_utils::results::ExecutionResult;
+use aurora_workspace_utils::transactions::CallTransaction;
+use aurora_workspace_utils::{impl_call_return, ContractBackend, ContractId};
+use near_workspaces::types::{Gas, NearToken};
+
+impl_call_return![
+    (CallSetFault, Call::SetFault),
+    (CallClearFault, Call::ClearFault),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Call {
+    SetFault,
+    ClearFault,
+}
+
+impl AsRef<str> for Call {
+    fn as_ref(&self) -> &str {
+        match self {
+            Call::SetFault => "set_fault",
+            Call::ClearFault => "clear_fault",
+        }
+    }
+}
+
+/// Fault injected into a method of the mock engine by `set_fault`.
+#[derive(Debug, Clone, PartialEq, Eq, BorshDeserialize, BorshSerialize)]
+pub enum Fault {
+    /// The method panics with the given error, e.g. `ERR_NOT_ALLOWED`.
+    Panic(String),
+    /// The EVM execution reverts with the given output. Methods which don't return
+    /// `SubmitResult` panic with `ERR_REVERT`.
+    Revert(Vec<u8>),
+    /// The EVM execution runs out of gas. Methods which don't return `SubmitResult`
+    /// panic with `ERR_OUT_OF_GAS`.
+    OutOfGas,
+    /// The method burns the given amount of NEAR gas before executing.
+    BurnGas(u64),
+}
+impl<C: ContractBackend> EngineContract<C> {
+    /// Makes the method of the mock engine fail in the way described by the fault.
+    pub fn set_fault(&self, method: &str, fault: Fault) -> CallSetFault {
+        CallSetFault::call(self.as_contract()).args_borsh((method.to_string(), fault))
+    }
+
+    pub fn clear_fault(&self, method: &str) -> CallClearFault {
+        CallClearFault::call(self.as_contract()).args_borsh(method.to_string())
+    }
+}
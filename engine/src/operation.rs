@@ -1,3 +1,7 @@
+use crate::balance::FtStorageBalance;
+#[cfg(feature = "local")]
+use crate::estimate::GasEstimate;
+use crate::flags::{EnginePausedFlags, PrecompileFlags};
 use aurora_engine_types::account_id::AccountId;
 use aurora_engine_types::parameters::connector::{FungibleTokenMetadata, WithdrawResult};
 use aurora_engine_types::parameters::engine::{StorageBalance, SubmitResult, TransactionStatus};
@@ -24,6 +28,9 @@ impl_call_return![
         Call::FactoryUpdateAddressVersion
     ),
     (CallRegisterRelayer, Call::RegisterRelayer),
+    (CallSetKeyManager, Call::SetKeyManager),
+    (CallAddRelayerKey, Call::AddRelayerKey),
+    (CallRemoveRelayerKey, Call::RemoveRelayerKey),
     (CallRefundOnError, Call::RefundOnError),
     (CallFactoryUpdate, Call::FactoryUpdate),
     (CallFundXccSubAccount, Call::FundXccSubAccount),
@@ -43,10 +50,10 @@ impl_call_return![
     (CallStorageUnregister => bool, Call::StorageUnregister, json),
     (CallStorageWithdraw => StorageBalance, Call::StorageWithdraw, json),
     (CallWithdraw => WithdrawResult, Call::Withdraw, borsh),
-    (CallDeployCode => SubmitResult, Call::DeployCode, borsh),
+    (CallDeployCode => SubmitResult, Call::DeployCode, borsh_submit),
     (CallDeployErc20Token => Address, Call::DeployErc20Token, borsh_address),
-    (CallCall => SubmitResult, Call::Call, borsh),
-    (CallSubmit => SubmitResult, Call::Submit, borsh),
+    (CallCall => SubmitResult, Call::Call, borsh_submit),
+    (CallSubmit => SubmitResult, Call::Submit, borsh_submit),
     (CallFtOnTransfer => U128, Call::FtOnTransfer, json),
 ];
 
@@ -54,13 +61,14 @@ impl_view_return![
     (ViewFtTotalSupply => U128, View::FtTotalSupply, json),
     (ViewFtBalanceOf => U128, View::FtBalanceOf, json),
     (ViewStorageBalanceOf => StorageBalance, View::StorageBalanceOf, json),
+    (ViewNep141StorageBalanceOf => Option<FtStorageBalance>, View::StorageBalanceOf, json),
     (ViewFtMetadata => FungibleTokenMetadata, View::FtMetadata, json),
     (ViewVersion => String, View::Version, borsh),
     (ViewOwner => AccountId, View::Owner, borsh),
     (ViewBridgeProver => AccountId, View::BridgeProver, borsh),
     (ViewChainId => U256, View::ChainId, borsh_U256),
     (ViewUpgradeIndex => u64, View::UpgradeIndex, borsh),
-    (ViewPausedPrecompiles => u32, View::PausedPrecompiles, borsh),
+    (ViewPausedPrecompiles => PrecompileFlags, View::PausedPrecompiles, borsh),
     (ViewBlockHash => H256, View::BlockHash, borsh_H256),
     (ViewCode => Vec<u8>, View::Code, vec),
     (ViewBalance => U256, View::Balance, borsh_U256),
@@ -73,10 +81,40 @@ impl_view_return![
     (ViewFtBalanceOfEth => U128, View::FtBalanceOfEth, json),
     (ViewErc20FromNep141 => Address, View::Erc20FromNep141, borsh),
     (ViewNep141FromErc20 => AccountId, View::Nep141FromErc20, borsh),
-    (ViewPausedFlags => u8, View::PausedFlags, borsh),
+    (ViewPausedFlags => EnginePausedFlags, View::PausedFlags, borsh),
     (ViewAccountsCounter => u64, View::AccountsCounter, borsh)
 ];
 
+#[cfg(feature = "local")]
+impl CallCall {
+    /// Estimates the gas of the call by its simulation, which doesn't send a transaction.
+    /// See [`GasEstimate::from_simulation`].
+    pub async fn estimate_gas(&self) -> anyhow::Result<GasEstimate> {
+        GasEstimate::from_simulation(&self.0.simulate().await?)
+    }
+
+    /// Attaches the NEAR gas estimated by [`CallCall::estimate_gas`].
+    pub async fn auto_gas(self) -> anyhow::Result<Self> {
+        let estimate = self.estimate_gas().await?;
+        Ok(self.gas(estimate.near_gas))
+    }
+}
+
+#[cfg(feature = "local")]
+impl CallSubmit {
+    /// Estimates the gas of the signed transaction by its simulation, which doesn't send
+    /// a transaction. See [`GasEstimate::from_simulation`].
+    pub async fn estimate_gas(&self) -> anyhow::Result<GasEstimate> {
+        GasEstimate::from_simulation(&self.0.simulate().await?)
+    }
+
+    /// Attaches the NEAR gas estimated by [`CallSubmit::estimate_gas`].
+    pub async fn auto_gas(self) -> anyhow::Result<Self> {
+        let estimate = self.estimate_gas().await?;
+        Ok(self.gas(estimate.near_gas))
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[allow(clippy::enum_variant_names)]
 pub(crate) enum Call {
@@ -87,6 +125,9 @@ pub(crate) enum Call {
     Call,
     Submit,
     RegisterRelayer,
+    SetKeyManager,
+    AddRelayerKey,
+    RemoveRelayerKey,
     FtOnTransfer,
     Withdraw,
     Deposit,
@@ -120,6 +161,9 @@ impl AsRef<str> for Call {
             Call::Call => "call",
             Call::Submit => "submit",
             Call::RegisterRelayer => "register_relayer",
+            Call::SetKeyManager => "set_key_manager",
+            Call::AddRelayerKey => "add_relayer_key",
+            Call::RemoveRelayerKey => "remove_relayer_key",
             Call::FtOnTransfer => "ft_on_transfer",
             Call::Withdraw => "withdraw",
             Call::Deposit => "deposit",
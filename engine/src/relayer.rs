@@ -0,0 +1,118 @@
+use crate::types::to_aurora_account_id;
+use crate::{ContractId, EngineContract};
+use near_workspaces::types::{KeyType, NearToken, PublicKey, SecretKey};
+use near_workspaces::Account;
+
+/// Allowance of the relayer keys added by `RelayerHelper::add_key`.
+const DEFAULT_KEY_ALLOWANCE: NearToken = NearToken::from_millinear(250);
+
+/// Drives the relayer key workflow of the engine: the owner sets the key manager, which adds
+/// function-call access keys to the engine account for the relayers to submit transactions.
+#[derive(Debug, Clone)]
+pub struct RelayerHelper {
+    engine: EngineContract,
+}
+
+impl RelayerHelper {
+    pub fn new(engine: &EngineContract) -> Self {
+        Self {
+            engine: engine.clone(),
+        }
+    }
+
+    /// Sets the key manager, signed by the owner of the engine.
+    pub async fn set_key_manager(&self, key_manager: &Account) -> anyhow::Result<()> {
+        self.engine
+            .set_key_manager(Some(to_aurora_account_id(key_manager.id())))
+            .transact()
+            .await
+            .map_err(|e| anyhow::anyhow!("error while setting key manager: {e}"))?;
+        Ok(())
+    }
+
+    /// Generates a key pair and adds it as the relayer key with the default allowance.
+    pub async fn add_key(&self, key_manager: &Account) -> anyhow::Result<RelayerKey> {
+        self.add_key_with_allowance(key_manager, DEFAULT_KEY_ALLOWANCE)
+            .await
+    }
+
+    pub async fn add_key_with_allowance(
+        &self,
+        key_manager: &Account,
+        allowance: NearToken,
+    ) -> anyhow::Result<RelayerKey> {
+        let secret_key = SecretKey::from_random(KeyType::ED25519);
+        self.engine
+            .as_account(key_manager)
+            .add_relayer_key(&secret_key.public_key())
+            .deposit(allowance)
+            .max_gas()
+            .transact()
+            .await
+            .map_err(|e| anyhow::anyhow!("error while adding relayer key: {e}"))?;
+
+        let mut account = self.engine_account()?;
+        account.set_secret_key(secret_key);
+        Ok(RelayerKey {
+            engine: self.engine.as_account(&account),
+            account,
+        })
+    }
+
+    pub async fn remove_key(&self, key_manager: &Account, key: &RelayerKey) -> anyhow::Result<()> {
+        self.engine
+            .as_account(key_manager)
+            .remove_relayer_key(&key.public_key())
+            .max_gas()
+            .transact()
+            .await
+            .map_err(|e| anyhow::anyhow!("error while removing relayer key: {e}"))?;
+        Ok(())
+    }
+
+    /// Returns `true` if the key is still an access key of the engine account.
+    pub async fn has_key(&self, key: &RelayerKey) -> anyhow::Result<bool> {
+        let public_key = key.public_key();
+        let keys = self.engine_account()?.view_access_keys().await?;
+        Ok(keys.iter().any(|info| info.public_key == public_key))
+    }
+
+    /// The relayer keys belong to the engine account, so its signer is reused with another key.
+    fn engine_account(&self) -> anyhow::Result<Account> {
+        let signer = self.engine.as_contract().signer();
+        anyhow::ensure!(
+            signer.id() == self.engine.id(),
+            "the engine contract must be signed by the engine account, got {}",
+            signer.id()
+        );
+        Ok(signer.clone())
+    }
+}
+
+/// Relayer key added to the engine account.
+#[derive(Debug, Clone)]
+pub struct RelayerKey {
+    account: Account,
+    engine: EngineContract,
+}
+
+impl RelayerKey {
+    pub fn public_key(&self) -> PublicKey {
+        self.account.secret_key().public_key()
+    }
+
+    pub fn secret_key(&self) -> &SecretKey {
+        self.account.secret_key()
+    }
+
+    /// The engine account signing with the restricted function-call key.
+    pub fn account(&self) -> &Account {
+        &self.account
+    }
+
+    /// The engine contract whose transactions are signed with the relayer key,
+    /// e.g. to `submit` EVM transactions.
+    pub fn engine(&self) -> &EngineContract {
+        &self.engine
+    }
+}
@@ -0,0 +1,143 @@
+use crate::hash::keccak;
+use crate::types::Address;
+use aurora_engine_types::U256;
+use libsecp256k1::{Message, PublicKey, SecretKey};
+
+/// Gas limit of the transactions created by `LegacyTransaction::new`.
+pub const DEFAULT_GAS_LIMIT: u64 = 10_000_000;
+
+/// Legacy EVM transaction, which `EvmSigner` signs with the EIP-155 replay protection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LegacyTransaction {
+    pub nonce: U256,
+    pub gas_price: U256,
+    pub gas_limit: U256,
+    /// Recipient of the transaction, `None` deploys the contract from the data.
+    pub to: Option<Address>,
+    pub value: U256,
+    pub data: Vec<u8>,
+}
+
+impl LegacyTransaction {
+    /// Creates the transaction with the zero gas price, which the engine accepts.
+    pub fn new(nonce: U256, to: Option<Address>, value: U256, data: Vec<u8>) -> Self {
+        Self {
+            nonce,
+            gas_price: U256::zero(),
+            gas_limit: U256::from(DEFAULT_GAS_LIMIT),
+            to,
+            value,
+            data,
+        }
+    }
+
+    pub fn gas_price(mut self, gas_price: U256) -> Self {
+        self.gas_price = gas_price;
+        self
+    }
+
+    pub fn gas_limit(mut self, gas_limit: U256) -> Self {
+        self.gas_limit = gas_limit;
+        self
+    }
+
+    fn rlp_fields(&self) -> Vec<Vec<u8>> {
+        let to = self.to.map(|to| to.as_bytes().to_vec()).unwrap_or_default();
+        vec![
+            rlp_u256(self.nonce),
+            rlp_u256(self.gas_price),
+            rlp_u256(self.gas_limit),
+            rlp_bytes(&to),
+            rlp_u256(self.value),
+            rlp_bytes(&self.data),
+        ]
+    }
+}
+
+/// Signs the EVM transactions with the secp256k1 key, e.g. to deploy the contracts by
+/// `submit` the way dapps do.
+#[derive(Debug, Clone)]
+pub struct EvmSigner {
+    secret_key: SecretKey,
+    address: Address,
+}
+
+impl EvmSigner {
+    pub fn new(secret_key: [u8; 32]) -> anyhow::Result<Self> {
+        let secret_key = SecretKey::parse(&secret_key)
+            .map_err(|e| anyhow::anyhow!("invalid secret key: {e:?}"))?;
+        let public_key = PublicKey::from_secret_key(&secret_key).serialize();
+        let hash = keccak(&public_key[1..]);
+        let mut address = [0u8; 20];
+        address.copy_from_slice(&hash.as_bytes()[12..]);
+
+        Ok(Self {
+            secret_key,
+            address: Address::from_array(address),
+        })
+    }
+
+    /// Creates the signer with the keccak hash of the seed as the secret key, so the tests
+    /// get the same addresses on every run.
+    pub fn from_seed(seed: &str) -> Self {
+        Self::new(keccak(seed.as_bytes()).0).expect("keccak hash is a valid secret key")
+    }
+
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    /// Returns the RLP encoded signed transaction, the input of `submit`.
+    pub fn sign(&self, transaction: &LegacyTransaction, chain_id: u64) -> Vec<u8> {
+        let mut fields = transaction.rlp_fields();
+        fields.extend([rlp_u256(chain_id.into()), rlp_bytes(&[]), rlp_bytes(&[])]);
+        let hash = keccak(&rlp_list(&fields));
+        let (signature, recovery_id) =
+            libsecp256k1::sign(&Message::parse(&hash.0), &self.secret_key);
+        let signature = signature.serialize();
+
+        fields.truncate(6);
+        fields.extend([
+            rlp_u256(U256::from(
+                chain_id * 2 + 35 + u64::from(recovery_id.serialize()),
+            )),
+            rlp_u256(U256::from_big_endian(&signature[..32])),
+            rlp_u256(U256::from_big_endian(&signature[32..])),
+        ]);
+        rlp_list(&fields)
+    }
+}
+
+pub(crate) fn rlp_bytes(bytes: &[u8]) -> Vec<u8> {
+    if bytes.len() == 1 && bytes[0] < 0x80 {
+        return bytes.to_vec();
+    }
+    let mut encoded = rlp_length(bytes.len(), 0x80);
+    encoded.extend_from_slice(bytes);
+    encoded
+}
+
+/// Encodes the integer as the big-endian bytes without the leading zeros.
+pub(crate) fn rlp_u256(value: U256) -> Vec<u8> {
+    let bytes = value.to_big_endian();
+    let start = bytes.iter().position(|b| *b != 0).unwrap_or(bytes.len());
+    rlp_bytes(&bytes[start..])
+}
+
+pub(crate) fn rlp_list(items: &[Vec<u8>]) -> Vec<u8> {
+    let payload = items.concat();
+    let mut encoded = rlp_length(payload.len(), 0xc0);
+    encoded.extend(payload);
+    encoded
+}
+
+fn rlp_length(len: usize, offset: u8) -> Vec<u8> {
+    if len < 56 {
+        return vec![offset + len as u8];
+    }
+    let bytes = (len as u64).to_be_bytes();
+    let start = bytes.iter().position(|b| *b != 0).unwrap_or(bytes.len());
+    let mut encoded = vec![offset + 55 + (bytes.len() - start) as u8];
+    encoded.extend_from_slice(&bytes[start..]);
+    encoded
+}
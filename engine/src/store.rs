@@ -0,0 +1,252 @@
+use crate::hash::keccak;
+use crate::types::{Address, SubmitResult, TransactionStatus};
+use crate::EngineContract;
+use aurora_engine_types::H256;
+use aurora_workspace_utils::results::ExecutionResult;
+use near_workspaces::network::Sandbox;
+use near_workspaces::types::CryptoHash;
+use near_workspaces::Worker;
+use std::collections::HashMap;
+
+/// Ethereum hash of the signed transaction: the keccak hash of its RLP encoding.
+pub fn transaction_hash(raw_tx: &[u8]) -> H256 {
+    keccak(raw_tx)
+}
+
+/// Stores the results of the submitted EVM transactions, so they can be looked up by
+/// the Ethereum hash the way dapps do.
+///
+/// The engine has no notion of the Ethereum blocks, so every recorded transaction gets
+/// its own block, the way development nodes do. The first block has the height `1`.
+/// A transaction can be recorded only once, so that every hash points to one block.
+#[derive(Debug, Clone, Default)]
+pub struct TransactionStore {
+    /// Hashes of the transactions, the index is the block height minus one.
+    blocks: Vec<H256>,
+    receipts: HashMap<H256, EvmReceipt>,
+    /// Worker of the sandbox running the engine, which looks up the NEAR block heights.
+    worker: Option<Worker<Sandbox>>,
+}
+
+impl TransactionStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Looks up the heights of the NEAR blocks of the transactions sent by
+    /// [`TransactionStore::submit`] in the sandbox of the worker.
+    pub fn with_worker(mut self, worker: Worker<Sandbox>) -> Self {
+        self.worker = Some(worker);
+        self
+    }
+
+    /// Submits the signed transaction to the engine and records its result. The height of
+    /// the NEAR block is recorded if the store has the worker of the sandbox.
+    pub async fn submit(
+        &mut self,
+        engine: &EngineContract,
+        raw_tx: Vec<u8>,
+    ) -> anyhow::Result<&EvmReceipt> {
+        self.check_new(&raw_tx)?;
+        let result = engine
+            .submit(raw_tx.clone())
+            .max_gas()
+            .transact()
+            .await
+            .map_err(|e| anyhow::anyhow!("error while submitting transaction: {e}"))?;
+        let near_block_height = match &self.worker {
+            Some(worker) => {
+                let block = worker
+                    .view_block()
+                    .block_hash(result.outcome().block_hash)
+                    .await
+                    .map_err(|e| anyhow::anyhow!("error while viewing block: {e}"))?;
+                Some(block.height())
+            }
+            None => None,
+        };
+        self.insert(raw_tx, &result, near_block_height)
+    }
+
+    /// Records the result of the transaction submitted outside of the store, without the
+    /// height of the NEAR block. Fails if the transaction has already been recorded.
+    pub fn record(
+        &mut self,
+        raw_tx: Vec<u8>,
+        result: &ExecutionResult<SubmitResult>,
+    ) -> anyhow::Result<&EvmReceipt> {
+        self.insert(raw_tx, result, None)
+    }
+
+    fn insert(
+        &mut self,
+        raw_tx: Vec<u8>,
+        result: &ExecutionResult<SubmitResult>,
+        near_block_height: Option<u64>,
+    ) -> anyhow::Result<&EvmReceipt> {
+        let tx_hash = self.check_new(&raw_tx)?;
+        self.blocks.push(tx_hash);
+        let block_height = self.block_height();
+        let logs = result
+            .value()
+            .logs
+            .iter()
+            .enumerate()
+            .map(|(index, log)| EvmLog {
+                address: log.address,
+                topics: log.topics.iter().copied().map(H256).collect(),
+                data: log.data.clone(),
+                block_height,
+                tx_hash,
+                log_index: index as u64,
+            })
+            .collect();
+        let receipt = EvmReceipt {
+            tx_hash,
+            near_tx_hash: result.outcome().transaction_hash,
+            near_block_hash: Some(result.outcome().block_hash),
+            near_block_height,
+            block_height,
+            transaction: raw_tx,
+            result: result.value().clone(),
+            logs,
+        };
+
+        self.receipts.insert(tx_hash, receipt);
+        Ok(&self.receipts[&tx_hash])
+    }
+
+    fn check_new(&self, raw_tx: &[u8]) -> anyhow::Result<H256> {
+        let tx_hash = transaction_hash(raw_tx);
+        if self.receipts.contains_key(&tx_hash) {
+            anyhow::bail!("transaction {tx_hash:?} has already been recorded");
+        }
+        Ok(tx_hash)
+    }
+
+    /// Height of the latest block, `0` if no transactions have been recorded.
+    pub fn block_height(&self) -> u64 {
+        self.blocks.len() as u64
+    }
+
+    pub fn receipt(&self, tx_hash: &H256) -> Option<&EvmReceipt> {
+        self.receipts.get(tx_hash)
+    }
+
+    /// Returns the receipt of the only transaction of the block.
+    pub fn receipt_by_block(&self, block_height: u64) -> Option<&EvmReceipt> {
+        let index = usize::try_from(block_height.checked_sub(1)?).ok()?;
+        self.receipt(self.blocks.get(index)?)
+    }
+
+    /// Receipts in the order the transactions have been recorded.
+    pub fn receipts(&self) -> impl Iterator<Item = &EvmReceipt> {
+        self.blocks.iter().map(|hash| &self.receipts[hash])
+    }
+
+    /// Returns the logs matching the filter, ordered by the block and the log index.
+    pub fn logs(&self, filter: &LogFilter) -> Vec<&EvmLog> {
+        self.receipts()
+            .flat_map(|receipt| receipt.logs.iter())
+            .filter(|log| filter.matches(log))
+            .collect()
+    }
+}
+
+/// Result of the recorded EVM transaction.
+#[derive(Debug, Clone)]
+pub struct EvmReceipt {
+    pub tx_hash: H256,
+    /// Hash of the NEAR transaction which submitted the EVM transaction: the id of its
+    /// transaction outcome.
+    pub near_tx_hash: CryptoHash,
+    /// Hash of the NEAR block which included the NEAR transaction, `None` for the
+    /// in-process backend.
+    pub near_block_hash: Option<CryptoHash>,
+    /// Height of the NEAR block which included the NEAR transaction. It is known only for
+    /// the transactions sent by the store with the worker, see [`TransactionStore::with_worker`].
+    pub near_block_height: Option<u64>,
+    /// Height of the synthetic Ethereum block of the transaction.
+    pub block_height: u64,
+    /// RLP encoded signed transaction.
+    pub transaction: Vec<u8>,
+    pub result: SubmitResult,
+    pub logs: Vec<EvmLog>,
+}
+
+impl EvmReceipt {
+    pub fn is_success(&self) -> bool {
+        matches!(self.result.status, TransactionStatus::Succeed(_))
+    }
+}
+
+/// Log emitted by the recorded transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvmLog {
+    pub address: Address,
+    pub topics: Vec<H256>,
+    pub data: Vec<u8>,
+    pub block_height: u64,
+    pub tx_hash: H256,
+    /// Position of the log in the block. Blocks hold a single transaction, so it is
+    /// the position in the transaction as well.
+    pub log_index: u64,
+}
+
+/// Filter of the logs with the semantics of `eth_getLogs`: empty criteria match any log
+/// and the topics are matched by their positions.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LogFilter {
+    pub from_block: Option<u64>,
+    pub to_block: Option<u64>,
+    pub addresses: Vec<Address>,
+    /// Accepted topics for every position, `None` accepts any topic.
+    pub topics: Vec<Option<Vec<H256>>>,
+}
+
+impl LogFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_block(mut self, block_height: u64) -> Self {
+        self.from_block = Some(block_height);
+        self
+    }
+
+    pub fn to_block(mut self, block_height: u64) -> Self {
+        self.to_block = Some(block_height);
+        self
+    }
+
+    /// Adds the address to the accepted addresses.
+    pub fn address(mut self, address: Address) -> Self {
+        self.addresses.push(address);
+        self
+    }
+
+    /// Adds the topic to the accepted topics at the position.
+    pub fn topic(mut self, position: usize, topic: H256) -> Self {
+        if self.topics.len() <= position {
+            self.topics.resize(position + 1, None);
+        }
+        self.topics[position]
+            .get_or_insert_with(Vec::new)
+            .push(topic);
+        self
+    }
+
+    pub fn matches(&self, log: &EvmLog) -> bool {
+        self.from_block
+            .map_or(true, |from| log.block_height >= from)
+            && self.to_block.map_or(true, |to| log.block_height <= to)
+            && (self.addresses.is_empty() || self.addresses.contains(&log.address))
+            && self.topics.iter().enumerate().all(|(position, topics)| {
+                topics.as_ref().map_or(true, |topics| {
+                    log.topics
+                        .get(position)
+                        .is_some_and(|topic| topics.contains(topic))
+                })
+            })
+    }
+}
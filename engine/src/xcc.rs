@@ -0,0 +1,210 @@
+use crate::types::{to_aurora_account_id, Address, SubmitResult};
+use crate::{ContractId, EngineContract};
+use aurora_engine_types::borsh;
+use aurora_engine_types::parameters::{CrossContractCallArgs, PromiseArgs};
+use aurora_engine_types::U256;
+use aurora_workspace_utils::receipts::ReceiptNode;
+use aurora_workspace_utils::results::ExecutionResult;
+use near_workspaces::types::NearToken;
+use near_workspaces::{Account, AccountId};
+use serde_json::json;
+
+/// Address of the cross-contract call precompile: `keccak("nearCrossContractCall")[12..]`.
+const XCC_PRECOMPILE_ADDRESS: &str = "516cded1d16af10cad47d6d49128e2eb7d27b372";
+/// Deposit attached to `storage_deposit` of wNEAR, the excess is refunded.
+const STORAGE_DEPOSIT: NearToken = NearToken::from_millinear(125);
+
+pub fn xcc_precompile_address() -> Address {
+    Address::decode(XCC_PRECOMPILE_ADDRESS).expect("valid precompile address")
+}
+
+/// Returns the id of the XCC router sub-account which the engine creates for the address.
+pub fn router_account_id(address: &Address, engine_id: &AccountId) -> anyhow::Result<AccountId> {
+    Ok(format!("{}.{engine_id}", address.encode()).parse()?)
+}
+
+/// wNEAR NEP-141 token and the account whose wNEAR funds the XCC of an address.
+#[derive(Debug, Clone)]
+pub struct WNearFunding {
+    pub token_id: AccountId,
+    pub funder: Account,
+    pub amount: NearToken,
+}
+
+/// Sets up and drives the cross-contract calls of the engine.
+#[derive(Debug, Clone)]
+pub struct XccHelper {
+    engine: EngineContract,
+}
+
+impl XccHelper {
+    pub fn new(engine: &EngineContract) -> Self {
+        Self {
+            engine: engine.clone(),
+        }
+    }
+
+    pub fn router_id(&self, address: &Address) -> anyhow::Result<AccountId> {
+        router_account_id(address, self.engine.id())
+    }
+
+    /// Deploys the router code to the factory and sets the address of the wNEAR ERC-20 token,
+    /// registering the wNEAR NEP-141 token with `deploy_erc20_token` if needed. The calls must
+    /// be signed by the owner of the engine. Returns the ERC-20 address of wNEAR.
+    pub async fn setup(
+        &self,
+        router_wasm: Vec<u8>,
+        wnear_id: &AccountId,
+    ) -> anyhow::Result<Address> {
+        self.engine
+            .factory_update(router_wasm)
+            .max_gas()
+            .transact()
+            .await
+            .map_err(|e| anyhow::anyhow!("error while updating XCC factory: {e}"))?;
+
+        let nep141 = to_aurora_account_id(wnear_id);
+        let lookup = self
+            .engine
+            .with_recorder(None)
+            .get_erc20_from_nep141(nep141.clone());
+        let wnear_address = match lookup.await {
+            Ok(view) => view.result,
+            Err(_) => self
+                .engine
+                .deploy_erc20_token(nep141)
+                .max_gas()
+                .transact()
+                .await
+                .map_err(|e| anyhow::anyhow!("error while registering wNEAR: {e}"))?
+                .into_value(),
+        };
+        self.engine
+            .factory_set_wnear_address(wnear_address)
+            .transact()
+            .await
+            .map_err(|e| anyhow::anyhow!("error while setting wNEAR address: {e}"))?;
+        Ok(wnear_address)
+    }
+
+    /// Creates the router of the address funded with the deposit and returns its id. With
+    /// `wnear`, the wNEAR is bridged to the address first, so it can pay for the NEAR attached
+    /// to its promises, and the router is created for that wNEAR token.
+    pub async fn fund_router(
+        &self,
+        target: Address,
+        wnear: Option<&WNearFunding>,
+        deposit: NearToken,
+    ) -> anyhow::Result<AccountId> {
+        let wnear_account_id = match wnear {
+            Some(wnear) => {
+                self.fund_wnear(wnear, target).await?;
+                Some(to_aurora_account_id(&wnear.token_id))
+            }
+            None => None,
+        };
+        self.engine
+            .fund_xcc_sub_account(target, wnear_account_id)
+            .deposit(deposit)
+            .max_gas()
+            .transact()
+            .await
+            .map_err(|e| anyhow::anyhow!("error while funding XCC router: {e}"))?;
+        self.router_id(&target)
+    }
+
+    /// Bridges the wNEAR of the funder to the address with `ft_transfer_call` to the engine.
+    pub async fn fund_wnear(&self, wnear: &WNearFunding, target: Address) -> anyhow::Result<()> {
+        wnear
+            .funder
+            .call(&wnear.token_id, "storage_deposit")
+            .args_json(json!({ "account_id": self.engine.id(), "registration_only": true }))
+            .deposit(STORAGE_DEPOSIT)
+            .transact()
+            .await
+            .map_err(|e| anyhow::anyhow!("error while registering engine in wNEAR: {e}"))?
+            .into_result()
+            .map_err(|e| anyhow::anyhow!("error while registering engine in wNEAR: {e}"))?;
+
+        let result = wnear
+            .funder
+            .call(&wnear.token_id, "ft_transfer_call")
+            .args_json(json!({
+                "receiver_id": self.engine.id(),
+                "amount": wnear.amount.as_yoctonear().to_string(),
+                "msg": target.encode(),
+            }))
+            .deposit(NearToken::from_yoctonear(1))
+            .max_gas()
+            .transact()
+            .await
+            .map_err(|e| anyhow::anyhow!("error while bridging wNEAR: {e}"))?;
+        let failures = result.receipt_failures();
+        anyhow::ensure!(
+            failures.is_empty(),
+            "error while bridging wNEAR: {failures:?}"
+        );
+        result
+            .into_result()
+            .map_err(|e| anyhow::anyhow!("error while bridging wNEAR: {e}"))?;
+        Ok(())
+    }
+
+    /// Calls the XCC precompile which schedules the promise immediately.
+    pub async fn call(&self, promise: PromiseArgs) -> anyhow::Result<XccOutcome> {
+        self.call_precompile(CrossContractCallArgs::Eager(promise))
+            .await
+    }
+
+    /// Calls the XCC precompile which stores the promise in the router to be executed later.
+    pub async fn schedule(&self, promise: PromiseArgs) -> anyhow::Result<XccOutcome> {
+        self.call_precompile(CrossContractCallArgs::Delayed(promise))
+            .await
+    }
+
+    async fn call_precompile(&self, args: CrossContractCallArgs) -> anyhow::Result<XccOutcome> {
+        let input = borsh::to_vec(&args)?;
+        let result = self
+            .engine
+            .call(xcc_precompile_address(), U256::zero(), input)
+            .max_gas()
+            .transact()
+            .await?;
+        Ok(XccOutcome::new(result))
+    }
+}
+
+/// Result of the EVM call to the XCC precompile with the NEAR promises it produced.
+#[derive(Debug)]
+pub struct XccOutcome {
+    result: ExecutionResult<SubmitResult>,
+    tree: ReceiptNode,
+}
+
+impl XccOutcome {
+    fn new(result: ExecutionResult<SubmitResult>) -> Self {
+        let tree = result.receipt_tree();
+        Self { result, tree }
+    }
+
+    pub fn result(&self) -> &ExecutionResult<SubmitResult> {
+        &self.result
+    }
+
+    pub fn receipt_tree(&self) -> &ReceiptNode {
+        &self.tree
+    }
+
+    /// Receipts executed by the account, e.g. by the router or by the target of the promise.
+    pub fn receipts_of(&self, account_id: &AccountId) -> Vec<&ReceiptNode> {
+        self.tree
+            .iter()
+            .filter(|node| &node.executor_id == account_id)
+            .collect()
+    }
+
+    /// Returns `true` if all receipts spawned by the call have succeeded.
+    pub fn is_success(&self) -> bool {
+        self.result.is_success() && self.tree.failures().is_empty()
+    }
+}
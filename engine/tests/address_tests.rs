@@ -0,0 +1,58 @@
+use aurora_workspace_engine::types::{
+    near_account_to_evm_address, parse_address, to_checksum_address, Address, EvmRecipient,
+};
+
+/// Addresses from the EIP-55 specification.
+const CHECKSUM_ADDRESSES: &[&str] = &[
+    "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+    "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+    "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+    "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+];
+
+#[test]
+fn test_near_account_to_evm_address() {
+    let address = near_account_to_evm_address("aurora");
+    assert_eq!(address.encode(), "4444588443c3a91288c5002483449aba1054192b");
+    assert_ne!(near_account_to_evm_address("aurora.root"), address);
+}
+
+#[test]
+fn test_checksum_address() {
+    for checksummed in CHECKSUM_ADDRESSES {
+        let address = parse_address(checksummed).unwrap();
+        assert_eq!(to_checksum_address(&address), *checksummed);
+        assert_eq!(parse_address(&checksummed.to_lowercase()).unwrap(), address);
+        assert_eq!(parse_address(&checksummed[2..]).unwrap(), address);
+    }
+
+    let invalid = CHECKSUM_ADDRESSES[0].replace('a', "A");
+    let err = parse_address(&invalid).unwrap_err();
+    assert!(err.to_string().contains("invalid checksum"));
+    assert!(parse_address("0x1234").is_err());
+    assert!(parse_address("0xzz").is_err());
+}
+
+#[test]
+fn test_evm_recipient() {
+    let address = Address::from_array([0xab; 20]);
+    let recipient = EvmRecipient::new("aurora", address);
+    assert_eq!(recipient.to_string(), format!("aurora:{}", "ab".repeat(20)));
+    assert_eq!(recipient.message(), "ab".repeat(20));
+    assert_eq!(
+        recipient.to_string().parse::<EvmRecipient>().unwrap(),
+        recipient
+    );
+
+    let recipient: EvmRecipient = format!("aurora.root:0x{}", "ab".repeat(20))
+        .parse()
+        .unwrap();
+    assert_eq!(recipient.engine_id, "aurora.root");
+    assert_eq!(recipient.address, address);
+
+    assert!("ab".repeat(20).parse::<EvmRecipient>().is_err());
+    assert!(format!(":{}", "ab".repeat(20))
+        .parse::<EvmRecipient>()
+        .is_err());
+    assert!("aurora:1234".parse::<EvmRecipient>().is_err());
+}
@@ -0,0 +1,262 @@
+use aurora_engine_types::U256;
+use aurora_workspace_engine::artifact::{Abi, Artifact, Bytecode};
+use aurora_workspace_engine::hash::keccak;
+use aurora_workspace_engine::signer::{EvmSigner, LegacyTransaction};
+use aurora_workspace_engine::types::{create_address, parse_address, Address};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+
+mod utils;
+
+const LIBRARY: &str = "src/Math.sol:Math";
+
+fn erc20_abi() -> Value {
+    json!([
+        {"type": "constructor", "inputs": [{"name": "supply", "type": "uint256"}]},
+        {
+            "type": "function",
+            "name": "transfer",
+            "inputs": [{"name": "to", "type": "address"}, {"name": "amount", "type": "uint256"}],
+            "outputs": [{"name": "", "type": "bool"}],
+            "stateMutability": "nonpayable"
+        },
+        {
+            "name": "balanceOf",
+            "inputs": [{"name": "owner", "type": "address"}],
+            "outputs": [{"name": "", "type": "uint256"}],
+            "stateMutability": "view"
+        },
+        {
+            "type": "event",
+            "name": "Transfer",
+            "inputs": [
+                {"name": "from", "type": "address", "indexed": true},
+                {"name": "to", "type": "address", "indexed": true},
+                {"name": "value", "type": "uint256", "indexed": false}
+            ],
+            "anonymous": false
+        },
+        {"type": "error", "name": "InsufficientBalance", "inputs": []},
+        {"type": "receive", "stateMutability": "payable"}
+    ])
+}
+
+fn write_json(name: &str, json: &Value) -> PathBuf {
+    let dir = std::env::temp_dir().join("aurora-workspace-artifacts");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(format!("{name}.json"));
+    std::fs::write(&path, json.to_string()).unwrap();
+    path
+}
+
+#[test]
+fn test_abi() {
+    let abi = Abi::from_json(&erc20_abi()).unwrap();
+    assert_eq!(abi.functions.len(), 2);
+    assert_eq!(abi.constructor.as_ref().unwrap().signature(), "(uint256)");
+
+    let transfer = abi.function("transfer").unwrap();
+    assert_eq!(transfer.signature(), "transfer(address,uint256)");
+    assert_eq!(transfer.selector(), [0xa9, 0x05, 0x9c, 0xbb]);
+    assert_eq!(
+        transfer.encode_input(&[1, 2]),
+        [0xa9, 0x05, 0x9c, 0xbb, 1, 2]
+    );
+    assert_eq!(
+        abi.function("balanceOf(address)").unwrap().selector(),
+        [0x70, 0xa0, 0x82, 0x31]
+    );
+    assert!(abi.function("approve").is_err());
+
+    let transfer = abi.event("Transfer").unwrap();
+    assert_eq!(
+        hex::encode(transfer.topic()),
+        "ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef"
+    );
+
+    let abi = Abi::from_json(&json!([
+        {"type": "function", "name": "f", "inputs": [{"type": "uint256"}]},
+        {"type": "function", "name": "f", "inputs": [{
+            "type": "tuple[]",
+            "components": [{"type": "uint256"}, {"type": "address"}]
+        }]}
+    ]))
+    .unwrap();
+    let err = abi.function("f").unwrap_err();
+    assert!(err.to_string().contains("overloaded"));
+    let f = abi.function("f((uint256,address)[])").unwrap();
+    assert_eq!(f.inputs[0].canonical_type(), "(uint256,address)[]");
+}
+
+#[test]
+fn test_foundry_and_hardhat_artifacts() {
+    let library = Address::from_array([0x11; 20]);
+    let placeholder = format!("__${}$__", "00".repeat(17));
+    let link_references = json!({"src/Math.sol": {"Math": [{"start": 2, "length": 20}]}});
+
+    let foundry = json!({
+        "abi": erc20_abi(),
+        "bytecode": {"object": format!("0x6001{placeholder}00"), "linkReferences": link_references},
+        "deployedBytecode": {"object": "0x6002", "linkReferences": {}},
+    });
+    let path = write_json("Token", &foundry);
+    let artifact = Artifact::from_file(&path).unwrap();
+    assert_eq!(artifact.contract_name, "Token");
+    assert!(!artifact.bytecode.is_linked());
+    assert!(artifact.deploy_input(&[]).is_err());
+    assert_eq!(artifact.deployed_bytecode.to_bytes().unwrap(), [0x60, 0x02]);
+
+    let linked = artifact.clone().link("Math", library).unwrap();
+    assert!(linked.bytecode.is_linked());
+    let expected = [&[0x60, 0x01][..], library.as_bytes(), &[0x00, 0x07]].concat();
+    assert_eq!(linked.deploy_input(&[0x07]).unwrap(), expected);
+    assert!(artifact.link("Other", library).is_err());
+
+    let hardhat = json!({
+        "_format": "hh-sol-artifact-1",
+        "contractName": "Token",
+        "sourceName": "contracts/Token.sol",
+        "abi": erc20_abi(),
+        "bytecode": format!("0x6001{placeholder}"),
+        "deployedBytecode": format!("0x6002{placeholder}"),
+        "linkReferences": link_references,
+        "deployedLinkReferences": link_references,
+    });
+    let artifact = Artifact::from_json("Token", &hardhat)
+        .unwrap()
+        .link(LIBRARY, library)
+        .unwrap();
+    assert_eq!(artifact.contract_name, "Token");
+    assert!(artifact.bytecode.is_linked());
+    assert!(artifact.deployed_bytecode.is_linked());
+    assert!(artifact.abi.function("transfer").is_ok());
+}
+
+#[test]
+fn test_combined_json_artifacts() {
+    let library = Address::from_array([0x22; 20]);
+    let hash = keccak(LIBRARY.as_bytes());
+    let placeholder = format!("__${}$__", hex::encode(&hash.as_bytes()[..17]));
+
+    let solc = json!({
+        "contracts": {
+            "src/Token.sol:Token": {
+                "abi": erc20_abi().to_string(),
+                "bin": format!("6001{placeholder}"),
+                "bin-runtime": "6002",
+            },
+            "src/Math.sol:Math": {"abi": [], "bin": "6003", "bin-runtime": "6004"},
+        },
+        "version": "0.8.24",
+    });
+    let path = write_json("solc", &solc);
+    let artifacts = Artifact::all_from_combined_json(&path).unwrap();
+    assert_eq!(artifacts.len(), 2);
+    assert_eq!(artifacts["src/Math.sol:Math"].contract_name, "Math");
+
+    let artifact = Artifact::from_combined_json(&path, "Token")
+        .unwrap()
+        .link(LIBRARY, library)
+        .unwrap();
+    let expected = [&[0x60, 0x01][..], library.as_bytes()].concat();
+    assert_eq!(artifact.deploy_input(&[]).unwrap(), expected);
+    assert_eq!(artifact.abi.functions.len(), 2);
+    assert!(Artifact::from_combined_json(&path, "Other").is_err());
+
+    let legacy = format!("__{:_<38}", LIBRARY);
+    let mut bytecode = Bytecode::new(&legacy, Default::default());
+    assert!(bytecode.link(LIBRARY, library).unwrap());
+    assert_eq!(bytecode.to_bytes().unwrap(), library.as_bytes());
+    // The 36th byte is in the middle of `é`.
+    let mut bytecode = Bytecode::new(&legacy, Default::default());
+    assert!(!bytecode
+        .link(&format!("{}é", "a".repeat(35)), library)
+        .unwrap());
+
+    let vyper = json!({
+        "contracts/Vault.vy": {
+            "abi": erc20_abi(),
+            "bytecode": "0x6005",
+            "bytecode_runtime": "0x6006",
+        },
+        "version": "0.3.10",
+    });
+    let path = write_json("vyper", &vyper);
+    let artifact = Artifact::from_combined_json(&path, "Vault").unwrap();
+    assert_eq!(artifact.contract_name, "Vault");
+    assert_eq!(artifact.bytecode.to_bytes().unwrap(), [0x60, 0x05]);
+    assert_eq!(artifact.deployed_bytecode.to_bytes().unwrap(), [0x60, 0x06]);
+}
+
+#[test]
+fn test_signer() {
+    // The example of EIP-155.
+    let signer = EvmSigner::new([0x46; 32]).unwrap();
+    assert_eq!(
+        signer.address(),
+        parse_address("0x9d8A62f656a8d1615C1294fd71e9CFb3E4855A4F").unwrap()
+    );
+    let transaction = LegacyTransaction::new(
+        U256::from(9),
+        Some(Address::from_array([0x35; 20])),
+        U256::from(10).pow(U256::from(18)),
+        vec![],
+    )
+    .gas_price(U256::from(20_000_000_000u64))
+    .gas_limit(U256::from(21_000));
+    assert_eq!(
+        hex::encode(signer.sign(&transaction, 1)),
+        "f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a7640000\
+         8025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f\
+         761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83"
+    );
+
+    let sender = parse_address("0x6ac7ea33f8831ea9dcc53393aaa88b25a785dbf0").unwrap();
+    assert_eq!(
+        create_address(sender, U256::zero()),
+        parse_address("0xcd234a471b72ba2f1ccf0a70fcaba648a5eecd8d").unwrap()
+    );
+    assert_eq!(
+        create_address(sender, U256::one()),
+        parse_address("0x343c43a37d37dff08ae8c4a11544c718abb4fcf8").unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_deploy_artifact() {
+    let contract = utils::deploy_and_init_contract().await.unwrap();
+    let artifact = Artifact::from_json(
+        "Token",
+        &json!({
+            "abi": erc20_abi(),
+            "bytecode": {"object": "0x60016002", "linkReferences": {}},
+            "deployedBytecode": {"object": "0x6002", "linkReferences": {}},
+        }),
+    )
+    .unwrap();
+
+    let token = artifact.deploy(&contract, &[0x07]).await.unwrap();
+    let code = contract.get_code(token.address()).await.unwrap().result;
+    assert_eq!(code, [0x60, 0x01, 0x60, 0x02, 0x07]);
+    let result = token
+        .call("transfer", &[0; 64])
+        .unwrap()
+        .max_gas()
+        .transact()
+        .await
+        .unwrap();
+    assert!(result.is_success());
+    assert!(token.call("approve", &[]).is_err());
+
+    let signer = EvmSigner::from_seed("deployer");
+    let token = artifact
+        .deploy_with_signer(&contract, &signer, &[])
+        .await
+        .unwrap();
+    assert_eq!(token.abi(), &artifact.abi);
+    // The engine derives the address from the signed transaction on its own.
+    let code = contract.get_code(token.address()).await.unwrap().result;
+    assert_eq!(code, [0x60, 0x01, 0x60, 0x02]);
+    let nonce = contract.get_nonce(signer.address()).await.unwrap().result;
+    assert_eq!(nonce, U256::one());
+}
@@ -1,12 +1,107 @@
+use aurora_engine_types::types::Address;
+use aurora_workspace_engine::types::{EnginePausedFlags, PrecompileFlags};
+use aurora_workspace_engine::ContractId;
+use near_workspaces::types::NearToken;
+
 mod utils;
 
 #[tokio::test]
 async fn test_pause_precompiles() {
     let contract = utils::deploy_and_init_contract().await.unwrap();
     contract
-        .pause_precompiles(0)
+        .pause_precompiles(PrecompileFlags::empty())
         .max_gas()
         .transact()
         .await
         .unwrap();
 }
+
+#[tokio::test]
+async fn test_pause_and_resume_precompiles() {
+    let contract = utils::deploy_and_init_contract().await.unwrap();
+    contract
+        .pause_precompiles(PrecompileFlags::EXIT_TO_NEAR | PrecompileFlags::EXIT_TO_ETHEREUM)
+        .max_gas()
+        .transact()
+        .await
+        .unwrap();
+    assert_eq!(
+        contract.get_paused_precompiles().await.unwrap().result,
+        PrecompileFlags::all()
+    );
+
+    contract
+        .resume_precompiles(PrecompileFlags::EXIT_TO_NEAR)
+        .max_gas()
+        .transact()
+        .await
+        .unwrap();
+    assert_eq!(
+        contract.get_paused_precompiles().await.unwrap().result,
+        PrecompileFlags::EXIT_TO_ETHEREUM
+    );
+}
+
+#[tokio::test]
+async fn test_set_paused_flags() {
+    let contract = utils::deploy_and_init_contract().await.unwrap();
+    contract
+        .set_paused_flags(EnginePausedFlags::PAUSE_DEPOSIT)
+        .transact()
+        .await
+        .unwrap();
+    let flags = contract.get_paused_flags().await.unwrap().result;
+    assert_eq!(flags, EnginePausedFlags::PAUSE_DEPOSIT);
+    assert_eq!(u8::from(flags), 1);
+}
+
+#[tokio::test]
+async fn test_pause_precompiles_by_non_owner() {
+    let contract = utils::deploy_and_init_contract().await.unwrap();
+    let user = contract
+        .root()
+        .create_subaccount("user")
+        .initial_balance(NearToken::from_near(10))
+        .transact()
+        .await
+        .unwrap()
+        .into_result()
+        .unwrap();
+    let user_contract = contract.as_account(&user);
+    assert_eq!(user_contract.id(), contract.id());
+
+    let err = user_contract
+        .pause_precompiles(PrecompileFlags::EXIT_TO_NEAR)
+        .max_gas()
+        .transact()
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("ERR_NOT_ALLOWED"));
+
+    let result = user_contract
+        .register_relayer(Address::from_array([1u8; 20]))
+        .transact()
+        .await
+        .unwrap();
+    assert_eq!(&result.outcome().executor_id, user.id());
+    assert_eq!(
+        contract.get_paused_precompiles().await.unwrap().result,
+        PrecompileFlags::empty()
+    );
+}
+
+#[test]
+fn test_flags_display() {
+    let flags = PrecompileFlags::EXIT_TO_NEAR | PrecompileFlags::EXIT_TO_ETHEREUM;
+    assert_eq!(flags.to_string(), "EXIT_TO_NEAR | EXIT_TO_ETHEREUM");
+    assert_eq!(u32::from(flags), 0b11);
+    assert_eq!(PrecompileFlags::empty().to_string(), "NONE");
+    assert_eq!(
+        PrecompileFlags::from(0b110).to_string(),
+        "EXIT_TO_ETHEREUM | 0x4"
+    );
+    assert_eq!(
+        EnginePausedFlags::PAUSE_WITHDRAW.to_string(),
+        "PAUSE_WITHDRAW"
+    );
+}
@@ -0,0 +1,78 @@
+use aurora_engine_types::U256;
+use aurora_workspace_engine::balance::{Balance, BalanceTracker};
+use aurora_workspace_engine::types::{near_account_to_evm_address, to_aurora_account_id, Address};
+use aurora_workspace_engine::ContractId;
+use near_sdk::json_types::U128;
+use near_workspaces::types::NearToken;
+
+mod utils;
+
+#[tokio::test]
+async fn test_balance_tracker() {
+    let contract = utils::deploy_and_init_contract().await.unwrap();
+    let user = contract
+        .root()
+        .create_subaccount("user")
+        .initial_balance(NearToken::from_near(10))
+        .transact()
+        .await
+        .unwrap()
+        .into_result()
+        .unwrap();
+    let sender = near_account_to_evm_address(contract.id());
+    let recipient = Address::from_array([1; 20]);
+    contract
+        .mint_account(sender, 0, 1_000)
+        .transact()
+        .await
+        .unwrap();
+
+    let tracker = BalanceTracker::new(&contract)
+        .near_account(&user)
+        .evm_address(sender)
+        .evm_address(recipient)
+        .erc20(Address::from_array([2; 20]));
+    let engine_id = contract.id();
+
+    let (_, diff) = tracker
+        .track(|| async {
+            contract
+                .exit_to_near(user.id().as_str(), U256::from(400))
+                .max_gas()
+                .transact()
+                .await?;
+            anyhow::Ok(())
+        })
+        .await
+        .unwrap();
+    diff.assert_delta(Balance::Eth(sender), -400);
+    diff.assert_delta(Balance::nep141(engine_id, user.id()).unwrap(), 400);
+    diff.assert_delta(Balance::TotalEthSupplyOnAurora, -400);
+    diff.assert_delta(Balance::TotalEthSupplyOnNear, 400);
+    diff.assert_unchanged(Balance::near(user.id()).unwrap());
+    diff.assert_unchanged(Balance::Eth(recipient));
+    assert_eq!(diff.changes().unwrap().len(), 5);
+
+    let user_contract = contract.as_account(&user);
+    let (_, diff) = tracker
+        .track(|| async {
+            user_contract
+                .deposit_to_aurora(&to_aurora_account_id(engine_id), U128(100), recipient)?
+                .max_gas()
+                .transact()
+                .await?;
+            anyhow::Ok(())
+        })
+        .await
+        .unwrap();
+    diff.assert_delta(Balance::nep141(engine_id, user.id()).unwrap(), -100);
+    diff.assert_delta(Balance::Eth(recipient), 100);
+    diff.assert_delta(Balance::TotalEthSupplyOnAurora, 100);
+    // The user pays the attached yocto and the gas.
+    diff.assert_delta(Balance::near(user.id()).unwrap(), -1);
+    assert!(diff.delta(&Balance::near(user.id()).unwrap()).unwrap() < -1);
+    assert_eq!(
+        diff.after().get(&Balance::Eth(recipient)),
+        Some(U256::from(100))
+    );
+}
@@ -0,0 +1,70 @@
+use aurora_engine_types::{H256, U256};
+use aurora_workspace_engine::contract::Fault;
+use aurora_workspace_engine::diff::{DiffRunner, Field, Step};
+use aurora_workspace_engine::types::Address;
+
+mod utils;
+
+#[tokio::test]
+async fn test_diff_runner() {
+    let code = utils::CONTRACT_WASM.clone();
+    let address = Address::from_array([1; 20]);
+    let slot = H256::from_low_u64_be(1);
+    let runner = DiffRunner::deploy(code.clone(), code)
+        .await
+        .unwrap()
+        .storage_slot(address, slot);
+
+    let steps = [
+        Step::DeployCode(vec![0x60, 0x01]),
+        Step::Submit(vec![1, 2, 3]),
+        Step::Call {
+            contract: address,
+            value: U256::zero(),
+            input: vec![],
+        },
+        Step::View {
+            sender: address,
+            address,
+            value: U256::zero(),
+            input: vec![],
+        },
+        Step::StorageAt { address, key: slot },
+        Step::Balance(address),
+        Step::Nonce(address),
+    ];
+    runner.run(&steps).await.unwrap().assert_ok();
+
+    let candidate = runner.candidate();
+    candidate
+        .mint_account(address, 0, 100)
+        .transact()
+        .await
+        .unwrap();
+    candidate
+        .set_fault("submit", Fault::Revert(vec![1]))
+        .transact()
+        .await
+        .unwrap();
+
+    let report = runner
+        .run(&[Step::Submit(vec![1, 2, 3]), Step::Balance(address)])
+        .await
+        .unwrap();
+    let fields: Vec<_> = report
+        .divergences
+        .iter()
+        .map(|divergence| (divergence.step, divergence.field))
+        .collect();
+    assert_eq!(
+        fields,
+        vec![(0, Field::Status), (0, Field::Logs), (1, Field::ViewOutput)]
+    );
+    let status = report.divergences_of(0)[0];
+    assert_eq!(status.baseline, "Succeed(0x)");
+    assert_eq!(status.candidate, "Revert(0x01)");
+    assert_eq!(report.divergences_of(1)[0].candidate, "100");
+    assert!(report
+        .to_string()
+        .contains("step 0 (submit 0x010203): status differs"));
+}
@@ -1,6 +1,21 @@
 use aurora_engine_types::account_id::AccountId;
 use aurora_engine_types::parameters::connector::FungibleTokenMetadata;
+use aurora_engine_types::parameters::engine::{SubmitResult, TransactionStatus};
 use aurora_engine_types::types::Address;
+#[cfg(feature = "local")]
+use aurora_engine_types::U256;
+use aurora_workspace_engine::estimate::{intrinsic_gas, GasEstimate, MAX_NEAR_GAS};
+#[cfg(feature = "local")]
+use aurora_workspace_engine::exit::ExitOutcome;
+#[cfg(feature = "local")]
+use aurora_workspace_engine::signer::{EvmSigner, LegacyTransaction};
+#[cfg(feature = "local")]
+use aurora_workspace_engine::types::near_account_to_evm_address;
+#[cfg(feature = "local")]
+use aurora_workspace_engine::ContractId;
+use aurora_workspace_utils::gas::{GasKind, GasReport};
+use aurora_workspace_utils::transactions::transact_concurrently;
+use near_workspaces::types::Gas;
 use std::str::FromStr;
 
 mod utils;
@@ -30,16 +45,166 @@ mod utils;
 async fn test_deploy_erc20() {
     let contract = utils::deploy_and_init_contract().await.unwrap();
 
+    let nep141 = AccountId::from_str("some_account.test").unwrap();
+
+    let res = contract
+        .deploy_erc20_token(nep141.clone())
+        .transact()
+        .await
+        .unwrap()
+        .into_value();
+
+    let erc20 = contract
+        .get_erc20_from_nep141(nep141.clone())
+        .await
+        .unwrap()
+        .result;
+    let mapped_nep141 = contract.get_nep141_from_erc20(res).await.unwrap().result;
+    assert_eq!(res, erc20);
+    assert_eq!(mapped_nep141, nep141);
+}
+
+#[tokio::test]
+async fn test_deploy_erc20_twice() {
+    let contract = utils::deploy_and_init_contract().await.unwrap();
+    let nep141 = AccountId::from_str("some_account.test").unwrap();
+
+    contract
+        .deploy_erc20_token(nep141.clone())
+        .transact()
+        .await
+        .unwrap();
+    let err = contract
+        .deploy_erc20_token(nep141)
+        .transact()
+        .await
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("ERR_NEP141_TOKEN_ALREADY_REGISTERED"));
+}
+
+#[tokio::test]
+async fn test_deploy_code() {
+    let contract = utils::deploy_and_init_contract().await.unwrap();
+    let code = vec![1u8; 32];
+
     let res = contract
-        .deploy_erc20_token(AccountId::from_str("some_account.test").unwrap())
+        .deploy_code(code.clone())
         .transact()
         .await
         .unwrap()
         .into_value();
+    let address = match res.status {
+        TransactionStatus::Succeed(bytes) => Address::try_from_slice(&bytes).unwrap(),
+        other => panic!("unexpected status: {other:?}"),
+    };
+
+    let deployed = contract.get_code(address).await.unwrap().result;
+    assert_eq!(deployed, code);
+}
+
+#[tokio::test]
+async fn test_call_without_funds() {
+    let contract = utils::deploy_and_init_contract().await.unwrap();
+
+    let res = contract
+        .call(Address::from_array([1u8; 20]), 0.into(), vec![])
+        .transact()
+        .await
+        .unwrap()
+        .into_value();
+    assert_eq!(res.gas_used, 21_000);
+
+    let err = contract
+        .call(Address::from_array([1u8; 20]), 10.into(), vec![])
+        .transact()
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("ERR_OUT_OF_FUND"));
+}
+
+#[test]
+fn test_gas_estimate() {
+    let target = Address::from_array([1u8; 20]);
+    assert_eq!(intrinsic_gas(Some(target), &[]), 21_000);
+    assert_eq!(intrinsic_gas(Some(target), &[0, 1]), 21_020);
+    assert_eq!(intrinsic_gas(None, &[1]), 53_016);
+
+    let result = SubmitResult::new(TransactionStatus::Succeed(vec![]), 21_000, vec![]);
+    let estimate = GasEstimate::from_submit_result(&result, Gas::from_tgas(5).as_gas()).unwrap();
+    assert_eq!(estimate.evm_gas, 21_000);
+    assert_eq!(estimate.near_gas, Gas::from_tgas(5));
+    let err = GasEstimate::from_submit_result(&result, MAX_NEAR_GAS.as_gas() + 1).unwrap_err();
+    assert!(err.to_string().contains("more than the maximum"));
+
+    let reverted = SubmitResult::new(TransactionStatus::Revert(vec![]), 21_000, vec![]);
+    let err = GasEstimate::from_submit_result(&reverted, 0).unwrap_err();
+    assert!(err.to_string().contains("Revert"));
+}
+
+#[cfg(feature = "local")]
+#[tokio::test]
+async fn test_call_auto_gas() {
+    let contract = utils::deploy_and_init_contract().await.unwrap();
+    let sender = near_account_to_evm_address(contract.id());
+    contract
+        .mint_account(sender, 0, 1_000)
+        .transact()
+        .await
+        .unwrap();
 
-    let expected = Address::from_array([1u8; 20]);
+    // The exit moves the value on Aurora and releases it on NEAR by a promise.
+    let estimate = contract
+        .exit_to_near("alice.near", U256::from(400))
+        .estimate_gas()
+        .await
+        .unwrap();
+    // The simulation doesn't send a transaction.
+    let nonce = contract.get_nonce(sender).await.unwrap().result;
+    assert_eq!(nonce, 0.into());
 
-    assert_eq!(res, expected);
+    let result = contract
+        .exit_to_near("alice.near", U256::from(400))
+        .auto_gas()
+        .await
+        .unwrap()
+        .transact()
+        .await
+        .unwrap();
+    assert_eq!(result.value().gas_used, estimate.evm_gas);
+    let outcome = ExitOutcome::new(result);
+    assert!(outcome.is_success());
+    let ft_balance = contract
+        .ft_balance_of(&"alice.near".parse().unwrap())
+        .await
+        .unwrap()
+        .result;
+    assert_eq!(ft_balance.0, 400);
+
+    let err = contract
+        .exit_to_near("alice.near", U256::from(1_000))
+        .auto_gas()
+        .await
+        .err()
+        .unwrap();
+    assert!(err.to_string().contains("ERR_OUT_OF_FUND"));
+}
+
+#[cfg(feature = "local")]
+#[tokio::test]
+async fn test_submit_auto_gas() {
+    let contract = utils::deploy_and_init_contract().await.unwrap();
+    let signer = EvmSigner::from_seed("sender");
+    let transaction = LegacyTransaction::new(0.into(), Some(signer.address()), 0.into(), vec![])
+        .gas_limit(100_000.into());
+    let input = signer.sign(&transaction, 1_313_161_556);
+
+    let submit = contract.submit(input);
+    let estimate = submit.estimate_gas().await.unwrap();
+    let res = submit.auto_gas().await.unwrap().transact().await.unwrap();
+    assert!(res.is_success());
+    assert_eq!(res.value().gas_used, estimate.evm_gas);
 }
 
 // #[tokio::test]
@@ -142,3 +307,80 @@ async fn test_refund_on_error() {
         .await
         .unwrap();
 }
+
+#[tokio::test]
+async fn test_batch_transaction() {
+    let contract = utils::deploy_and_init_contract().await.unwrap();
+
+    let res = contract
+        .batch()
+        .call(contract.register_relayer(Address::from_array([1u8; 20])))
+        .call(contract.factory_update_address_version(Address::zero(), 0))
+        .transact_last(
+            contract.deploy_erc20_token(AccountId::from_str("some_account.test").unwrap()),
+        )
+        .await
+        .unwrap();
+
+    assert!(res.is_success());
+    let erc20 = contract
+        .get_erc20_from_nep141(AccountId::from_str("some_account.test").unwrap())
+        .await
+        .unwrap()
+        .result;
+    assert_eq!(res.into_value(), erc20);
+}
+
+#[tokio::test]
+async fn test_submit_async() {
+    let contract = utils::deploy_and_init_contract().await.unwrap();
+
+    let pending = contract
+        .submit(vec![1u8; 32])
+        .max_gas()
+        .transact_async()
+        .await
+        .unwrap();
+    let res = pending.wait().await.unwrap();
+
+    assert!(res.is_success());
+    assert_eq!(res.into_value().status, TransactionStatus::Succeed(vec![]));
+}
+
+#[tokio::test]
+async fn test_submit_concurrently() {
+    let contract = utils::deploy_and_init_contract().await.unwrap();
+    let calls = (0..3).map(|i| contract.submit(vec![i; 32]).max_gas());
+
+    let results = transact_concurrently(calls).await.unwrap();
+
+    assert_eq!(results.len(), 3);
+    assert!(results.iter().all(|res| res.is_success()));
+}
+
+#[tokio::test]
+async fn test_gas_report() {
+    let contract = utils::deploy_and_init_contract().await.unwrap();
+    let mut report = GasReport::default();
+
+    for i in 0..2 {
+        let res = contract
+            .submit(vec![i; 32])
+            .max_gas()
+            .transact()
+            .await
+            .unwrap();
+        report.record(&res);
+    }
+
+    let stats = report.get(GasKind::Transaction, "submit").unwrap();
+    assert_eq!(stats.count, 2);
+    assert!(stats.min <= stats.avg() && stats.avg() <= stats.max);
+    assert_eq!(report.get(GasKind::Evm, "submit").unwrap().max, 0);
+    // The refunds of the unused gas aren't reported.
+    assert!(report
+        .rows()
+        .iter()
+        .all(|row| row.kind != GasKind::Receipt || row.min > 0));
+    assert!(report.to_string().contains("| transaction | submit | 2 |"));
+}
@@ -1,3 +1,6 @@
+use aurora_engine_types::types::Address;
+use aurora_engine_types::{H256, U256};
+
 mod utils;
 
 #[tokio::test]
@@ -11,7 +14,53 @@ async fn test_version() {
 async fn test_owner() {
     let contract = utils::deploy_and_init_contract().await.unwrap();
     let res = contract.get_owner().await.unwrap().result;
-    assert_eq!(res.as_ref(), "owner.test.near");
+    assert_eq!(res.as_ref(), "owner");
+}
+
+#[tokio::test]
+async fn test_chain_id() {
+    let contract = utils::deploy_and_init_contract().await.unwrap();
+    let res = contract.get_chain_id().await.unwrap().result;
+    assert_eq!(res, U256::from(1313161556u64));
+}
+
+#[tokio::test]
+async fn test_mint_account_balance_and_nonce() {
+    let contract = utils::deploy_and_init_contract().await.unwrap();
+    let address = Address::from_array([7u8; 20]);
+
+    contract
+        .mint_account(address, 3, 1_000)
+        .transact()
+        .await
+        .unwrap();
+
+    let balance = contract.get_balance(address).await.unwrap().result;
+    let nonce = contract.get_nonce(address).await.unwrap().result;
+    let balance_of_eth = contract.ft_balance_of_eth(address).await.unwrap().result;
+    assert_eq!(balance, U256::from(1_000));
+    assert_eq!(nonce, U256::from(3));
+    assert_eq!(balance_of_eth.0, 1_000);
+    assert_eq!(
+        contract
+            .ft_total_eth_supply_on_aurora()
+            .await
+            .unwrap()
+            .result
+            .0,
+        1_000
+    );
+}
+
+#[tokio::test]
+async fn test_storage_at_is_empty() {
+    let contract = utils::deploy_and_init_contract().await.unwrap();
+    let res = contract
+        .get_storage_at(Address::zero(), H256::zero())
+        .await
+        .unwrap()
+        .result;
+    assert_eq!(res, H256::zero());
 }
 
 // #[tokio::test]
@@ -0,0 +1,200 @@
+use aurora_engine_types::types::Address;
+use aurora_engine_types::U256;
+use aurora_workspace_engine::exit::{exit_to_near_address, ExitCall, ExitOutcome, FtTransfer};
+use aurora_workspace_engine::signer::EvmSigner;
+use aurora_workspace_engine::types::{near_account_to_evm_address, to_aurora_account_id};
+use aurora_workspace_engine::ContractId;
+use near_sdk::json_types::U128;
+
+mod utils;
+
+#[test]
+fn test_exit_call_inputs() {
+    let exit = ExitCall::eth_to_near("alice.near", U256::from(10));
+    assert_eq!(exit.to, exit_to_near_address());
+    assert_eq!(exit.value, U256::from(10));
+    assert_eq!(exit.input, b"\0alice.near");
+
+    let token = Address::from_array([1u8; 20]);
+    let exit = ExitCall::erc20_to_near(token, "alice.near", U256::from(10));
+    assert_eq!(exit.to, token);
+    assert_eq!(exit.value, U256::zero());
+    assert_eq!(exit.input.len(), 4 + 4 * 32);
+    assert_eq!(exit.input[..4], [0x6b, 0x35, 0x18, 0x48]);
+    assert_eq!(exit.input[4 + 31], 64);
+    assert_eq!(exit.input[4 + 63], 10);
+    assert_eq!(exit.input[4 + 95], 10);
+    assert_eq!(&exit.input[4 + 96..4 + 106], b"alice.near");
+
+    let recipient = Address::from_array([2u8; 20]);
+    let exit = ExitCall::erc20_to_ethereum(token, recipient, U256::from(10));
+    assert_eq!(exit.input.len(), 4 + 2 * 32);
+    assert_eq!(exit.input[..4], [0x8d, 0x32, 0xca, 0xf4]);
+    assert_eq!(&exit.input[4 + 12..4 + 32], recipient.as_bytes());
+    assert_eq!(exit.input[4 + 63], 10);
+}
+
+#[tokio::test]
+async fn test_exit_to_near() {
+    let contract = utils::deploy_and_init_contract().await.unwrap();
+    let sender = near_account_to_evm_address(contract.id());
+    contract
+        .mint_account(sender, 0, 1_000)
+        .transact()
+        .await
+        .unwrap();
+
+    let result = contract
+        .exit_to_near("alice.near", U256::from(400))
+        .max_gas()
+        .transact()
+        .await
+        .unwrap();
+    let outcome = ExitOutcome::new(result);
+    assert!(outcome.is_success());
+    assert_eq!(
+        outcome.ft_transfers(),
+        vec![FtTransfer {
+            token_id: contract.id().clone(),
+            old_owner_id: contract.id().clone(),
+            new_owner_id: "alice.near".parse().unwrap(),
+            amount: U128(400),
+            memo: None,
+        }]
+    );
+
+    let balance = contract.get_balance(sender).await.unwrap().result;
+    assert_eq!(balance, U256::from(600));
+    let ft_balance = contract
+        .ft_balance_of(&"alice.near".parse().unwrap())
+        .await
+        .unwrap()
+        .result;
+    assert_eq!(ft_balance.0, 400);
+}
+
+#[tokio::test]
+async fn test_exit_to_near_receipt_methods() {
+    let (contract, worker) = utils::deploy_and_init_contract_with_worker().await.unwrap();
+    let sender = near_account_to_evm_address(contract.id());
+    contract
+        .mint_account(sender, 0, 1_000)
+        .transact()
+        .await
+        .unwrap();
+
+    let result = contract
+        .exit_to_near("alice.near", U256::from(400))
+        .max_gas()
+        .transact()
+        .await
+        .unwrap();
+    let mut tree = result.receipt_tree();
+    assert!(tree.find(contract.id(), "ft_transfer").is_none());
+
+    tree.resolve_methods(&worker).await.unwrap();
+    assert!(tree.find(contract.id(), "call").is_some(), "{tree}");
+    assert!(tree.find(contract.id(), "ft_transfer").is_some(), "{tree}");
+}
+
+#[tokio::test]
+async fn test_exit_to_ethereum() {
+    let contract = utils::deploy_and_init_contract().await.unwrap();
+    let sender = near_account_to_evm_address(contract.id());
+    contract
+        .mint_account(sender, 0, 1_000)
+        .transact()
+        .await
+        .unwrap();
+
+    let recipient = Address::from_array([2u8; 20]);
+    let outcome: ExitOutcome = contract
+        .exit_to_ethereum(recipient, U256::from(400))
+        .max_gas()
+        .transact()
+        .await
+        .unwrap()
+        .into();
+    assert!(outcome.is_success());
+    assert!(outcome.ft_transfers().is_empty());
+
+    let withdraw = outcome.withdraw_result(contract.id()).unwrap();
+    assert_eq!(withdraw.amount.as_u128(), 400);
+    assert_eq!(withdraw.recipient_id, recipient);
+    let total_supply = contract.ft_total_supply().await.unwrap().result;
+    assert_eq!(total_supply.0, 0);
+}
+
+#[tokio::test]
+async fn test_exit_signed() {
+    let contract = utils::deploy_and_init_contract().await.unwrap();
+    let signer = EvmSigner::from_seed("sender");
+    contract
+        .mint_account(signer.address(), 0, 1_000)
+        .transact()
+        .await
+        .unwrap();
+    let chain_id = contract.get_chain_id().await.unwrap().result.as_u64();
+
+    let exit = ExitCall::eth_to_near("alice.near", U256::from(400));
+    let outcome: ExitOutcome = contract
+        .exit_signed(&signer, U256::zero(), chain_id, exit)
+        .max_gas()
+        .transact()
+        .await
+        .unwrap()
+        .into();
+    assert!(outcome.is_success());
+    assert_eq!(outcome.ft_transfers().len(), 1);
+    assert_eq!(outcome.ft_transfers()[0].amount, U128(400));
+
+    let balance = contract.get_balance(signer.address()).await.unwrap().result;
+    assert_eq!(balance, U256::from(600));
+    let nonce = contract.get_nonce(signer.address()).await.unwrap().result;
+    assert_eq!(nonce, U256::one());
+}
+
+#[tokio::test]
+async fn test_withdraw_erc20() {
+    let contract = utils::deploy_and_init_contract().await.unwrap();
+    // The engine's own NEP-141 is bridged, so the mock releases it by `ft_transfer` and
+    // `withdraw` of the engine.
+    let token = contract
+        .deploy_erc20_token(to_aurora_account_id(contract.id()))
+        .transact()
+        .await
+        .unwrap()
+        .into_value();
+
+    let outcome: ExitOutcome = contract
+        .withdraw_to_near(token, "alice.near", U256::from(300))
+        .max_gas()
+        .transact()
+        .await
+        .unwrap()
+        .into();
+    assert!(outcome.is_success());
+    assert_eq!(
+        outcome.ft_transfers(),
+        vec![FtTransfer {
+            token_id: contract.id().clone(),
+            old_owner_id: contract.id().clone(),
+            new_owner_id: "alice.near".parse().unwrap(),
+            amount: U128(300),
+            memo: None,
+        }]
+    );
+
+    let recipient = Address::from_array([2u8; 20]);
+    let outcome: ExitOutcome = contract
+        .withdraw_to_ethereum(token, recipient, U256::from(200))
+        .max_gas()
+        .transact()
+        .await
+        .unwrap()
+        .into();
+    assert!(outcome.is_success());
+    let withdraw = outcome.withdraw_result(contract.id()).unwrap();
+    assert_eq!(withdraw.amount.as_u128(), 200);
+    assert_eq!(withdraw.recipient_id, recipient);
+}
@@ -0,0 +1,144 @@
+#![cfg(feature = "mock")]
+use aurora_engine_types::account_id::AccountId;
+use aurora_engine_types::parameters::engine::TransactionStatus;
+use aurora_engine_types::types::Address;
+use aurora_workspace_engine::mock::Fault;
+use near_workspaces::types::NearToken;
+use std::str::FromStr;
+
+mod utils;
+
+#[tokio::test]
+async fn test_submit_revert() {
+    let contract = utils::deploy_and_init_contract().await.unwrap();
+    contract
+        .set_fault("submit", Fault::Revert(vec![1, 2, 3]))
+        .transact()
+        .await
+        .unwrap();
+
+    let res = contract
+        .submit(vec![1u8; 32])
+        .max_gas()
+        .transact()
+        .await
+        .unwrap()
+        .into_value();
+    assert_eq!(res.status, TransactionStatus::Revert(vec![1, 2, 3]));
+}
+
+#[tokio::test]
+async fn test_call_out_of_gas() {
+    let contract = utils::deploy_and_init_contract().await.unwrap();
+    contract
+        .set_fault("call", Fault::OutOfGas)
+        .transact()
+        .await
+        .unwrap();
+
+    let res = contract
+        .call(Address::from_array([1u8; 20]), 0.into(), vec![])
+        .transact()
+        .await
+        .unwrap()
+        .into_value();
+    assert_eq!(res.status, TransactionStatus::OutOfGas);
+}
+
+#[tokio::test]
+async fn test_panic_and_clear_fault() {
+    let contract = utils::deploy_and_init_contract().await.unwrap();
+    let nep141 = AccountId::from_str("some_account.test").unwrap();
+    contract
+        .set_fault(
+            "deploy_erc20_token",
+            Fault::Panic("ERR_GETTING_ERC20_FROM_NEP141".to_string()),
+        )
+        .transact()
+        .await
+        .unwrap();
+
+    let err = contract
+        .deploy_erc20_token(nep141.clone())
+        .transact()
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("ERR_GETTING_ERC20_FROM_NEP141"));
+
+    contract
+        .clear_fault("deploy_erc20_token")
+        .transact()
+        .await
+        .unwrap();
+    let res = contract
+        .deploy_erc20_token(nep141)
+        .transact()
+        .await
+        .unwrap();
+    assert!(res.is_success());
+}
+
+#[tokio::test]
+async fn test_status_fault_in_method_without_submit_result() {
+    let contract = utils::deploy_and_init_contract().await.unwrap();
+    contract
+        .set_fault("register_relayer", Fault::Revert(vec![]))
+        .transact()
+        .await
+        .unwrap();
+
+    let err = contract
+        .register_relayer(Address::from_array([1u8; 20]))
+        .transact()
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("ERR_REVERT"));
+}
+
+#[tokio::test]
+async fn test_burn_gas() {
+    let contract = utils::deploy_and_init_contract().await.unwrap();
+    let before = contract
+        .submit(vec![1u8; 32])
+        .max_gas()
+        .transact()
+        .await
+        .unwrap()
+        .total_gas_burnt();
+    contract
+        .set_fault("submit", Fault::BurnGas(20_000_000_000_000))
+        .transact()
+        .await
+        .unwrap();
+
+    let after = contract
+        .submit(vec![1u8; 32])
+        .max_gas()
+        .transact()
+        .await
+        .unwrap()
+        .total_gas_burnt();
+    assert!(after.as_gas() >= before.as_gas() + 20_000_000_000_000);
+}
+
+#[tokio::test]
+async fn test_set_fault_by_non_owner() {
+    let contract = utils::deploy_and_init_contract().await.unwrap();
+    let user = contract
+        .root()
+        .create_subaccount("user")
+        .initial_balance(NearToken::from_near(10))
+        .transact()
+        .await
+        .unwrap()
+        .into_result()
+        .unwrap();
+
+    let err = contract
+        .as_account(&user)
+        .set_fault("submit", Fault::OutOfGas)
+        .transact()
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("ERR_NOT_ALLOWED"));
+}
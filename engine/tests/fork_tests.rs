@@ -0,0 +1,106 @@
+use aurora_engine_types::storage::{address_to_key, bytes_to_key, KeyPrefix};
+use aurora_engine_types::types::Address;
+use aurora_workspace_engine::fork::StateItem;
+use aurora_workspace_engine::{ContractId, EngineForkBuilder};
+use aurora_workspace_utils::compile::compile_project;
+
+fn item(key: &[u8], value: &[u8]) -> StateItem {
+    StateItem {
+        key: key.to_vec().into(),
+        value: value.to_vec().into(),
+    }
+}
+
+#[test]
+fn test_fork_state_filters() {
+    let first = Address::from_array([1u8; 20]);
+    let second = Address::from_array([2u8; 20]);
+    let state = vec![
+        item(&bytes_to_key(KeyPrefix::Config, b"owner"), b"aurora"),
+        item(&address_to_key(KeyPrefix::Balance, &first), &[1]),
+        item(&address_to_key(KeyPrefix::Balance, &second), &[2]),
+        item(&address_to_key(KeyPrefix::Nonce, &second), &[3]),
+    ];
+
+    let fork = EngineForkBuilder::new()
+        .unwrap()
+        .with_state(state.clone())
+        .with_addresses(&[second]);
+    let filtered = fork.filtered_state();
+    assert_eq!(filtered, vec![&state[0], &state[2], &state[3]]);
+
+    let fork = fork.with_prefixes(&[KeyPrefix::Balance]);
+    assert_eq!(fork.filtered_state(), vec![&state[2]]);
+}
+
+#[tokio::test]
+async fn test_fork_deploy() {
+    let path = std::env::temp_dir().join(format!(
+        "aurora-workspace-fork-state-{}.json",
+        std::process::id()
+    ));
+    std::fs::write(
+        &path,
+        r#"{"values":[{"key":"AAE=","value":"AQ=="}],"proof":[]}"#,
+    )
+    .unwrap();
+
+    let worker = near_workspaces::sandbox().await.unwrap();
+    let contract = EngineForkBuilder::new()
+        .unwrap()
+        .with_code_file(compile_project("../res/mock_engine"))
+        .unwrap()
+        .with_state_file(&path)
+        .unwrap()
+        .deploy_to(&worker)
+        .await
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(contract.id().as_str(), "aurora");
+
+    // The patched key is in the state before the engine is initialised.
+    let state = worker.view_state(contract.id()).await.unwrap();
+    assert_eq!(state.get([0u8, 1].as_slice()), Some(&vec![1u8]));
+
+    contract
+        .new([0u8; 32], "owner".parse().unwrap(), 1)
+        .transact()
+        .await
+        .unwrap();
+    assert_eq!(contract.get_owner().await.unwrap().result.as_ref(), "owner");
+}
+
+#[tokio::test]
+async fn test_fork_deploy_filtered() {
+    let first = Address::from_array([1u8; 20]);
+    let second = Address::from_array([2u8; 20]);
+    let config = bytes_to_key(KeyPrefix::Config, b"owner");
+    let first_balance = address_to_key(KeyPrefix::Balance, &first);
+    let second_balance = address_to_key(KeyPrefix::Balance, &second);
+    let second_nonce = address_to_key(KeyPrefix::Nonce, &second);
+    let state = vec![
+        item(&config, b"aurora"),
+        item(&first_balance, &[1]),
+        item(&second_balance, &[2]),
+        item(&second_nonce, &[3]),
+    ];
+
+    let worker = near_workspaces::sandbox().await.unwrap();
+    let contract = EngineForkBuilder::new()
+        .unwrap()
+        .with_code_file(compile_project("../res/mock_engine"))
+        .unwrap()
+        .with_state(state)
+        .with_prefixes(&[KeyPrefix::Config, KeyPrefix::Balance])
+        .with_addresses(&[second])
+        .deploy_to(&worker)
+        .await
+        .unwrap();
+
+    let state = worker.view_state(contract.id()).await.unwrap();
+    assert_eq!(state.get(config.as_slice()), Some(&b"aurora".to_vec()));
+    assert_eq!(state.get(second_balance.as_slice()), Some(&vec![2u8]));
+    // Filtered out by the address and by the prefix.
+    assert!(!state.contains_key(first_balance.as_slice()));
+    assert!(!state.contains_key(second_nonce.as_slice()));
+}
@@ -0,0 +1,19 @@
+use aurora_workspace_utils::gas::GAS_REPORT_ENV;
+
+mod utils;
+
+#[tokio::test]
+async fn test_gas_report_write_error() {
+    let contract = utils::deploy_and_init_contract().await.unwrap();
+    // A directory can't be opened for appending, so writing the report fails.
+    std::env::set_var(GAS_REPORT_ENV, std::env::temp_dir());
+
+    let res = contract
+        .submit(vec![1; 32])
+        .max_gas()
+        .transact()
+        .await
+        .unwrap();
+    assert!(res.is_success());
+    assert_eq!(res.evm_gas(), Some(0));
+}
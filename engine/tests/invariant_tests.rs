@@ -0,0 +1,112 @@
+use aurora_engine_types::U256;
+use aurora_workspace_engine::invariant::{
+    ConnectorSnapshot, EthBalance, Invariant, InvariantChecker, SupplySnapshot,
+};
+use aurora_workspace_engine::types::{
+    near_account_to_evm_address, to_aurora_account_id, AccountId, Address,
+};
+use aurora_workspace_engine::ContractId;
+
+mod utils;
+
+fn account(account_id: &str) -> AccountId {
+    account_id.parse().unwrap()
+}
+
+fn consistent_snapshot() -> SupplySnapshot {
+    SupplySnapshot {
+        engine_id: account("aurora"),
+        ft_total_supply: 300,
+        ft_total_eth_supply_on_near: 300,
+        ft_total_eth_supply_on_aurora: 100,
+        ft_balances: vec![(account("alice.near"), 200), (account("aurora"), 100)],
+        eth_balances: vec![EthBalance {
+            address: Address::from_array([1; 20]),
+            ft_balance_of_eth: 100,
+            balance: U256::from(100),
+        }],
+        connector: Some(ConnectorSnapshot {
+            ft_total_supply: 300,
+            ft_balances: vec![(account("alice.near"), 200), (account("aurora"), 100)],
+        }),
+    }
+}
+
+#[test]
+fn test_supply_snapshot_check() {
+    let mut snapshot = consistent_snapshot();
+    snapshot.check().assert_ok();
+
+    snapshot.ft_balances[0].1 = 190;
+    let report = snapshot.check();
+    assert!(!report.is_ok());
+    let violations = report.violations_of(Invariant::EngineBalances);
+    assert_eq!(violations[0].expected, U256::from(300));
+    assert_eq!(violations[0].actual, U256::from(290));
+    assert_eq!(
+        violations[0].offenders,
+        vec!["alice.near: 190".to_string(), "aurora: 100".to_string()]
+    );
+    let violations = report.violations_of(Invariant::BridgedBalances);
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].offenders, vec!["alice.near".to_string()]);
+    assert!(report
+        .to_string()
+        .contains("engine and connector balances differ"));
+
+    let mut snapshot = consistent_snapshot();
+    snapshot.connector = None;
+    snapshot.ft_total_supply = 310;
+    snapshot.eth_balances[0].ft_balance_of_eth = 90;
+    let report = snapshot.check();
+    let invariants: Vec<_> = report.violations.iter().map(|v| v.invariant).collect();
+    assert_eq!(
+        invariants,
+        vec![
+            Invariant::EngineSupplyOnNear,
+            Invariant::EngineBalances,
+            Invariant::EthBalanceViews
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_invariant_checker() {
+    let contract = utils::deploy_and_init_contract().await.unwrap();
+    let engine_id = to_aurora_account_id(contract.id());
+    let sender = near_account_to_evm_address(contract.id());
+    contract
+        .mint_account(sender, 0, 1_000)
+        .transact()
+        .await
+        .unwrap();
+    contract
+        .exit_to_near("alice.near", U256::from(400))
+        .max_gas()
+        .transact()
+        .await
+        .unwrap();
+
+    let checker = InvariantChecker::new(&contract)
+        .account(engine_id)
+        .account(account("alice.near"))
+        .address(sender);
+    let snapshot = checker.snapshot().await.unwrap();
+    assert_eq!(snapshot.ft_total_supply, 400);
+    assert_eq!(snapshot.ft_total_eth_supply_on_aurora, 600);
+    snapshot.check().assert_ok();
+
+    // The ETH of the unknown address breaks the sum of the EVM balances.
+    let unknown = Address::from_array([9; 20]);
+    contract
+        .mint_account(unknown, 0, 50)
+        .transact()
+        .await
+        .unwrap();
+    let report = checker.check().await.unwrap();
+    let violations = report.violations_of(Invariant::EthBalances);
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].expected, U256::from(650));
+    assert_eq!(violations[0].actual, U256::from(600));
+    checker.address(unknown).check().await.unwrap().assert_ok();
+}
@@ -0,0 +1,57 @@
+#![cfg(feature = "local")]
+
+use aurora_workspace_engine::types::{EnginePausedFlags, LocalContract, LocalRuntime};
+use aurora_workspace_engine::{ContractId, EngineContract, EngineContractBuilder};
+use aurora_workspace_utils::compile::compile_project;
+use near_workspaces::types::NearToken;
+
+async fn deploy_and_init_local() -> anyhow::Result<EngineContract<LocalContract>> {
+    let wasm = std::fs::read(compile_project("../res/mock_engine"))?;
+    let runtime = LocalRuntime::new();
+    EngineContractBuilder::new()?
+        .with_code(wasm)
+        .deploy_and_init_local(&runtime)
+        .await
+}
+
+#[tokio::test]
+async fn test_local_init() {
+    let contract = deploy_and_init_local().await.unwrap();
+    assert_eq!(contract.id().as_str(), "aurora.root");
+    assert_eq!(
+        contract.get_owner().await.unwrap().result.as_ref(),
+        "aurora.root"
+    );
+}
+
+#[tokio::test]
+async fn test_local_set_paused_flags() {
+    let contract = deploy_and_init_local().await.unwrap();
+    let result = contract
+        .set_paused_flags(EnginePausedFlags::PAUSE_DEPOSIT)
+        .transact_local()
+        .unwrap();
+    assert!(result.is_success());
+    assert!(result.total_gas_burnt().as_gas() > 0);
+    assert_eq!(
+        contract.get_paused_flags().await.unwrap().result,
+        EnginePausedFlags::PAUSE_DEPOSIT
+    );
+}
+
+#[tokio::test]
+async fn test_local_call_by_non_owner() {
+    let contract = deploy_and_init_local().await.unwrap();
+    let user = contract
+        .root()
+        .create_subaccount("user", NearToken::from_near(10))
+        .unwrap();
+
+    let err = contract
+        .as_local_account(&user)
+        .set_paused_flags(EnginePausedFlags::PAUSE_DEPOSIT)
+        .transact_local()
+        .unwrap_err();
+    assert!(err.to_string().contains("ERR_NOT_ALLOWED"));
+    assert!(contract.get_paused_flags().await.unwrap().result.is_empty());
+}
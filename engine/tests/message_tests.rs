@@ -0,0 +1,104 @@
+use aurora_engine_types::U256;
+use aurora_workspace_engine::types::{
+    near_account_to_evm_address, to_aurora_account_id, Address, FtTransferMessage,
+};
+use aurora_workspace_engine::ContractId;
+use near_sdk::json_types::U128;
+use near_workspaces::types::NearToken;
+
+mod utils;
+
+#[test]
+fn test_ft_transfer_message() {
+    let recipient = Address::from_array([0xab; 20]);
+    let message = FtTransferMessage::new(recipient);
+    assert_eq!(message.to_string(), "ab".repeat(20));
+    assert_eq!(message.fee(), 0);
+    assert_eq!(
+        message.to_string().parse::<FtTransferMessage>().unwrap(),
+        message
+    );
+
+    let message = FtTransferMessage::with_fee(recipient, "relayer.near".parse().unwrap(), 0x0102);
+    let expected = format!("relayer.near:0201{}{}", "00".repeat(30), "ab".repeat(20));
+    assert_eq!(message.to_string(), expected);
+    assert_eq!(message.fee(), 0x0102);
+    assert_eq!(expected.parse::<FtTransferMessage>().unwrap(), message);
+
+    let overflow = format!("relayer.near:{}01{}", "00".repeat(31), "ab".repeat(20));
+    assert!(overflow.parse::<FtTransferMessage>().is_err());
+    assert!(format!("relayer.near:{}", "ab".repeat(20))
+        .parse::<FtTransferMessage>()
+        .is_err());
+    assert!("ab".repeat(19).parse::<FtTransferMessage>().is_err());
+}
+
+#[tokio::test]
+async fn test_deposit_to_aurora() {
+    let contract = utils::deploy_and_init_contract().await.unwrap();
+    let user = contract
+        .root()
+        .create_subaccount("user")
+        .initial_balance(NearToken::from_near(10))
+        .transact()
+        .await
+        .unwrap()
+        .into_result()
+        .unwrap();
+    contract
+        .mint_account(near_account_to_evm_address(contract.id()), 0, 1_000)
+        .transact()
+        .await
+        .unwrap();
+    contract
+        .exit_to_near(user.id().as_str(), U256::from(500))
+        .max_gas()
+        .transact()
+        .await
+        .unwrap();
+
+    let engine_id = to_aurora_account_id(contract.id());
+    let user_contract = contract.as_account(&user);
+    let recipient = Address::from_array([1; 20]);
+    user_contract
+        .deposit_to_aurora(&engine_id, U128(100), recipient)
+        .unwrap()
+        .max_gas()
+        .transact()
+        .await
+        .unwrap();
+    let balance = contract.get_balance(recipient).await.unwrap().result;
+    assert_eq!(balance, U256::from(100));
+
+    let relayer_id = "relayer.near".parse().unwrap();
+    let message = FtTransferMessage::with_fee(recipient, relayer_id, 30);
+    user_contract
+        .deposit_to_aurora_with_message(&engine_id, U128(200), &message)
+        .unwrap()
+        .max_gas()
+        .transact()
+        .await
+        .unwrap();
+    let balance = contract.get_balance(recipient).await.unwrap().result;
+    assert_eq!(balance, U256::from(270));
+    let relayer = near_account_to_evm_address("relayer.near");
+    let balance = contract.get_balance(relayer).await.unwrap().result;
+    assert_eq!(balance, U256::from(30));
+
+    let message = FtTransferMessage::with_fee(recipient, "relayer.near".parse().unwrap(), 300);
+    let err = user_contract
+        .deposit_to_aurora_with_message(&engine_id, U128(200), &message)
+        .unwrap()
+        .max_gas()
+        .transact()
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("ERR_NOT_ENOUGH_BALANCE_FOR_FEE"));
+
+    let ft_balance = contract
+        .ft_balance_of(&to_aurora_account_id(user.id()))
+        .await
+        .unwrap()
+        .result;
+    assert_eq!(ft_balance.0, 200);
+}
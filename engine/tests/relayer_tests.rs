@@ -0,0 +1,69 @@
+use aurora_workspace_engine::relayer::RelayerHelper;
+use aurora_workspace_engine::types::EnginePausedFlags;
+use near_workspaces::types::NearToken;
+use near_workspaces::Account;
+
+mod utils;
+
+async fn create_account(root: &Account, name: &str) -> Account {
+    root.create_subaccount(name)
+        .initial_balance(NearToken::from_near(10))
+        .transact()
+        .await
+        .unwrap()
+        .into_result()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_relayer_key_workflow() {
+    let contract = utils::deploy_and_init_contract().await.unwrap();
+    let manager = create_account(contract.root(), "manager").await;
+    let relayers = RelayerHelper::new(&contract);
+    relayers.set_key_manager(&manager).await.unwrap();
+
+    let key = relayers.add_key(&manager).await.unwrap();
+    assert!(relayers.has_key(&key).await.unwrap());
+    assert_eq!(key.account().id(), contract.root().id());
+
+    let result = key
+        .engine()
+        .submit(vec![1, 2, 3])
+        .max_gas()
+        .transact()
+        .await
+        .unwrap();
+    assert!(result.is_success());
+
+    // The key is limited to the submit methods.
+    assert!(key
+        .engine()
+        .set_paused_flags(EnginePausedFlags::PAUSE_DEPOSIT)
+        .transact()
+        .await
+        .is_err());
+
+    relayers.remove_key(&manager, &key).await.unwrap();
+    assert!(!relayers.has_key(&key).await.unwrap());
+    assert!(key
+        .engine()
+        .submit(vec![1, 2, 3])
+        .max_gas()
+        .transact()
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_add_relayer_key_by_non_manager() {
+    let contract = utils::deploy_and_init_contract().await.unwrap();
+    let manager = create_account(contract.root(), "manager").await;
+    let user = create_account(contract.root(), "user").await;
+    let relayers = RelayerHelper::new(&contract);
+    relayers.set_key_manager(&manager).await.unwrap();
+
+    let err = relayers.add_key(&user).await.unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("ERR_KEY_MANAGER_IS_NOT_SET_OR_NOT_ALLOWED"));
+}
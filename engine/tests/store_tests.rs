@@ -0,0 +1,94 @@
+use aurora_engine_types::H256;
+use aurora_workspace_engine::hash::keccak;
+use aurora_workspace_engine::store::{transaction_hash, EvmLog, LogFilter, TransactionStore};
+use aurora_workspace_engine::types::{Address, TransactionStatus};
+
+mod utils;
+
+fn log(block_height: u64, address: Address, topics: Vec<H256>) -> EvmLog {
+    EvmLog {
+        address,
+        topics,
+        data: vec![],
+        block_height,
+        tx_hash: H256::zero(),
+        log_index: 0,
+    }
+}
+
+#[test]
+fn test_log_filter() {
+    let address = Address::from_array([1; 20]);
+    let topic = H256::repeat_byte(2);
+    let other = H256::repeat_byte(3);
+    let log = log(5, address, vec![topic, other]);
+
+    assert!(LogFilter::new().matches(&log));
+    assert!(LogFilter::new().from_block(5).to_block(5).matches(&log));
+    assert!(!LogFilter::new().from_block(6).matches(&log));
+    assert!(!LogFilter::new().to_block(4).matches(&log));
+    assert!(LogFilter::new().address(address).matches(&log));
+    assert!(!LogFilter::new().address(Address::zero()).matches(&log));
+    assert!(LogFilter::new()
+        .address(Address::zero())
+        .address(address)
+        .matches(&log));
+    assert!(LogFilter::new().topic(0, topic).matches(&log));
+    assert!(LogFilter::new().topic(1, other).matches(&log));
+    assert!(!LogFilter::new().topic(0, other).matches(&log));
+    assert!(LogFilter::new()
+        .topic(0, other)
+        .topic(0, topic)
+        .matches(&log));
+    assert!(!LogFilter::new().topic(2, topic).matches(&log));
+}
+
+#[tokio::test]
+async fn test_transaction_store() {
+    let (contract, worker) = utils::deploy_and_init_contract_with_worker().await.unwrap();
+    let mut store = TransactionStore::new().with_worker(worker.clone());
+    assert_eq!(store.block_height(), 0);
+
+    let first = store.submit(&contract, vec![1; 32]).await.unwrap().clone();
+    let second = store.submit(&contract, vec![2; 32]).await.unwrap().clone();
+
+    assert_eq!(store.block_height(), 2);
+    assert_eq!(first.tx_hash, transaction_hash(&[1; 32]));
+    assert_eq!(first.block_height, 1);
+    assert_eq!(second.block_height, 2);
+    assert_ne!(first.near_tx_hash, second.near_tx_hash);
+    let near_block = worker
+        .view_block()
+        .block_hash(first.near_block_hash.unwrap())
+        .await
+        .unwrap();
+    assert_eq!(first.near_block_height, Some(near_block.height()));
+    assert!(second.near_block_height > first.near_block_height);
+    assert!(first.is_success());
+    assert_eq!(first.result.status, TransactionStatus::Succeed(vec![]));
+
+    let receipt = store.receipt(&second.tx_hash).unwrap();
+    assert_eq!(receipt.transaction, vec![2; 32]);
+    assert_eq!(store.receipt_by_block(1).unwrap().tx_hash, first.tx_hash);
+    assert!(store.receipt_by_block(3).is_none());
+    assert!(store.receipt(&H256::zero()).is_none());
+
+    // A recorded transaction is rejected without submitting it again.
+    assert!(store.submit(&contract, vec![1; 32]).await.is_err());
+    assert_eq!(store.block_height(), 2);
+    assert_eq!(store.receipt_by_block(1).unwrap().tx_hash, first.tx_hash);
+
+    // The mock engine emits a log with the hash of the input as the topic.
+    let logs = store.logs(&LogFilter::new());
+    assert_eq!(logs.len(), 2);
+    assert_eq!(logs[0].tx_hash, first.tx_hash);
+    assert_eq!(logs[0].topics, vec![keccak(&[1; 32])]);
+    assert_eq!(logs[0].data, vec![1; 32]);
+    assert_eq!(logs[1].block_height, 2);
+
+    let logs = store.logs(&LogFilter::new().topic(0, keccak(&[2; 32])));
+    assert_eq!(logs, vec![&second.logs[0]]);
+    let logs = store.logs(&LogFilter::new().address(second.logs[0].address));
+    assert_eq!(logs, vec![&second.logs[0]]);
+    assert!(store.logs(&LogFilter::new().from_block(3)).is_empty());
+}
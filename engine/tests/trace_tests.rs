@@ -0,0 +1,59 @@
+use aurora_workspace_engine::invariant::InvariantChecker;
+use aurora_workspace_engine::types::EnginePausedFlags;
+use aurora_workspace_engine::ContractId;
+use aurora_workspace_utils::trace::{self, CallKind, Replayer, TraceRecorder};
+
+mod utils;
+
+#[tokio::test]
+async fn test_record_and_replay() {
+    let path = std::env::temp_dir().join(format!(
+        "aurora-workspace-trace-{}.jsonl",
+        std::process::id()
+    ));
+    let contract = utils::deploy_and_init_contract()
+        .await
+        .unwrap()
+        .with_recorder(Some(TraceRecorder::new(&path)));
+
+    contract
+        .set_paused_flags(EnginePausedFlags::PAUSE_DEPOSIT)
+        .transact()
+        .await
+        .unwrap();
+    assert_eq!(
+        contract.get_paused_flags().await.unwrap().result,
+        EnginePausedFlags::PAUSE_DEPOSIT
+    );
+    // The views issued by the helpers aren't recorded.
+    InvariantChecker::new(&contract).check().await.unwrap();
+    // Neither are the calls of the contract without the recorder.
+    contract
+        .with_recorder(None)
+        .set_paused_flags(EnginePausedFlags::PAUSE_DEPOSIT)
+        .transact()
+        .await
+        .unwrap();
+
+    let entries = trace::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].call.kind, CallKind::Call);
+    assert_eq!(entries[0].call.method, "set_paused_flags");
+    assert_eq!(entries[0].call.signer_id.as_ref(), Some(contract.id()));
+    assert!(entries[0].outcome.is_success());
+    assert_eq!(entries[1].call.kind, CallKind::View);
+    assert_eq!(entries[1].call.method, "get_paused_flags");
+
+    let fresh = utils::deploy_and_init_contract().await.unwrap();
+    let replayer = Replayer::new(fresh.as_contract());
+    let report = replayer.replay(&entries[1..]).await.unwrap();
+    assert_eq!(report.diffs.len(), 1);
+    assert_eq!(report.diffs[0].call.method, "get_paused_flags");
+
+    let fresh = utils::deploy_and_init_contract().await.unwrap();
+    let replayer = Replayer::new(fresh.as_contract());
+    let report = replayer.replay(&entries).await.unwrap();
+    assert_eq!(report.replayed, 2);
+    assert!(report.is_clean(), "{report}");
+}
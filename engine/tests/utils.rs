@@ -3,7 +3,9 @@ use std::sync::LazyLock;
 use aurora_workspace_engine::EngineContract;
 use aurora_workspace_utils::{compile::compile_project, Contract};
 use ethereum_types::U256;
+use near_workspaces::network::Sandbox;
 use near_workspaces::types::{KeyType, SecretKey};
+use near_workspaces::Worker;
 
 const AURORA_LOCAL_CHAIN_ID: u64 = 1313161556;
 const AURORA_ACCOUNT_ID: &str = "aurora";
@@ -17,6 +19,13 @@ pub static CONTRACT_WASM: LazyLock<Vec<u8>> = LazyLock::new(|| {
 });
 
 pub async fn deploy_and_init_contract() -> anyhow::Result<EngineContract> {
+    let (engine_contract, _) = deploy_and_init_contract_with_worker().await?;
+    Ok(engine_contract)
+}
+
+/// Same as `deploy_and_init_contract`, also returns the worker of the sandbox.
+pub async fn deploy_and_init_contract_with_worker(
+) -> anyhow::Result<(EngineContract, Worker<Sandbox>)> {
     let worker = near_workspaces::sandbox()
         .await
         .map_err(|err| anyhow::anyhow!("Failed init sandbox: {:?}", err))?;
@@ -40,7 +49,7 @@ pub async fn deploy_and_init_contract() -> anyhow::Result<EngineContract> {
         .await
         .unwrap();
 
-    Ok(engine_contract)
+    Ok((engine_contract, worker))
 }
 
 fn into_chain_id(value: u64) -> [u8; 32] {
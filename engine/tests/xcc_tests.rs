@@ -0,0 +1,195 @@
+use aurora_engine_types::parameters::engine::TransactionStatus;
+use aurora_engine_types::parameters::{PromiseArgs, PromiseCreateArgs};
+use aurora_engine_types::types::{Address, NearGas, Yocto};
+use aurora_workspace_engine::types::{near_account_to_evm_address, to_aurora_account_id};
+use aurora_workspace_engine::xcc::{router_account_id, WNearFunding, XccHelper};
+use aurora_workspace_engine::{ContractId, EngineContract};
+use aurora_workspace_utils::compile::compile_project;
+use near_workspaces::types::NearToken;
+use near_workspaces::AccountId;
+use serde_json::json;
+use std::sync::LazyLock;
+
+mod utils;
+
+/// The mock eth-connector is a plain NEP-141 token, so it stands in for wNEAR.
+static WNEAR_WASM: LazyLock<Vec<u8>> = LazyLock::new(|| {
+    let wasm_path = compile_project("../res/mock_eth_connector");
+    std::fs::read(wasm_path).unwrap()
+});
+
+const WNEAR_AMOUNT: NearToken = NearToken::from_near(1);
+
+/// Deploys the wNEAR token and mints the wNEAR to a new funder account.
+async fn deploy_wnear(contract: &EngineContract) -> WNearFunding {
+    let root = contract.root();
+    let token = root
+        .create_subaccount("wnear")
+        .initial_balance(NearToken::from_near(10))
+        .transact()
+        .await
+        .unwrap()
+        .into_result()
+        .unwrap()
+        .deploy(&WNEAR_WASM)
+        .await
+        .unwrap()
+        .into_result()
+        .unwrap();
+    token
+        .call("new")
+        .args_json(json!({
+            "metadata": {
+                "spec": "ft-1.0.0",
+                "name": "Wrapped NEAR",
+                "symbol": "wNEAR",
+                "decimals": 24,
+            },
+            "aurora_engine_account_id": contract.id(),
+            "owner_id": root.id(),
+            "controller": root.id(),
+        }))
+        .transact()
+        .await
+        .unwrap()
+        .into_result()
+        .unwrap();
+
+    let funder = root
+        .create_subaccount("funder")
+        .initial_balance(NearToken::from_near(5))
+        .transact()
+        .await
+        .unwrap()
+        .into_result()
+        .unwrap();
+    root.call(token.id(), "mint")
+        .args_json(json!({
+            "account_id": funder.id(),
+            "amount": WNEAR_AMOUNT.as_yoctonear().to_string(),
+        }))
+        .transact()
+        .await
+        .unwrap()
+        .into_result()
+        .unwrap();
+
+    WNearFunding {
+        token_id: token.id().clone(),
+        funder,
+        amount: WNEAR_AMOUNT,
+    }
+}
+
+/// Sets up the XCC with the mock engine as the router code and funds the router of the
+/// engine account, which calls the precompile.
+async fn setup_router(contract: &EngineContract) -> (XccHelper, AccountId) {
+    let xcc = XccHelper::new(contract);
+    let wnear = deploy_wnear(contract).await;
+    xcc.setup(utils::CONTRACT_WASM.clone(), &wnear.token_id)
+        .await
+        .unwrap();
+    let sender = near_account_to_evm_address(contract.id());
+    let router_id = xcc
+        .fund_router(sender, Some(&wnear), NearToken::from_near(5))
+        .await
+        .unwrap();
+    (xcc, router_id)
+}
+
+fn ping_engine(contract: &EngineContract) -> PromiseArgs {
+    PromiseArgs::Create(PromiseCreateArgs {
+        target_account_id: to_aurora_account_id(contract.id()),
+        method: "ft_total_supply".to_string(),
+        args: Vec::new(),
+        attached_balance: Yocto::new(0),
+        attached_gas: NearGas::new(5_000_000_000_000),
+    })
+}
+
+#[test]
+fn test_router_account_id() {
+    let address = Address::from_array([0xab; 20]);
+    let router_id = router_account_id(&address, &"aurora".parse().unwrap()).unwrap();
+    assert_eq!(
+        router_id.as_str(),
+        "abababababababababababababababababababab.aurora"
+    );
+}
+
+#[tokio::test]
+async fn test_xcc_setup_and_fund_router() {
+    let contract = utils::deploy_and_init_contract().await.unwrap();
+    let xcc = XccHelper::new(&contract);
+    let wnear = deploy_wnear(&contract).await;
+    let wnear_address = xcc
+        .setup(utils::CONTRACT_WASM.clone(), &wnear.token_id)
+        .await
+        .unwrap();
+    let nep141 = to_aurora_account_id(&wnear.token_id);
+    let registered = contract.get_erc20_from_nep141(nep141).await.unwrap();
+    assert_eq!(registered.result, wnear_address);
+    // The setup can be repeated, the wNEAR token is registered once.
+    let again = xcc
+        .setup(utils::CONTRACT_WASM.clone(), &wnear.token_id)
+        .await
+        .unwrap();
+    assert_eq!(again, wnear_address);
+
+    let target = Address::from_array([3u8; 20]);
+    let router_id = xcc
+        .fund_router(target, Some(&wnear), NearToken::from_near(5))
+        .await
+        .unwrap();
+    assert_eq!(
+        router_id,
+        router_account_id(&target, contract.id()).unwrap()
+    );
+    // The wNEAR of the funder is locked in the engine.
+    let balance = wnear
+        .funder
+        .view(&wnear.token_id, "ft_balance_of")
+        .args_json(json!({ "account_id": contract.id() }))
+        .await
+        .unwrap()
+        .json::<String>()
+        .unwrap();
+    assert_eq!(balance, WNEAR_AMOUNT.as_yoctonear().to_string());
+}
+
+#[tokio::test]
+async fn test_xcc_call() {
+    let contract = utils::deploy_and_init_contract().await.unwrap();
+    let (xcc, router_id) = setup_router(&contract).await;
+
+    let outcome = xcc.call(ping_engine(&contract)).await.unwrap();
+    assert!(outcome.is_success());
+    assert_eq!(
+        outcome.result().value().status,
+        TransactionStatus::Succeed(vec![])
+    );
+    // The router executes the promise, which calls the engine back.
+    let router_receipts = outcome.receipts_of(&router_id);
+    assert_eq!(router_receipts.len(), 1);
+    assert!(router_receipts[0].is_success());
+    assert!(router_receipts[0]
+        .children
+        .iter()
+        .any(|node| &node.executor_id == contract.id() && node.is_success()));
+}
+
+#[tokio::test]
+async fn test_xcc_schedule() {
+    let contract = utils::deploy_and_init_contract().await.unwrap();
+    let (xcc, router_id) = setup_router(&contract).await;
+
+    let outcome = xcc.schedule(ping_engine(&contract)).await.unwrap();
+    assert!(outcome.is_success());
+    // The router stores the promise without executing it.
+    let router_receipts = outcome.receipts_of(&router_id);
+    assert_eq!(router_receipts.len(), 1);
+    assert!(router_receipts[0]
+        .children
+        .iter()
+        .all(|node| &node.executor_id != contract.id()));
+}
@@ -10,26 +10,64 @@ use crate::operation::{
 };
 use crate::types::MigrationInputData;
 use aurora_engine_types::types::Address;
-use aurora_workspace_utils::{Contract, ContractId};
+#[cfg(feature = "local")]
+use aurora_workspace_utils::local::{LocalAccount, LocalContract};
+use aurora_workspace_utils::trace::TraceRecorder;
+use aurora_workspace_utils::transactions::BatchTransaction;
+use aurora_workspace_utils::{Contract, ContractBackend, ContractId};
 use near_contract_standards::fungible_token::metadata::FungibleTokenMetadata;
 use near_sdk::json_types::U128;
+use near_workspaces::Account;
 use serde_json::json;
 
 type Balance = u128;
 
 #[derive(Debug, Clone)]
-pub struct EthConnectorContract {
-    contract: Contract,
+pub struct EthConnectorContract<C: ContractBackend = Contract> {
+    contract: C,
 }
 
-impl EthConnectorContract {
-    pub fn new(contract: Contract) -> Self {
+impl<C: ContractBackend> EthConnectorContract<C> {
+    pub fn new(contract: C) -> Self {
         Self { contract }
     }
+
+    /// Returns a view of the same contract which appends the calls and views issued through
+    /// it to the trace of the recorder, or doesn't record them if the recorder is `None`.
+    pub fn with_recorder(&self, recorder: Option<TraceRecorder>) -> Self {
+        Self {
+            contract: self.contract.with_recorder(recorder),
+        }
+    }
+}
+
+impl EthConnectorContract {
+    /// Returns a view of the same contract which signs transactions with the given account.
+    pub fn as_account(&self, account: &Account) -> Self {
+        Self {
+            contract: self.contract.with_signer(account),
+        }
+    }
+
+    /// Starts a transaction which may contain several calls to the contract.
+    pub fn batch(&self) -> BatchTransaction {
+        self.contract.batch()
+    }
 }
 
-impl ContractId for EthConnectorContract {
-    fn as_contract(&self) -> &Contract {
+#[cfg(feature = "local")]
+impl EthConnectorContract<LocalContract> {
+    /// Returns a view of the same in-process contract which signs transactions with the
+    /// given account.
+    pub fn as_local_account(&self, account: &LocalAccount) -> Self {
+        Self {
+            contract: self.contract.with_signer(account),
+        }
+    }
+}
+
+impl<C: ContractBackend> ContractId<C> for EthConnectorContract<C> {
+    fn as_contract(&self) -> &C {
         &self.contract
     }
 
@@ -39,7 +77,7 @@ impl ContractId for EthConnectorContract {
 }
 
 /// Call functions
-impl EthConnectorContract {
+impl<C: ContractBackend> EthConnectorContract<C> {
     pub fn init(
         &self,
         metadata: FungibleTokenMetadata,
@@ -194,8 +232,7 @@ impl EthConnectorContract {
     }
 
     pub fn withdraw(&self, recipient_address: Address, amount: Balance) -> CallWithdraw {
-        CallWithdraw::call(&self.contract)
-            .args_json(json!({"recipient_address": recipient_address, "amount": amount}))
+        CallWithdraw::call(&self.contract).args_borsh((recipient_address, amount))
     }
 
     pub fn engine_withdraw(
@@ -227,7 +264,7 @@ impl EthConnectorContract {
 }
 
 /// View functions
-impl EthConnectorContract {
+impl<C: ContractBackend> EthConnectorContract<C> {
     pub fn check_migration_correctness(
         &self,
         data: MigrationInputData,
@@ -1,10 +1,16 @@
 use crate::contract::EthConnectorContract;
+#[cfg(feature = "local")]
+use aurora_workspace_utils::local::{LocalAccount, LocalContract, LocalRuntime};
 use aurora_workspace_utils::Contract;
 use near_workspaces::types::NearToken;
 use near_workspaces::Account;
 use std::path::Path;
 
 pub mod contract;
+#[cfg(feature = "mock")]
+pub mod mock;
+#[cfg(feature = "model")]
+pub mod model;
 pub mod operation;
 pub mod types;
 
@@ -39,3 +45,19 @@ pub async fn deploy<P: AsRef<Path> + Copy>(
 
     Ok((EthConnectorContract::new(contract), root_account))
 }
+
+/// Deploy eth-connector contract using provided WASM file to the in-process runtime.
+#[cfg(feature = "local")]
+pub fn deploy_local<P: AsRef<Path> + Copy>(
+    runtime: &LocalRuntime,
+    path: P,
+) -> anyhow::Result<(EthConnectorContract<LocalContract>, LocalAccount)> {
+    let root_account = runtime.create_account(ROOT_ACCOUNT, ROOT_BALANCE)?;
+    let eth_connector = root_account.create_subaccount(ETH_CONNECTOR_ACCOUNT, CONTRACT_BALANCE)?;
+    let contract_data = std::fs::read(path).map_err(|_| {
+        anyhow::anyhow!("Failed read contract in path: {}", path.as_ref().display())
+    })?;
+    let contract = LocalContract::deploy(&eth_connector, contract_data)?;
+
+    Ok((EthConnectorContract::new(contract), root_account))
+}
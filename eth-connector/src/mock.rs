@@ -0,0 +1,51 @@
+//! Methods of the mock eth-connector in `res/mock_eth_connector`, which don't exist in the
+//! real contract.
+use crate::contract::EthConnectorContract;
+use aurora_engine_types::borsh;
+use aurora_workspace_utils::results::ExecutionResult;
+use aurora_workspace_utils::transactions::CallTransaction;
+use aurora_workspace_utils::{impl_call_return, ContractBackend, ContractId};
+use near_workspaces::types::{Gas, NearToken};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+impl_call_return![
+    (CallSetFault, Call::SetFault),
+    (CallClearFault, Call::ClearFault)
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Call {
+    SetFault,
+    ClearFault,
+}
+
+impl AsRef<str> for Call {
+    fn as_ref(&self) -> &str {
+        match self {
+            Call::SetFault => "set_fault",
+            Call::ClearFault => "clear_fault",
+        }
+    }
+}
+
+/// Fault injected into a method of the mock eth-connector by `set_fault`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Fault {
+    /// The method panics with the given error. Set for `ft_resolve_transfer` it makes
+    /// the callback of `ft_transfer_call` fail.
+    Panic(String),
+    /// The method burns the given amount of NEAR gas before executing.
+    BurnGas(u64),
+}
+impl<C: ContractBackend> EthConnectorContract<C> {
+    /// Makes the method of the mock contract fail in the way described by the fault.
+    pub fn set_fault(&self, method: &str, fault: Fault) -> CallSetFault {
+        CallSetFault::call(self.as_contract())
+            .args_json(json!({ "method": method, "fault": fault }))
+    }
+
+    pub fn clear_fault(&self, method: &str) -> CallClearFault {
+        CallClearFault::call(self.as_contract()).args_json(json!({ "method": method }))
+    }
+}
@@ -0,0 +1,670 @@
+//! Model-based tests of the eth-connector, which compare the connector with the reference
+//! model `ConnectorState`.
+//!
+//! The harness covers the standalone connector only: the root account plays the engine, and
+//! the operations going through the engine, e.g. `deposit_to_aurora` and `ft_on_transfer`,
+//! aren't generated nor modeled. With the `mock` feature the faults injected by
+//! `ModelHarness::fault` make the connector diverge from the model, which tests the shrinking.
+use crate::contract::EthConnectorContract;
+#[cfg(feature = "mock")]
+use crate::mock::Fault;
+use crate::types::{PausedMask, PAUSE_DEPOSIT, PAUSE_WITHDRAW, UNPAUSE_ALL};
+use crate::{ROOT_ACCOUNT, ROOT_BALANCE};
+use aurora_engine_types::types::Address;
+use aurora_workspace_utils::{Contract, ContractId};
+use near_contract_standards::fungible_token::metadata::FungibleTokenMetadata;
+use near_workspaces::types::NearToken;
+use near_workspaces::Account;
+use proptest::collection::vec;
+use proptest::prop_oneof;
+use proptest::strategy::{Just, Strategy, ValueTree};
+use proptest::test_runner::{Config, TestRunner};
+use serde_json::json;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+const USER_BALANCE: NearToken = NearToken::from_near(10);
+const CONNECTOR_BALANCE: NearToken = NearToken::from_near(10);
+const ONE_YOCTO: NearToken = NearToken::from_yoctonear(1);
+/// Upper bound of the generated amounts. It's kept low, so the generated transfers and
+/// withdrawals often exceed the balances.
+const MAX_AMOUNT: u128 = 200;
+const DEFAULT_CASES: u32 = 8;
+const DEFAULT_MAX_OPERATIONS: usize = 16;
+const DEFAULT_MAX_SHRINK_ITERS: u32 = 64;
+
+/// Feature of the eth-connector which could be paused by `pa_pause_feature`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Feature {
+    Deposit,
+    Withdraw,
+}
+
+impl Feature {
+    pub const fn key(self) -> &'static str {
+        match self {
+            Self::Deposit => "deposit",
+            Self::Withdraw => "withdraw",
+        }
+    }
+
+    const fn mask(self) -> PausedMask {
+        match self {
+            Self::Deposit => PAUSE_DEPOSIT,
+            Self::Withdraw => PAUSE_WITHDRAW,
+        }
+    }
+}
+
+/// Operation generated by the model test. The accounts are indices of the users
+/// of `ModelHarness`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Operation {
+    /// `mint` by the owner, which registers the account if needed.
+    Deposit {
+        account: usize,
+        amount: u128,
+    },
+    FtTransfer {
+        sender: usize,
+        receiver: usize,
+        amount: u128,
+    },
+    /// `ft_transfer_call` to a user without a contract, so the amount is refunded.
+    FtTransferCall {
+        sender: usize,
+        receiver: usize,
+        amount: u128,
+    },
+    Withdraw {
+        account: usize,
+        amount: u128,
+    },
+    /// `storage_deposit` of the minimal storage balance by the account itself.
+    StorageDeposit {
+        account: usize,
+    },
+    StorageUnregister {
+        account: usize,
+        force: bool,
+    },
+    Pause(Feature),
+    Unpause(Feature),
+    /// `engine_ft_transfer` by the engine account.
+    EngineFtTransfer {
+        sender: usize,
+        receiver: usize,
+        amount: u128,
+    },
+}
+
+impl Operation {
+    /// Strategy generating the operations of the given number of users.
+    pub fn arbitrary(users: usize) -> impl Strategy<Value = Self> {
+        let user = 0..users;
+        let amount = 0..=MAX_AMOUNT;
+        let transfer = (user.clone(), user.clone(), amount.clone());
+        let feature = prop_oneof![Just(Feature::Deposit), Just(Feature::Withdraw)];
+
+        prop_oneof![
+            3 => (user.clone(), amount.clone())
+                .prop_map(|(account, amount)| Self::Deposit { account, amount }),
+            3 => transfer.clone().prop_map(|(sender, receiver, amount)| Self::FtTransfer {
+                sender,
+                receiver,
+                amount,
+            }),
+            2 => transfer.clone().prop_map(|(sender, receiver, amount)| Self::FtTransferCall {
+                sender,
+                receiver,
+                amount,
+            }),
+            2 => (user.clone(), amount)
+                .prop_map(|(account, amount)| Self::Withdraw { account, amount }),
+            2 => user.clone().prop_map(|account| Self::StorageDeposit { account }),
+            1 => (user, proptest::bool::ANY)
+                .prop_map(|(account, force)| Self::StorageUnregister { account, force }),
+            1 => feature.clone().prop_map(Self::Pause),
+            1 => feature.prop_map(Self::Unpause),
+            2 => transfer.prop_map(|(sender, receiver, amount)| Self::EngineFtTransfer {
+                sender,
+                receiver,
+                amount,
+            }),
+        ]
+    }
+}
+
+impl Display for Operation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Deposit { account, amount } => write!(f, "deposit(user{account}, {amount})"),
+            Self::FtTransfer {
+                sender,
+                receiver,
+                amount,
+            } => write!(f, "ft_transfer(user{sender} -> user{receiver}, {amount})"),
+            Self::FtTransferCall {
+                sender,
+                receiver,
+                amount,
+            } => write!(
+                f,
+                "ft_transfer_call(user{sender} -> user{receiver}, {amount})"
+            ),
+            Self::Withdraw { account, amount } => write!(f, "withdraw(user{account}, {amount})"),
+            Self::StorageDeposit { account } => write!(f, "storage_deposit(user{account})"),
+            Self::StorageUnregister { account, force } => {
+                write!(f, "storage_unregister(user{account}, force: {force})")
+            }
+            Self::Pause(feature) => write!(f, "pa_pause_feature({})", feature.key()),
+            Self::Unpause(feature) => write!(f, "pa_unpause_feature({})", feature.key()),
+            Self::EngineFtTransfer {
+                sender,
+                receiver,
+                amount,
+            } => write!(
+                f,
+                "engine_ft_transfer(user{sender} -> user{receiver}, {amount})"
+            ),
+        }
+    }
+}
+
+/// Reference model of the eth-connector: NEP-141 token with the pausable deposits
+/// and withdrawals.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectorState {
+    /// Balances of the users, `None` if the user isn't registered.
+    pub balances: Vec<Option<u128>>,
+    pub total_supply: u128,
+    pub paused: PausedMask,
+}
+
+impl ConnectorState {
+    pub fn new(users: usize) -> Self {
+        Self {
+            balances: vec![None; users],
+            total_supply: 0,
+            paused: UNPAUSE_ALL,
+        }
+    }
+
+    pub const fn is_paused(&self, feature: Feature) -> bool {
+        self.paused & feature.mask() != 0
+    }
+
+    /// Applies the operation to the model and returns whether it's expected to succeed.
+    /// The failed operations don't change the state.
+    pub fn apply(&mut self, operation: &Operation) -> bool {
+        match *operation {
+            Operation::Deposit { account, amount } => {
+                if self.is_paused(Feature::Deposit) {
+                    return false;
+                }
+                *self.balances[account].get_or_insert(0) += amount;
+                self.total_supply += amount;
+                true
+            }
+            Operation::FtTransfer {
+                sender,
+                receiver,
+                amount,
+            }
+            | Operation::EngineFtTransfer {
+                sender,
+                receiver,
+                amount,
+            } => {
+                if !self.can_transfer(sender, receiver, amount) {
+                    return false;
+                }
+                self.balances[sender] = self.balances[sender].map(|balance| balance - amount);
+                self.balances[receiver] = self.balances[receiver].map(|balance| balance + amount);
+                true
+            }
+            // The receiver has no `ft_on_transfer`, so `ft_resolve_transfer` refunds the amount.
+            Operation::FtTransferCall {
+                sender,
+                receiver,
+                amount,
+            } => self.can_transfer(sender, receiver, amount),
+            Operation::Withdraw { account, amount } => {
+                if self.is_paused(Feature::Withdraw) {
+                    return false;
+                }
+                match &mut self.balances[account] {
+                    Some(balance) if *balance >= amount => {
+                        *balance -= amount;
+                        self.total_supply -= amount;
+                        true
+                    }
+                    _ => false,
+                }
+            }
+            Operation::StorageDeposit { account } => {
+                self.balances[account].get_or_insert(0);
+                true
+            }
+            Operation::StorageUnregister { account, force } => match self.balances[account] {
+                // Unregistering of the unknown account returns `false` but doesn't fail.
+                None => true,
+                Some(balance) if balance == 0 || force => {
+                    self.balances[account] = None;
+                    self.total_supply -= balance;
+                    true
+                }
+                Some(_) => false,
+            },
+            Operation::Pause(feature) => {
+                self.paused |= feature.mask();
+                true
+            }
+            Operation::Unpause(feature) => {
+                self.paused &= !feature.mask();
+                true
+            }
+        }
+    }
+
+    fn can_transfer(&self, sender: usize, receiver: usize, amount: u128) -> bool {
+        sender != receiver
+            && amount > 0
+            && self.balances[sender].map_or(false, |balance| balance >= amount)
+            && self.balances[receiver].is_some()
+    }
+}
+
+/// Runs random sequences of operations against the eth-connector and compares the results
+/// with `ConnectorState` after every operation. The failing sequences are shrunk to the
+/// minimal ones which still diverge from the model.
+///
+/// Every sequence is run against a new connector deployed to the same sandbox, and the root
+/// account is the engine of the connectors.
+#[derive(Debug)]
+pub struct ModelHarness {
+    root: Account,
+    users: Vec<Account>,
+    wasm: Vec<u8>,
+    deployments: AtomicUsize,
+    config: Config,
+    max_operations: usize,
+    #[cfg(feature = "mock")]
+    faults: Vec<(String, Fault)>,
+}
+
+impl ModelHarness {
+    /// Creates the sandbox with the given number of users for the connector WASM file.
+    pub async fn new<P: AsRef<Path>>(path: P, users: usize) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let wasm = std::fs::read(path)
+            .map_err(|e| anyhow::anyhow!("error while reading {}: {e}", path.display()))?;
+        let root = Contract::create_root_account(ROOT_ACCOUNT, ROOT_BALANCE).await?;
+        let mut accounts = Vec::with_capacity(users);
+        for index in 0..users {
+            let name = format!("user{index}");
+            accounts.push(Contract::create_sub_account(&root, &name, USER_BALANCE).await?);
+        }
+
+        Ok(Self {
+            root,
+            users: accounts,
+            wasm,
+            deployments: AtomicUsize::new(0),
+            config: Config {
+                cases: DEFAULT_CASES,
+                max_shrink_iters: DEFAULT_MAX_SHRINK_ITERS,
+                ..Config::default()
+            },
+            max_operations: DEFAULT_MAX_OPERATIONS,
+            #[cfg(feature = "mock")]
+            faults: vec![],
+        })
+    }
+
+    /// Number of the generated sequences.
+    pub fn cases(mut self, cases: u32) -> Self {
+        self.config.cases = cases;
+        self
+    }
+
+    pub fn max_operations(mut self, max_operations: usize) -> Self {
+        self.max_operations = max_operations;
+        self
+    }
+
+    /// Maximal number of the sequences run while shrinking the failing one.
+    pub fn max_shrink_iters(mut self, max_shrink_iters: u32) -> Self {
+        self.config.max_shrink_iters = max_shrink_iters;
+        self
+    }
+
+    /// Injects the fault into the method of every deployed connector, so the connector
+    /// diverges from the model.
+    #[cfg(feature = "mock")]
+    pub fn fault(mut self, method: &str, fault: Fault) -> Self {
+        self.faults.push((method.to_string(), fault));
+        self
+    }
+
+    pub fn users(&self) -> &[Account] {
+        &self.users
+    }
+
+    /// Strategy generating the sequences of operations.
+    pub fn operations(&self) -> impl Strategy<Value = Vec<Operation>> {
+        vec(
+            Operation::arbitrary(self.users.len()),
+            1..=self.max_operations,
+        )
+    }
+
+    /// Runs the generated sequences and returns `ModelFailure` with the minimal failing
+    /// sequence, if any.
+    pub async fn check(&self) -> anyhow::Result<()> {
+        let mut runner = TestRunner::new(self.config.clone());
+        let strategy = self.operations();
+
+        for _ in 0..self.config.cases {
+            let tree = strategy
+                .new_tree(&mut runner)
+                .map_err(|e| anyhow::anyhow!("error while generating operations: {e}"))?;
+            let operations = tree.current();
+            if let Some(mismatch) = self.run(&operations).await? {
+                let failure = self.shrink(tree, operations, mismatch).await?;
+                return Err(failure.into());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Runs the sequence against a new connector and returns the first divergence from
+    /// the model. Useful to replay the sequence reported by `check`.
+    pub async fn run(&self, operations: &[Operation]) -> anyhow::Result<Option<Mismatch>> {
+        let (account, connector) = self.deploy_connector().await?;
+        let mismatch = self.execute(&connector, operations).await;
+        // Return the balance of the connector to the root account.
+        account
+            .delete_account(self.root.id())
+            .await?
+            .into_result()
+            .map_err(|e| anyhow::anyhow!("error while deleting connector: {e}"))?;
+        mismatch
+    }
+
+    async fn shrink(
+        &self,
+        mut tree: impl ValueTree<Value = Vec<Operation>>,
+        operations: Vec<Operation>,
+        mismatch: Mismatch,
+    ) -> anyhow::Result<ModelFailure> {
+        let mut failure = ModelFailure {
+            operations,
+            mismatch,
+            shrink_iters: 0,
+        };
+        if !tree.simplify() {
+            return Ok(failure);
+        }
+
+        while failure.shrink_iters < self.config.max_shrink_iters {
+            failure.shrink_iters += 1;
+            let operations = tree.current();
+            let changed = match self.run(&operations).await? {
+                Some(mismatch) => {
+                    failure.operations = operations;
+                    failure.mismatch = mismatch;
+                    tree.simplify()
+                }
+                None => tree.complicate(),
+            };
+            if !changed {
+                break;
+            }
+        }
+
+        Ok(failure)
+    }
+
+    async fn deploy_connector(&self) -> anyhow::Result<(Account, EthConnectorContract)> {
+        let deployment = self.deployments.fetch_add(1, Ordering::Relaxed);
+        let name = format!("connector{deployment}");
+        let account = Contract::create_sub_account(&self.root, &name, CONNECTOR_BALANCE).await?;
+        let connector =
+            EthConnectorContract::new(Contract::deploy(&account, self.wasm.clone()).await?);
+        connector
+            .init(metadata(), self.root.id(), account.id(), self.root.id())
+            .transact()
+            .await
+            .map_err(|e| anyhow::anyhow!("error while initializing connector: {e}"))?;
+        #[cfg(feature = "mock")]
+        for (method, fault) in &self.faults {
+            connector
+                .set_fault(method, fault.clone())
+                .transact()
+                .await
+                .map_err(|e| anyhow::anyhow!("error while injecting fault into {method}: {e}"))?;
+        }
+        Ok((account, connector))
+    }
+
+    async fn execute(
+        &self,
+        connector: &EthConnectorContract,
+        operations: &[Operation],
+    ) -> anyhow::Result<Option<Mismatch>> {
+        // The views comparing the state with the model aren't recorded in the trace.
+        let views = connector.with_recorder(None);
+        let storage_deposit = views.storage_balance_bounds().await?.result.min;
+        let mut model = ConnectorState::new(self.users.len());
+
+        for (step, operation) in operations.iter().enumerate() {
+            let succeeded = model.apply(operation);
+            let result = self.transact(connector, operation, storage_deposit).await;
+            let state = self.state(&views).await?;
+            if result.is_ok() != succeeded || state != model {
+                return Ok(Some(Mismatch {
+                    step,
+                    operation: operation.clone(),
+                    succeeded,
+                    error: result.err().map(|e| e.to_string()),
+                    expected: model,
+                    actual: state,
+                }));
+            }
+        }
+
+        Ok(None)
+    }
+
+    async fn transact(
+        &self,
+        connector: &EthConnectorContract,
+        operation: &Operation,
+        storage_deposit: NearToken,
+    ) -> anyhow::Result<()> {
+        let user = |index: usize| connector.as_account(&self.users[index]);
+        let id = |index: usize| self.users[index].id();
+
+        match *operation {
+            Operation::Deposit { account, amount } => {
+                connector
+                    .mint(id(account).to_string(), amount)
+                    .max_gas()
+                    .transact()
+                    .await?;
+            }
+            Operation::FtTransfer {
+                sender,
+                receiver,
+                amount,
+            } => {
+                user(sender)
+                    .ft_transfer(id(receiver), amount.into(), None)
+                    .max_gas()
+                    .deposit(ONE_YOCTO)
+                    .transact()
+                    .await?;
+            }
+            Operation::FtTransferCall {
+                sender,
+                receiver,
+                amount,
+            } => {
+                user(sender)
+                    .ft_transfer_call(id(receiver), amount.into(), None, String::new())
+                    .max_gas()
+                    .deposit(ONE_YOCTO)
+                    .transact()
+                    .await?;
+            }
+            Operation::Withdraw { account, amount } => {
+                user(account)
+                    .withdraw(Address::zero(), amount)
+                    .max_gas()
+                    .deposit(ONE_YOCTO)
+                    .transact()
+                    .await?;
+            }
+            Operation::StorageDeposit { account } => {
+                user(account)
+                    .storage_deposit(Some(id(account)), None)
+                    .max_gas()
+                    .deposit(storage_deposit)
+                    .transact()
+                    .await?;
+            }
+            Operation::StorageUnregister { account, force } => {
+                user(account)
+                    .storage_unregister(Some(force))
+                    .max_gas()
+                    .deposit(ONE_YOCTO)
+                    .transact()
+                    .await?;
+            }
+            Operation::Pause(feature) => {
+                connector
+                    .pa_pause_feature(feature.key().to_string())
+                    .max_gas()
+                    .transact()
+                    .await?;
+            }
+            Operation::Unpause(feature) => {
+                connector
+                    .pa_unpause_feature(feature.key().to_string())
+                    .max_gas()
+                    .transact()
+                    .await?;
+            }
+            Operation::EngineFtTransfer {
+                sender,
+                receiver,
+                amount,
+            } => {
+                connector
+                    .as_account(&self.root)
+                    .engine_ft_transfer(id(sender), id(receiver), amount.into(), None)
+                    .max_gas()
+                    .deposit(ONE_YOCTO)
+                    .transact()
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn state(&self, connector: &EthConnectorContract) -> anyhow::Result<ConnectorState> {
+        let mut balances = Vec::with_capacity(self.users.len());
+        for user in &self.users {
+            let error = |e: anyhow::Error| {
+                anyhow::anyhow!("error while reading balance of {}: {e}", user.id())
+            };
+            // `ViewStorageBalanceOf` doesn't expect `null` returned for unregistered accounts.
+            let storage_balance = connector
+                .as_contract()
+                .near_view(&"storage_balance_of")
+                .args_json(json!({ "account_id": user.id() }))
+                .await
+                .map_err(error)?
+                .json::<serde_json::Value>()?;
+            let balance = connector.ft_balance_of(user.id()).await.map_err(error)?;
+            balances.push((!storage_balance.is_null()).then_some(balance.result.0));
+        }
+
+        Ok(ConnectorState {
+            balances,
+            total_supply: connector.ft_total_supply().await?.result.0,
+            paused: connector.get_paused_flags().await?.result,
+        })
+    }
+}
+
+fn metadata() -> FungibleTokenMetadata {
+    FungibleTokenMetadata {
+        spec: String::from("1.0.0"),
+        symbol: String::default(),
+        name: String::default(),
+        icon: None,
+        reference: None,
+        reference_hash: None,
+        decimals: 0,
+    }
+}
+
+/// Divergence of the connector from the model after the operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    /// Index of the operation in the sequence.
+    pub step: usize,
+    pub operation: Operation,
+    /// Whether the model expected the operation to succeed.
+    pub succeeded: bool,
+    /// Error of the operation if it failed.
+    pub error: Option<String>,
+    pub expected: ConnectorState,
+    pub actual: ConnectorState,
+}
+
+impl Display for Mismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "step {}: {}", self.step, self.operation)?;
+        match (&self.error, self.succeeded) {
+            (Some(error), true) => writeln!(f, " failed: {error}")?,
+            (None, false) => writeln!(f, " succeeded, but expected to fail")?,
+            _ => writeln!(f)?,
+        }
+        if self.expected != self.actual {
+            writeln!(f, "expected: {:?}", self.expected)?;
+            writeln!(f, "actual:   {:?}", self.actual)?;
+        }
+        Ok(())
+    }
+}
+
+/// Minimal failing sequence found by `ModelHarness::check`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModelFailure {
+    pub operations: Vec<Operation>,
+    pub mismatch: Mismatch,
+    /// Number of the sequences run while shrinking.
+    pub shrink_iters: u32,
+}
+
+impl Display for ModelFailure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "connector diverged from the model (shrunk in {} iterations):",
+            self.shrink_iters
+        )?;
+        for (step, operation) in self.operations.iter().enumerate() {
+            writeln!(f, "  {step}: {operation}")?;
+        }
+        write!(f, "{}", self.mismatch)
+    }
+}
+
+impl std::error::Error for ModelFailure {}
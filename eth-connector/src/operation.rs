@@ -17,7 +17,6 @@ impl_call_return![
     (CallSetEngineAccount, Call::SetEngineAccount),
     (CallRemoveEngineAccount, Call::RemoveEngineAccount),
     (CallMint, Call::Mint),
-    (CallWithdraw, Call::Withdraw),
     (CallPaPauseFeature, Call::PaPauseFeature),
     (CallPaUnpauseFeature, Call::PaUnpauseFeature),
     (CallAclRevokeRole, Call::AclRevokeRole),
@@ -35,6 +34,7 @@ impl_call_return![
     (CallEngineStorageDeposit => StorageBalance, Call::EngineStorageDeposit, json),
     (CallEngineStorageUnregister => bool, Call::EngineStorageUnregister, json),
     (CallEngineStorageWithdraw => StorageBalance, Call::EngineStorageWithdraw, json),
+    (CallWithdraw => WithdrawResult, Call::Withdraw, borsh),
     (CallEngineWithdraw => WithdrawResult, Call::EngineWithdraw, borsh),
 ];
 
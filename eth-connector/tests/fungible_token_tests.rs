@@ -1,13 +1,20 @@
 use aurora_engine_types::account_id::AccountId;
+use aurora_engine_types::types::Address;
 use aurora_workspace_eth_connector::contract::EthConnectorContract;
-use aurora_workspace_eth_connector::types::{MigrationCheckResult, MigrationInputData};
+#[cfg(feature = "mock")]
+use aurora_workspace_eth_connector::mock::Fault;
+use aurora_workspace_eth_connector::types::{
+    MigrationCheckResult, MigrationInputData, PAUSE_DEPOSIT, PAUSE_WITHDRAW, UNPAUSE_ALL,
+};
 use aurora_workspace_utils::compile::compile_project;
 use aurora_workspace_utils::results::ViewResult;
 use aurora_workspace_utils::ContractId;
-use near_contract_standards::fungible_token::metadata::{FungibleTokenMetadata, FT_METADATA_SPEC};
+use near_contract_standards::fungible_token::metadata::FungibleTokenMetadata;
 use near_sdk::json_types::U128;
 use near_sdk::PromiseOrValue;
-use near_workspaces::types::NearToken;
+use near_workspaces::types::{Gas, NearToken};
+use near_workspaces::Account;
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::LazyLock;
@@ -17,10 +24,8 @@ pub const OWNER_ID: &str = "aurora.test.near";
 pub static CONTRACT_PATH: LazyLock<PathBuf> =
     LazyLock::new(|| compile_project("../res/mock_eth_connector"));
 
-async fn deploy_and_init() -> anyhow::Result<EthConnectorContract> {
-    let (eth_contract, account) =
-        aurora_workspace_eth_connector::deploy(CONTRACT_PATH.as_path()).await?;
-    let metadata = FungibleTokenMetadata {
+fn metadata() -> FungibleTokenMetadata {
+    FungibleTokenMetadata {
         spec: String::from("1.0.0"),
         symbol: String::default(),
         name: String::default(),
@@ -28,49 +33,114 @@ async fn deploy_and_init() -> anyhow::Result<EthConnectorContract> {
         reference: None,
         reference_hash: None,
         decimals: 0,
-    };
+    }
+}
+
+/// Deploys and initializes the contract. The returned account is the known engine account.
+async fn deploy_and_init() -> anyhow::Result<(EthConnectorContract, Account)> {
+    let (eth_contract, account) =
+        aurora_workspace_eth_connector::deploy(CONTRACT_PATH.as_path()).await?;
     let owner_id = AccountId::from_str(OWNER_ID).unwrap();
     let account_with_access_right = AccountId::from_str(account.id().as_str()).unwrap();
 
     eth_contract
         .init(
-            metadata,
+            metadata(),
             &account_with_access_right,
             &owner_id,
             &account_with_access_right,
         )
         .transact()
         .await?;
-    Ok(eth_contract)
+    Ok((eth_contract, account))
+}
+
+async fn storage_deposit(
+    contract: &EthConnectorContract,
+    account_id: &impl AsRef<str>,
+) -> anyhow::Result<()> {
+    let bounds = contract.storage_balance_bounds().await?.result;
+    contract
+        .storage_deposit(Some(account_id), None)
+        .deposit(bounds.min)
+        .transact()
+        .await?;
+    Ok(())
+}
+
+async fn balance_of(contract: &EthConnectorContract, account_id: &impl AsRef<str>) -> u128 {
+    contract.ft_balance_of(account_id).await.unwrap().result.0
+}
+
+fn contract_account_id(contract: &EthConnectorContract) -> AccountId {
+    AccountId::from_str(contract.id().as_str()).unwrap()
 }
 
 #[tokio::test]
 async fn test_ft_transfer() {
-    let contract = deploy_and_init().await.unwrap();
+    let (contract, _) = deploy_and_init().await.unwrap();
+    let owner = contract_account_id(&contract);
     let some_acc = AccountId::from_str("some_account.test.near").unwrap();
-    let amount: U128 = 10.into();
     let memo = Some(String::from("some message"));
 
+    contract
+        .mint(owner.to_string(), 100)
+        .transact()
+        .await
+        .unwrap();
+    storage_deposit(&contract, &some_acc).await.unwrap();
+
     let result = contract
-        .ft_transfer(&some_acc, amount, memo)
+        .ft_transfer(&some_acc, 10.into(), memo)
         .max_gas()
         .deposit(NearToken::from_yoctonear(1))
         .transact()
         .await
         .unwrap();
     assert!(result.is_success());
+    assert_eq!(balance_of(&contract, &owner).await, 90);
+    assert_eq!(balance_of(&contract, &some_acc).await, 10);
+}
+
+#[tokio::test]
+async fn test_ft_transfer_to_unregistered_account() {
+    let (contract, _) = deploy_and_init().await.unwrap();
+    let owner = contract_account_id(&contract);
+    let some_acc = AccountId::from_str("some_account.test.near").unwrap();
+
+    contract
+        .mint(owner.to_string(), 100)
+        .transact()
+        .await
+        .unwrap();
+
+    let err = contract
+        .ft_transfer(&some_acc, 10.into(), None)
+        .max_gas()
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("is not registered"));
+    assert_eq!(balance_of(&contract, &owner).await, 100);
 }
 
 #[tokio::test]
 async fn test_ft_transfer_call() {
-    let contract = deploy_and_init().await.unwrap();
+    let (contract, _) = deploy_and_init().await.unwrap();
+    let owner = contract_account_id(&contract);
     let some_acc = AccountId::from_str("some_account.test.near").unwrap();
-    let amount: U128 = 10.into();
-    let memo = Some(String::from("some message"));
     let msg = String::from("some msg");
 
+    contract
+        .mint(owner.to_string(), 100)
+        .transact()
+        .await
+        .unwrap();
+    storage_deposit(&contract, &some_acc).await.unwrap();
+
     let res: PromiseOrValue<U128> = contract
-        .ft_transfer_call(&some_acc, amount, memo, msg)
+        .ft_transfer_call(&some_acc, 10.into(), None, msg)
         .max_gas()
         .deposit(NearToken::from_yoctonear(1))
         .transact()
@@ -78,19 +148,36 @@ async fn test_ft_transfer_call() {
         .unwrap()
         .into_value();
 
+    // The receiver has no `ft_on_transfer`, so the whole amount is refunded.
     let val = match res {
         PromiseOrValue::Value(v) => v,
         _ => panic!("failed parse"),
     };
-    assert_eq!(U128::from(10), val);
+    assert_eq!(U128::from(0), val);
+    assert_eq!(balance_of(&contract, &owner).await, 100);
+    assert_eq!(balance_of(&contract, &some_acc).await, 0);
 }
 
 #[tokio::test]
 async fn test_ft_total_supply() {
-    let contract = deploy_and_init().await.unwrap();
+    let (contract, _) = deploy_and_init().await.unwrap();
+    let owner = contract_account_id(&contract);
+    let some_acc = AccountId::from_str("some_account.test.near").unwrap();
+
+    contract
+        .mint(owner.to_string(), 100)
+        .transact()
+        .await
+        .unwrap();
+    contract
+        .mint(some_acc.to_string(), 50)
+        .transact()
+        .await
+        .unwrap();
+
     let res = contract.ft_total_supply().await.unwrap();
     let expected = ViewResult {
-        result: U128::from(100),
+        result: U128::from(150),
         logs: vec![],
     };
     assert_eq!(res, expected);
@@ -98,9 +185,15 @@ async fn test_ft_total_supply() {
 
 #[tokio::test]
 async fn test_ft_balance_of() {
-    let contract = deploy_and_init().await.unwrap();
-    let account = contract.as_contract().id();
-    let account_id = AccountId::from_str(account.as_str()).unwrap();
+    let (contract, _) = deploy_and_init().await.unwrap();
+    let account_id = contract_account_id(&contract);
+    assert_eq!(balance_of(&contract, &account_id).await, 0);
+
+    contract
+        .mint(account_id.to_string(), 200)
+        .transact()
+        .await
+        .unwrap();
     let res = contract.ft_balance_of(&account_id).await.unwrap();
     let expected = ViewResult {
         result: U128::from(200),
@@ -109,9 +202,41 @@ async fn test_ft_balance_of() {
     assert_eq!(res, expected);
 }
 
+#[tokio::test]
+async fn test_mint_by_not_owner() {
+    let (contract, engine) = deploy_and_init().await.unwrap();
+    let err = contract
+        .as_account(&engine)
+        .mint(engine.id().to_string(), 100)
+        .transact()
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("ERR_NOT_ALLOWED"));
+    assert_eq!(balance_of(&contract, engine.id()).await, 0);
+}
+
+#[tokio::test]
+async fn test_mint_paused() {
+    let (contract, _) = deploy_and_init().await.unwrap();
+    let account_id = contract_account_id(&contract);
+    contract
+        .pa_pause_feature("deposit".to_string())
+        .max_gas()
+        .transact()
+        .await
+        .unwrap();
+
+    let err = contract
+        .mint(account_id.to_string(), 100)
+        .transact()
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("ERR_PAUSED"));
+}
+
 #[tokio::test]
 async fn test_set_engine_account() {
-    let contract = deploy_and_init().await.unwrap();
+    let (contract, _) = deploy_and_init().await.unwrap();
     let engine_account = AccountId::from_str("test.near").unwrap();
     let result = contract
         .set_engine_account(&engine_account)
@@ -120,120 +245,221 @@ async fn test_set_engine_account() {
         .await
         .unwrap();
     assert!(result.is_success());
+
+    let res = contract
+        .is_engine_account_exist(&engine_account)
+        .await
+        .unwrap();
+    assert!(res.result);
 }
 
 #[tokio::test]
 async fn test_remove_engine_account() {
-    let contract = deploy_and_init().await.unwrap();
-    let engine_account = AccountId::from_str("test.near").unwrap();
+    let (contract, engine) = deploy_and_init().await.unwrap();
     let result = contract
-        .remove_engine_account(&engine_account)
+        .remove_engine_account(engine.id())
         .max_gas()
         .transact()
         .await
         .unwrap();
     assert!(result.is_success());
+
+    let res = contract.is_engine_account_exist(engine.id()).await.unwrap();
+    assert!(!res.result);
+    let err = contract
+        .as_account(&engine)
+        .engine_ft_transfer(engine.id(), &contract_account_id(&contract), 0.into(), None)
+        .max_gas()
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("Method can be called only by aurora engine"));
 }
 
 #[tokio::test]
 async fn test_get_engine_accounts() {
-    let contract = deploy_and_init().await.unwrap();
-    let engine_account = AccountId::from_str("test.root").unwrap();
-    let res = contract
-        .is_engine_account_exist(&engine_account)
-        .await
-        .unwrap();
+    let (contract, engine) = deploy_and_init().await.unwrap();
+    let res = contract.is_engine_account_exist(engine.id()).await.unwrap();
     let expected = ViewResult {
         result: true,
         logs: vec![],
     };
     assert_eq!(res, expected);
+
+    let unknown = AccountId::from_str("test.near").unwrap();
+    let res = contract.is_engine_account_exist(&unknown).await.unwrap();
+    assert!(!res.result);
+}
+
+#[tokio::test]
+async fn test_engine_ft_transfer() {
+    let (contract, engine) = deploy_and_init().await.unwrap();
+    let sender = AccountId::from_str("sender.test.near").unwrap();
+    let receiver = AccountId::from_str("receiver.test.near").unwrap();
+
+    contract
+        .mint(sender.to_string(), 100)
+        .transact()
+        .await
+        .unwrap();
+    storage_deposit(&contract, &receiver).await.unwrap();
+
+    contract
+        .as_account(&engine)
+        .engine_ft_transfer(&sender, &receiver, 30.into(), None)
+        .max_gas()
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await
+        .unwrap();
+    assert_eq!(balance_of(&contract, &sender).await, 70);
+    assert_eq!(balance_of(&contract, &receiver).await, 30);
+}
+
+#[tokio::test]
+async fn test_engine_ft_transfer_by_not_engine() {
+    let (contract, _) = deploy_and_init().await.unwrap();
+    let sender = AccountId::from_str("sender.test.near").unwrap();
+    let receiver = contract_account_id(&contract);
+
+    contract
+        .mint(sender.to_string(), 100)
+        .transact()
+        .await
+        .unwrap();
+
+    let err = contract
+        .engine_ft_transfer(&sender, &receiver, 30.into(), None)
+        .max_gas()
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("Method can be called only by aurora engine"));
+    assert_eq!(balance_of(&contract, &sender).await, 100);
 }
 
 #[tokio::test]
 async fn test_storage_deposit() {
-    let contract = deploy_and_init().await.unwrap();
+    let (contract, _) = deploy_and_init().await.unwrap();
     let account_id = AccountId::from_str("test.near").unwrap();
+    let bounds = contract.storage_balance_bounds().await.unwrap().result;
     let res = contract
         .storage_deposit(Some(&account_id), Some(true))
         .max_gas()
+        .deposit(bounds.min)
         .transact()
         .await
         .unwrap()
         .into_value();
-    assert_eq!(res.total, NearToken::from_yoctonear(100));
-    assert_eq!(res.available, NearToken::from_yoctonear(200));
+    assert_eq!(res.total, bounds.min);
+    assert_eq!(res.available, NearToken::from_yoctonear(0));
 }
 
 #[tokio::test]
 async fn test_storage_withdraw() {
-    let contract = deploy_and_init().await.unwrap();
-    let amount = Some(U128::from(100));
+    let (contract, _) = deploy_and_init().await.unwrap();
+    let account_id = contract_account_id(&contract);
+    let bounds = contract.storage_balance_bounds().await.unwrap().result;
+    storage_deposit(&contract, &account_id).await.unwrap();
+
     let res = contract
-        .storage_withdraw(amount)
+        .storage_withdraw(None)
         .max_gas()
+        .deposit(NearToken::from_yoctonear(1))
         .transact()
         .await
         .unwrap()
         .into_value();
-    assert_eq!(res.total, NearToken::from_yoctonear(100));
-    assert_eq!(res.available, NearToken::from_yoctonear(200));
+    assert_eq!(res.total, bounds.min);
+    assert_eq!(res.available, NearToken::from_yoctonear(0));
 }
 
 #[tokio::test]
 async fn test_storage_unregister() {
-    let contract = deploy_and_init().await.unwrap();
+    let (contract, _) = deploy_and_init().await.unwrap();
+    let account_id = contract_account_id(&contract);
+    storage_deposit(&contract, &account_id).await.unwrap();
+
     let force = Some(true);
     let res = contract
         .storage_unregister(force)
         .max_gas()
+        .deposit(NearToken::from_yoctonear(1))
         .transact()
         .await
         .unwrap()
         .into_value();
     assert!(res);
+
+    let res = contract
+        .storage_unregister(force)
+        .max_gas()
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await
+        .unwrap()
+        .into_value();
+    assert!(!res);
 }
 
 #[tokio::test]
 async fn test_engine_storage_deposit() {
-    let contract = deploy_and_init().await.unwrap();
+    let (contract, engine) = deploy_and_init().await.unwrap();
     let sender_id = AccountId::from_str("test.near").unwrap();
-    let account_id = sender_id.clone();
+    let bounds = contract.storage_balance_bounds().await.unwrap().result;
     let res = contract
-        .engine_storage_deposit(&sender_id, Some(&account_id), Some(true))
+        .as_account(&engine)
+        .engine_storage_deposit(&sender_id, None::<&AccountId>, Some(true))
         .max_gas()
+        .deposit(bounds.min)
         .transact()
         .await
         .unwrap()
         .into_value();
-    assert_eq!(res.total, NearToken::from_yoctonear(100));
-    assert_eq!(res.available, NearToken::from_yoctonear(200));
+    assert_eq!(res.total, bounds.min);
+    assert_eq!(res.available, NearToken::from_yoctonear(0));
+
+    let res = contract.storage_balance_of(&sender_id).await.unwrap();
+    assert_eq!(res.result.total, bounds.min);
 }
 
 #[tokio::test]
 async fn test_engine_storage_withdraw() {
-    let contract = deploy_and_init().await.unwrap();
+    let (contract, engine) = deploy_and_init().await.unwrap();
     let sender_id = AccountId::from_str("test.near").unwrap();
-    let amount = Some(U128::from(100));
+    let bounds = contract.storage_balance_bounds().await.unwrap().result;
+    storage_deposit(&contract, &sender_id).await.unwrap();
+
     let res = contract
-        .engine_storage_withdraw(&sender_id, amount)
+        .as_account(&engine)
+        .engine_storage_withdraw(&sender_id, None)
         .max_gas()
+        .deposit(NearToken::from_yoctonear(1))
         .transact()
         .await
         .unwrap()
         .into_value();
-    assert_eq!(res.total, NearToken::from_yoctonear(100));
-    assert_eq!(res.available, NearToken::from_yoctonear(200));
+    assert_eq!(res.total, bounds.min);
+    assert_eq!(res.available, NearToken::from_yoctonear(0));
 }
 
 #[tokio::test]
 async fn test_engine_storage_unregister() {
-    let contract = deploy_and_init().await.unwrap();
-    let sender_id = AccountId::from_str("test.near").unwrap();
-    let force = Some(true);
+    let (contract, engine) = deploy_and_init().await.unwrap();
+    let sender_id = AccountId::from_str(engine.id().as_str()).unwrap();
+    storage_deposit(&contract, &sender_id).await.unwrap();
+
     let res = contract
-        .engine_storage_unregister(&sender_id, force)
+        .as_account(&engine)
+        .engine_storage_unregister(&sender_id, Some(true))
         .max_gas()
+        .deposit(NearToken::from_yoctonear(1))
         .transact()
         .await
         .unwrap()
@@ -243,25 +469,28 @@ async fn test_engine_storage_unregister() {
 
 #[tokio::test]
 async fn test_storage_balance_of() {
-    let contract = deploy_and_init().await.unwrap();
+    let (contract, _) = deploy_and_init().await.unwrap();
     let account_id = AccountId::from_str("test.near").unwrap();
+    let bounds = contract.storage_balance_bounds().await.unwrap().result;
+    storage_deposit(&contract, &account_id).await.unwrap();
+
     let res = contract.storage_balance_of(&account_id).await.unwrap();
     let result = res.result;
-    assert_eq!(result.total, NearToken::from_yoctonear(10));
-    assert_eq!(result.available, NearToken::from_yoctonear(20));
+    assert_eq!(result.total, bounds.min);
+    assert_eq!(result.available, NearToken::from_yoctonear(0));
 }
 
 #[tokio::test]
 async fn test_storage_balance_bounds() {
-    let contract = deploy_and_init().await.unwrap();
+    let (contract, _) = deploy_and_init().await.unwrap();
     let res = contract.storage_balance_bounds().await.unwrap();
-    assert_eq!(res.result.min, NearToken::from_yoctonear(100));
-    assert_eq!(res.result.max, Some(NearToken::from_yoctonear(200)));
+    assert!(res.result.min > NearToken::from_yoctonear(0));
+    assert_eq!(res.result.max, Some(res.result.min));
 }
 
 #[tokio::test]
 async fn test_pa_pause_feature() {
-    let contract = deploy_and_init().await.unwrap();
+    let (contract, _) = deploy_and_init().await.unwrap();
     let result = contract
         .pa_pause_feature("withdraw".to_string())
         .max_gas()
@@ -269,11 +498,29 @@ async fn test_pa_pause_feature() {
         .await
         .unwrap();
     assert!(result.is_success());
+
+    let res = contract.get_paused_flags().await.unwrap();
+    assert_eq!(res.result, PAUSE_WITHDRAW);
 }
 
 #[tokio::test]
 async fn test_pa_unpause_feature() {
-    let contract = deploy_and_init().await.unwrap();
+    let (contract, _) = deploy_and_init().await.unwrap();
+    contract
+        .pa_pause_feature("withdraw".to_string())
+        .max_gas()
+        .transact()
+        .await
+        .unwrap();
+    contract
+        .pa_pause_feature("deposit".to_string())
+        .max_gas()
+        .transact()
+        .await
+        .unwrap();
+    let res = contract.get_paused_flags().await.unwrap();
+    assert_eq!(res.result, PAUSE_DEPOSIT | PAUSE_WITHDRAW);
+
     let result = contract
         .pa_unpause_feature("withdraw".to_string())
         .max_gas()
@@ -281,11 +528,99 @@ async fn test_pa_unpause_feature() {
         .await
         .unwrap();
     assert!(result.is_success());
+
+    let res = contract.get_paused_flags().await.unwrap();
+    assert_eq!(res.result, PAUSE_DEPOSIT);
+}
+
+#[tokio::test]
+async fn test_get_paused_flags() {
+    let (contract, _) = deploy_and_init().await.unwrap();
+    let res = contract.get_paused_flags().await.unwrap();
+    assert_eq!(res.result, UNPAUSE_ALL);
+}
+
+#[tokio::test]
+async fn test_withdraw() {
+    let (contract, _) = deploy_and_init().await.unwrap();
+    let owner = contract_account_id(&contract);
+    let recipient = Address::from_array([1; 20]);
+
+    contract
+        .mint(owner.to_string(), 100)
+        .transact()
+        .await
+        .unwrap();
+    let res = contract
+        .withdraw(recipient, 40)
+        .max_gas()
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await
+        .unwrap()
+        .into_value();
+    assert_eq!(res.amount, 40);
+    assert_eq!(res.recipient_id, recipient);
+    assert_eq!(balance_of(&contract, &owner).await, 60);
+    assert_eq!(contract.ft_total_supply().await.unwrap().result, U128(60));
+}
+
+#[tokio::test]
+async fn test_withdraw_paused() {
+    let (contract, _) = deploy_and_init().await.unwrap();
+    let owner = contract_account_id(&contract);
+
+    contract
+        .mint(owner.to_string(), 100)
+        .transact()
+        .await
+        .unwrap();
+    contract
+        .pa_pause_feature("withdraw".to_string())
+        .max_gas()
+        .transact()
+        .await
+        .unwrap();
+
+    let err = contract
+        .withdraw(Address::from_array([1; 20]), 40)
+        .max_gas()
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("ERR_PAUSED"));
+    assert_eq!(balance_of(&contract, &owner).await, 100);
+}
+
+#[tokio::test]
+async fn test_engine_withdraw() {
+    let (contract, engine) = deploy_and_init().await.unwrap();
+    let sender = AccountId::from_str("sender.test.near").unwrap();
+    let recipient = Address::from_array([2; 20]);
+
+    contract
+        .mint(sender.to_string(), 100)
+        .transact()
+        .await
+        .unwrap();
+    let res = contract
+        .as_account(&engine)
+        .engine_withdraw(&sender, recipient, 100)
+        .max_gas()
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await
+        .unwrap()
+        .into_value();
+    assert_eq!(res.amount, 100);
+    assert_eq!(res.recipient_id, recipient);
+    assert_eq!(balance_of(&contract, &sender).await, 0);
 }
 
 #[tokio::test]
 async fn test_acl_grant_role() {
-    let contract = deploy_and_init().await.unwrap();
+    let (contract, _) = deploy_and_init().await.unwrap();
     let result = contract
         .acl_grant_role("PauseManager".to_string(), OWNER_ID.to_string())
         .max_gas()
@@ -297,7 +632,7 @@ async fn test_acl_grant_role() {
 
 #[tokio::test]
 async fn test_acl_revoke_role() {
-    let contract = deploy_and_init().await.unwrap();
+    let (contract, _) = deploy_and_init().await.unwrap();
     let result = contract
         .acl_revoke_role("PauseManager".to_string(), OWNER_ID.to_string())
         .max_gas()
@@ -309,7 +644,7 @@ async fn test_acl_revoke_role() {
 
 #[tokio::test]
 async fn test_acl_get_grantees() {
-    let contract = deploy_and_init().await.unwrap();
+    let (contract, _) = deploy_and_init().await.unwrap();
     let res = contract
         .acl_get_grantees("PauseManager".to_string(), 0, 1)
         .await
@@ -320,7 +655,7 @@ async fn test_acl_get_grantees() {
 
 #[tokio::test]
 async fn test_set_aurora_engine_account_id() {
-    let contract = deploy_and_init().await.unwrap();
+    let (contract, _) = deploy_and_init().await.unwrap();
     let result = contract
         .set_aurora_engine_account_id("test.near".to_string())
         .max_gas()
@@ -328,39 +663,38 @@ async fn test_set_aurora_engine_account_id() {
         .await
         .unwrap();
     assert!(result.is_success());
+
+    let res = contract.get_aurora_engine_account_id().await.unwrap();
+    assert_eq!(res.result.to_string(), "test.near");
 }
 
 #[tokio::test]
 async fn test_get_aurora_engine_account_id() {
-    let contract = deploy_and_init().await.unwrap();
-    contract.get_aurora_engine_account_id().await.unwrap();
+    let (contract, engine) = deploy_and_init().await.unwrap();
+    let res = contract.get_aurora_engine_account_id().await.unwrap();
+    assert_eq!(res.result.to_string(), engine.id().to_string());
 }
 
 #[tokio::test]
 async fn test_migrate() {
-    let contract = deploy_and_init().await.unwrap();
-    let accounts = vec![];
+    let (contract, _) = deploy_and_init().await.unwrap();
+    let accounts = vec!["alice.test.near".to_string(), "bob.test.near".to_string()];
     contract
         .migrate(accounts)
         .max_gas()
         .transact()
         .await
         .unwrap();
+
+    let res = contract.storage_balance_of(&"alice.test.near").await;
+    assert!(res.is_ok());
 }
 
 #[tokio::test]
 async fn test_ft_metadata() {
-    let contract = deploy_and_init().await.unwrap();
+    let (contract, _) = deploy_and_init().await.unwrap();
     let res = contract.ft_metadata().await.unwrap();
-    let expected =  FungibleTokenMetadata {
-            spec: FT_METADATA_SPEC.to_string(),
-            name: "Ether".to_string(),
-            symbol: "ETH".to_string(),
-            icon: Some("data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAGQAAABkCAYAAABw4pVUAAAAAXNSR0IArs4c6QAAAARnQU1BAACxjwv8YQUAAAAJcEhZcwAADsQAAA7EAZUrDhsAAAs3SURBVHhe7Z1XqBQ9FMdFsYu999577wUfbCiiPoggFkQsCKJP9t57V7AgimLBjg8qKmLBXrD33hVUEAQ1H7+QXMb9Zndnd+/MJJf7h8Pu3c3Mzua3yTk5SeZmEZkySplADFMmEMOUCcQwZQggHz58EHfu3FF/2a0MAWTjxo2iWbNm6i+7ZT2QW7duiUWLFolixYqJQ4cOqVftlfVAZs6cKdauXSuqV68uKlWqpF61V1YDoUXMmTNHrFu3TtSoUUNCmTBhgnrXTlkL5Nu3b2Ly5MmyuwJIzZo1RaNGjUTx4sXFu3fvVCn7ZC2QVatWiQULFvwPSL169USnTp1UKftkJZCbN2+KGTNmSBiLFy/+BwhWoUIFsX//flXaLlkJZPr06WkwIoE0btxYNGzYUFSsWFGVtkvWATlw4IB05BqGGxAMBz9u3Dh1lD2yCsjXr1/THHk8IDwvVaqUeP36tTraDlkFZOXKldKRO2HEAoKD79ixozraDlkD5Pr16/848nhANBQc/N69e9VZzJc1QCIduRcgGA4eKLbICiD79u37nyN3WiwgvMZ7Y8eOVWczW8YDwZFPmTIlauvA4gHhsUSJEuLFixfqrObKeCArVqxwdeROiwUE43UcfNu2bdVZzZXRQK5duyYduRsEp8UDog1fsnPnTnV2M2U0kFiO3GlegeDgy5cvr85upowFQqg6d+5cVwCR5hUI71NuzJgx6lPMk5FAPn365Doij2ZegWCUIUX/9OlT9WlmyUggy5Yti+vInZYIEAwH37JlS/VpZsk4IJcvX5bTsl5bB5YoEMqRDd62bZv6VHNkHJBp06YlBANLFAiGgy9btqz6VHNkFJBdu3Z5duROSwYIxjEjRoxQn26GjAHy8ePHuCPyaJYsEMozgn/48KG6ivBlDJAlS5Yk5MidlgqQ+vXri+bNm6urCF9GALl48aJ05G6V7cWSBYJxDOu5Nm/erK4mXBkBJBlH7rRUgGAmOfjQgZBbSsaROy1VIBjHDxs2TF1VeAoVyPv37+WI3K2SE7H0AMKxJUuWFHfv3lVXF45CBZKKI3daegDBcPBNmzZVVxeOQgNy/vz5hEfkbsbxAGFtb6pAOL5y5cpye0NYCg1Iqo5c29KlS2WEVKdOHdGkSZOUoeDgS5cura4yeIUCZMeOHWLevHkpASEBScvAB/Xs2VMUKVJE1K1bV44pUgHDcbVq1RJDhgxRVxusAgfy5s0bMXXq1IRgOMsuX75c7gcZP368aN++vez3W7VqJfLnzy8KFCggU+tUKNncZMFwDA6eNcRBK3AgCxculOas8HiG82duffXq1WLkyJGiRYsWokGDBrI1UPHMlQOjaNGisqUUKlRIPrKclLKA0RUdWfnRDNCUD1qBAjl79qyYNWuWa6VHGq0CEGw7oHsaNGiQrCBMg9DmBKJNgylYsKAciQOFfYhUtlcwHEe3GKQCA/Lnzx/PyUMc9Zo1a+SAsV+/fvLXSgXxa3eCiAXECaZw4cISDPPpGijniweG93HwXHtQCgwIk0E4cjcAGhItAf8AuG7dukknzbgAENFgYLGAaNNgKMcibGYNdXdGxUeDgz8aOHCg+hb+KxAgr169kpUcCUKb01GzOJrKonuJB0KbFyBOAw4thgCgdu3aaWAA4AYGB8/a4iAUCBBG405Hrv2Dm6MGhFulx7JEgWjTYHisVq2a/GxapBMGgLguLAj5DuTMmTP/OHLtqPETdAW6u4h01IlYskC06e6MIICROlA0GH19vM51+y1fgfz+/TvNkWtHjR/p27ev7JboJrx2S7EsVSAYUDCgcC4CAEbtXJsGg4PnO/kpX4Fs3bpVwiB0BEz37t09O+pELD2AOE23GM5ZpkwZGeVxraRnBgwYoL6dP/INCCNyfAeOukOHDmmZVLcKTdXSG4jTNBidAaDlXLlyRX3L9JdvQPr06SObvHbU6dUa3MxPINp0d5Y3b16RJ08e9S3TX74Befz4sejcubOoWrWqdNi2AgEEj8DIkiWLdO4PHjxQ3zL95asPQQcPHpSTR/gOv6D4BUQ7+uzZs4usWbOK7du3q2/ln3wHosU+j3LlysmIxa1SUzG/gOTLl0+2ilGjRqlv4b8CA4K+fPkievXqJZt9MgPAaJbeQHT3hA9kJX6QChSI1smTJ+U4RKct3Co5EUsvIHRP2bJlEzlz5hRHjhxRVxusfANy4cIF9Sy6GLnrAZhbRXu1VIEAguiJVuHlfltbtmxRz9JfvgHhxpQMBt++fatecdfPnz/lYIvtAcmOU1IBQi4LEG3atJHXEkssEWK0fvv2bfVK+svXLosJKW4AQ3QSb07h6tWr0uEz+Eq0G0sGCAM+IieOI98WS3///hVDhw4VOXLkkAlRP+W7D9mwYYNMLtJa4n1xRBqe3bIMKL2CSQQI3VPu3Lllq+C64olsNPMnBCJdunRRr/qnQJw6IS/pdypg/vz5cff38YscPny49C9eujGvQCgDiB49eqhPii4WgJPuAQQ+Lqi1v4EAefToUVrWFzCsyWIx2q9fv1QJd92/f1+0bt1aLlaINdqPB4TuCRD80rmtbCzhR8hG66SizvKeOHFClfBXgQBBe/bskfcr0dO1pOFZU3Xs2DFVIrqY/q1SpUpa1tUrELqnXLlySRhe5jKYw2d2kHBcz4OwIjLIXVaBAUF0V5Ezh7Nnz5Z27949VSq6CBDoOphHiQYECDyyTgsQ/fv3V0dH1/Hjx2V6h7wbEAguMH4ABBlBKlAgbneE090Yd21Yv369+P79uyrtrpcvX/6TtIwEorsnlvA8efJEHeUuRuFdu3aVKR2CCCcMnpNyf/78uSodjAIFgk6fPh11txQtCGBebhlO0pLuhKSlBkISEBhMjMXTxIkTZYVzvBOEhgFQriloBQ4EEUrGWhKEryEyu3HjhjoiuggWqDxAeOnrufcW5QkUIkFoGEBiUi0MhQKEeel4q995DyjcZ/Hz58/qSHfRrcTbSUuZdu3ayTEOYawbDIz3iLDiRYB+KRQgiP/3waJrNxjagMI0MK2AKC1ZjR49Wm5/JqEZDQTGe8A4fPiwOjJ4hQYEsS3By/5CwFCOVsWAzatIAhKVed3MQznWEIepUIEg/IUzFI5lgCEgYG1XrKQlyT9CY3wFXZBb5UcaURZ+JWyFDoSs8KRJk2L6E6dRDoB0YyQtneukSGAOHjxYDu70KNut8iONckRcJvzbpNCBIAZmXrcpYBoekRpgyBQzhiE1wkDOKwiMsuSr6BJNkBFAENEU45DIyo9nwGGxNs44ERAY5QlxmQsxRcYAIcxMdKubtmS3RVOe7u3Hjx/qKsKXMUAQA0EiKbdKj2XJAiEC2717t/p0M2QUEETaw0so7LREgVCO8l4Sj0HLOCAIB+81FMYSAUIZQmGSkybKSCAs1I7MCseyRIEwaveSJwtDRgJBR48e9RwKewXC+0x0AdtUGQsEMSL3cnMaL0B4j1wWc/Qmy2ggzG/ruXg3ENq8AmHgyCSZyTIaCLp06VLce8DHA8LrrGDxMnEVtowHgjZt2hR1QguLB4R0Su/evdXZzJYVQJBe25UoELK4Nv1PQ2uAPHv2LKo/iQaEv0mNeFn4bYqsAYL4p5IsGfIChOfMb7Dp1CZZBQTRQiJDYTcgerrWNlkHhHVbkV1XJBAemXDirqe2yTog6Ny5c9LJayhOIBgrS1h1b6OsBIKocB0KO4FwtwVu7WSrrAWC9NouDYQsLstCbZbVQNjmwCwjQFjCwzTuqVOn1Lt2ymogiBk/PafOfbdsl/VAEEBs+gfEsZQhgDChxVKgjKAMASQjKROIYcoEYpgygRglIf4D6lp/+XognSwAAAAASUVORK5CYII=".to_string()),
-            reference: None,
-            reference_hash: None,
-            decimals: 18,
-        };
+    let expected = metadata();
     assert_eq!(res.result.spec, expected.spec);
     assert_eq!(res.result.name, expected.name);
     assert_eq!(res.result.symbol, expected.symbol);
@@ -372,8 +706,253 @@ async fn test_ft_metadata() {
 
 #[tokio::test]
 async fn test_check_migration_correctness() {
-    let contract = deploy_and_init().await.unwrap();
+    let (contract, _) = deploy_and_init().await.unwrap();
     let data = MigrationInputData::default();
     let res = contract.check_migration_correctness(data).await.unwrap();
     assert_eq!(res.result, MigrationCheckResult::Success);
 }
+
+#[tokio::test]
+async fn test_check_migration_correctness_of_stored_balances() {
+    let (contract, _) = deploy_and_init().await.unwrap();
+    let alice = near_workspaces::AccountId::from_str("alice.test.near").unwrap();
+    let bob = near_workspaces::AccountId::from_str("bob.test.near").unwrap();
+    contract
+        .migrate(vec![alice.to_string()])
+        .max_gas()
+        .transact()
+        .await
+        .unwrap();
+    contract
+        .mint(alice.to_string(), 10)
+        .transact()
+        .await
+        .unwrap();
+
+    let data = MigrationInputData {
+        accounts: HashMap::from([(alice.clone(), 10)]),
+        total_supply: Some(10),
+    };
+    let res = contract.check_migration_correctness(data).await.unwrap();
+    assert_eq!(res.result, MigrationCheckResult::Success);
+
+    let data = MigrationInputData {
+        accounts: HashMap::from([(alice.clone(), 10), (bob.clone(), 0)]),
+        total_supply: None,
+    };
+    let res = contract.check_migration_correctness(data).await.unwrap();
+    assert_eq!(res.result, MigrationCheckResult::AccountNotExist(vec![bob]));
+
+    let data = MigrationInputData {
+        accounts: HashMap::from([(alice.clone(), 5)]),
+        total_supply: None,
+    };
+    let res = contract.check_migration_correctness(data).await.unwrap();
+    assert_eq!(
+        res.result,
+        MigrationCheckResult::AccountAmount(HashMap::from([(alice, 10)]))
+    );
+
+    let data = MigrationInputData {
+        accounts: HashMap::new(),
+        total_supply: Some(20),
+    };
+    let res = contract.check_migration_correctness(data).await.unwrap();
+    assert_eq!(res.result, MigrationCheckResult::TotalSupply(10));
+}
+
+#[tokio::test]
+async fn test_ft_transfer_as_account() {
+    let (contract, _) = deploy_and_init().await.unwrap();
+    let user = contract
+        .as_contract()
+        .signer()
+        .create_subaccount("user")
+        .initial_balance(NearToken::from_near(10))
+        .transact()
+        .await
+        .unwrap()
+        .into_result()
+        .unwrap();
+    let user_contract = contract.as_account(&user);
+    assert_eq!(user_contract.id(), contract.id());
+
+    let some_acc = AccountId::from_str("some_account.test.near").unwrap();
+    contract
+        .mint(user.id().to_string(), 100)
+        .transact()
+        .await
+        .unwrap();
+    storage_deposit(&contract, &some_acc).await.unwrap();
+
+    let result = user_contract
+        .ft_transfer(&some_acc, 10.into(), None)
+        .max_gas()
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await
+        .unwrap();
+    assert!(result.is_success());
+    assert_eq!(&result.outcome().executor_id, user.id());
+    assert_eq!(balance_of(&contract, user.id()).await, 90);
+}
+
+#[tokio::test]
+async fn test_batch_mint_and_ft_transfer_call() {
+    let (contract, _) = deploy_and_init().await.unwrap();
+    let owner = contract_account_id(&contract);
+    let some_acc = AccountId::from_str("some_account.test.near").unwrap();
+    storage_deposit(&contract, &some_acc).await.unwrap();
+
+    let res = contract
+        .batch()
+        .call(contract.mint(owner.to_string(), 100))
+        .transact_last(
+            contract
+                .ft_transfer_call(&some_acc, 10.into(), None, String::from("some msg"))
+                .gas(Gas::from_tgas(100))
+                .deposit(NearToken::from_yoctonear(1)),
+        )
+        .await
+        .unwrap();
+
+    assert!(res.is_success());
+    // The receiver has no `ft_on_transfer`, so the whole amount is refunded.
+    match res.into_value() {
+        PromiseOrValue::Value(v) => assert_eq!(v, U128::from(0)),
+        _ => panic!("failed parse"),
+    }
+    assert_eq!(balance_of(&contract, &owner).await, 100);
+    assert!(contract.storage_balance_of(&some_acc).await.is_ok());
+}
+
+#[tokio::test]
+async fn test_ft_transfer_call_receipt_tree() {
+    let (contract, _) = deploy_and_init().await.unwrap();
+    let owner = contract_account_id(&contract);
+    let some_acc = AccountId::from_str("some_account.test.near").unwrap();
+
+    contract
+        .mint(owner.to_string(), 100)
+        .transact()
+        .await
+        .unwrap();
+    storage_deposit(&contract, &some_acc).await.unwrap();
+
+    let result = contract
+        .ft_transfer_call(&some_acc, 10.into(), None, String::from("some msg"))
+        .max_gas()
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await
+        .unwrap();
+    let tree = result.receipt_tree();
+
+    assert_eq!(result.method(), Some("ft_transfer_call"));
+    let call = tree
+        .find(contract.id(), "ft_transfer_call")
+        .unwrap_or_else(|| panic!("no ft_transfer_call receipt in:\n{tree}"));
+    assert!(call.logs.iter().any(|log| log.contains("ft_transfer")));
+    // The methods of the receipts spawned by the contract aren't known without the sandbox.
+    assert!(call
+        .children
+        .iter()
+        .any(|node| node.executor_id == *contract.id() && node.method.is_none()));
+    // Only the call of the receiver without contract code fails.
+    assert!(tree
+        .failures()
+        .iter()
+        .all(|node| node.executor_id.as_str() == some_acc.as_ref()));
+}
+
+#[cfg(feature = "mock")]
+#[tokio::test]
+async fn test_ft_transfer_fault() {
+    let (contract, _) = deploy_and_init().await.unwrap();
+    let owner = contract_account_id(&contract);
+    let some_acc = AccountId::from_str("some_account.test.near").unwrap();
+
+    contract
+        .mint(owner.to_string(), 100)
+        .transact()
+        .await
+        .unwrap();
+    storage_deposit(&contract, &some_acc).await.unwrap();
+    contract
+        .set_fault("ft_transfer", Fault::Panic("ERR_TEST".to_string()))
+        .transact()
+        .await
+        .unwrap();
+
+    let err = contract
+        .ft_transfer(&some_acc, 10.into(), None)
+        .max_gas()
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("ERR_TEST"));
+    assert_eq!(balance_of(&contract, &owner).await, 100);
+
+    contract
+        .clear_fault("ft_transfer")
+        .transact()
+        .await
+        .unwrap();
+    contract
+        .ft_transfer(&some_acc, 10.into(), None)
+        .max_gas()
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await
+        .unwrap();
+    assert_eq!(balance_of(&contract, &owner).await, 90);
+}
+
+#[cfg(feature = "mock")]
+#[tokio::test]
+async fn test_ft_resolve_transfer_fault() {
+    let (contract, _) = deploy_and_init().await.unwrap();
+    let owner = contract_account_id(&contract);
+    let some_acc = AccountId::from_str("some_account.test.near").unwrap();
+
+    contract
+        .mint(owner.to_string(), 100)
+        .transact()
+        .await
+        .unwrap();
+    storage_deposit(&contract, &some_acc).await.unwrap();
+    contract
+        .set_fault(
+            "ft_resolve_transfer",
+            Fault::Panic("ERR_CALLBACK".to_string()),
+        )
+        .transact()
+        .await
+        .unwrap();
+
+    let err = contract
+        .ft_transfer_call(&some_acc, 10.into(), None, String::from("some msg"))
+        .max_gas()
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("ERR_CALLBACK"));
+    // The failed callback doesn't refund the amount rejected by the receiver.
+    assert_eq!(balance_of(&contract, &owner).await, 90);
+    assert_eq!(balance_of(&contract, &some_acc).await, 10);
+}
+
+#[cfg(feature = "mock")]
+#[tokio::test]
+async fn test_set_fault_by_not_owner() {
+    let (contract, engine) = deploy_and_init().await.unwrap();
+    let err = contract
+        .as_account(&engine)
+        .set_fault("ft_transfer", Fault::BurnGas(1_000_000))
+        .transact()
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("ERR_NOT_ALLOWED"));
+}
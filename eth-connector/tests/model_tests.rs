@@ -0,0 +1,139 @@
+#![cfg(feature = "model")]
+
+#[cfg(feature = "mock")]
+use aurora_workspace_eth_connector::mock::Fault;
+#[cfg(feature = "mock")]
+use aurora_workspace_eth_connector::model::ModelFailure;
+use aurora_workspace_eth_connector::model::{ConnectorState, Feature, ModelHarness, Operation};
+use aurora_workspace_eth_connector::types::{PAUSE_DEPOSIT, UNPAUSE_ALL};
+use aurora_workspace_utils::compile::compile_project;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+
+static CONTRACT_PATH: LazyLock<PathBuf> =
+    LazyLock::new(|| compile_project("../res/mock_eth_connector"));
+
+#[test]
+fn test_connector_state() {
+    let mut state = ConnectorState::new(2);
+    assert!(state.apply(&Operation::Deposit {
+        account: 0,
+        amount: 100
+    }));
+    // The receiver isn't registered.
+    let transfer = Operation::FtTransfer {
+        sender: 0,
+        receiver: 1,
+        amount: 40,
+    };
+    assert!(!state.apply(&transfer));
+    assert!(state.apply(&Operation::StorageDeposit { account: 1 }));
+    assert!(state.apply(&transfer));
+    assert!(!state.apply(&Operation::EngineFtTransfer {
+        sender: 1,
+        receiver: 0,
+        amount: 41,
+    }));
+    assert!(state.apply(&Operation::FtTransferCall {
+        sender: 1,
+        receiver: 0,
+        amount: 40,
+    }));
+    assert_eq!(state.balances, vec![Some(60), Some(40)]);
+
+    assert!(state.apply(&Operation::Pause(Feature::Deposit)));
+    assert_eq!(state.paused, PAUSE_DEPOSIT);
+    assert!(!state.apply(&Operation::Deposit {
+        account: 1,
+        amount: 1
+    }));
+    assert!(state.apply(&Operation::Withdraw {
+        account: 0,
+        amount: 10
+    }));
+    assert!(state.apply(&Operation::Unpause(Feature::Deposit)));
+    assert_eq!(state.paused, UNPAUSE_ALL);
+
+    let unregister = |force| Operation::StorageUnregister { account: 1, force };
+    assert!(!state.apply(&unregister(false)));
+    assert!(state.apply(&unregister(true)));
+    assert!(state.apply(&unregister(false)));
+    assert_eq!(state.balances, vec![Some(50), None]);
+    assert_eq!(state.total_supply, 50);
+    assert_eq!(transfer.to_string(), "ft_transfer(user0 -> user1, 40)");
+}
+
+#[tokio::test]
+async fn test_model_harness() {
+    let harness = ModelHarness::new(CONTRACT_PATH.as_path(), 3)
+        .await
+        .unwrap()
+        .cases(4)
+        .max_operations(8);
+    assert_eq!(harness.users().len(), 3);
+
+    let operations = [
+        Operation::Deposit {
+            account: 0,
+            amount: 100,
+        },
+        Operation::StorageDeposit { account: 1 },
+        Operation::FtTransfer {
+            sender: 0,
+            receiver: 1,
+            amount: 30,
+        },
+        Operation::FtTransferCall {
+            sender: 1,
+            receiver: 0,
+            amount: 10,
+        },
+        Operation::EngineFtTransfer {
+            sender: 1,
+            receiver: 0,
+            amount: 10,
+        },
+        Operation::Pause(Feature::Withdraw),
+        Operation::Withdraw {
+            account: 0,
+            amount: 10,
+        },
+        Operation::Unpause(Feature::Withdraw),
+        Operation::Withdraw {
+            account: 0,
+            amount: 10,
+        },
+        Operation::StorageUnregister {
+            account: 1,
+            force: true,
+        },
+    ];
+    let mismatch = harness.run(&operations).await.unwrap();
+    assert!(mismatch.is_none(), "{}", mismatch.unwrap());
+
+    harness.check().await.unwrap();
+}
+
+#[cfg(feature = "mock")]
+#[tokio::test]
+async fn test_model_harness_shrinks_mismatch() {
+    // Every mint fails, while the model expects the unpaused deposits to succeed.
+    let harness = ModelHarness::new(CONTRACT_PATH.as_path(), 2)
+        .await
+        .unwrap()
+        .cases(16)
+        .max_operations(8)
+        .fault("mint", Fault::Panic("ERR_INJECTED".to_string()));
+
+    let error = harness.check().await.unwrap_err();
+    let failure = error.downcast_ref::<ModelFailure>().unwrap();
+    assert_eq!(failure.operations.len(), 1, "{failure}");
+    assert!(matches!(failure.operations[0], Operation::Deposit { .. }));
+    assert_eq!(failure.mismatch.step, 0);
+    assert!(failure.mismatch.succeeded);
+    assert!(failure
+        .mismatch
+        .error
+        .as_deref()
+        .is_some_and(|error| error.contains("ERR_INJECTED")));
+}
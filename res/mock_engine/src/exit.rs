@@ -0,0 +1,146 @@
+use crate::xcc::XCC_PRECOMPILE;
+use crate::*;
+use near_sdk::serde_json::{self, json};
+use near_sdk::Gas;
+
+/// `keccak("exitToNear")[12..]`
+const EXIT_TO_NEAR: [u8; 20] = [
+    0xe9, 0x21, 0x7b, 0xc7, 0x0b, 0x7e, 0xd1, 0xf5, 0x98, 0xdd, 0xd3, 0x19, 0x9e, 0x80, 0xb0, 0x93,
+    0xfa, 0x71, 0x12, 0x4f,
+];
+/// `keccak("exitToEthereum")[12..]`
+const EXIT_TO_ETHEREUM: [u8; 20] = [
+    0xb0, 0xbd, 0x02, 0xf6, 0xa3, 0x92, 0xaf, 0x54, 0x8b, 0xdf, 0x1c, 0xfa, 0xee, 0x5d, 0xfa, 0x0e,
+    0xef, 0xcc, 0x8e, 0xab,
+];
+/// Selector of `withdrawToNear(bytes,uint256)` of the bridged ERC-20 tokens.
+const WITHDRAW_TO_NEAR: [u8; 4] = [0x6b, 0x35, 0x18, 0x48];
+/// Selector of `withdrawToEthereum(address,uint256)` of the bridged ERC-20 tokens.
+const WITHDRAW_TO_ETHEREUM: [u8; 4] = [0x8d, 0x32, 0xca, 0xf4];
+/// Flag of the precompile input which exits base ETH.
+const FLAG_ETH: u8 = 0;
+const EXIT_GAS: Gas = Gas(10_000_000_000_000);
+
+impl MockEngineContract {
+    /// Transfers the value from the sender to the contract and emulates the precompiles
+    /// and the exits of the bridged ERC-20 tokens.
+    pub(crate) fn execute_call(
+        &mut self,
+        sender: &[u8; 20],
+        contract: &[u8; 20],
+        value: u128,
+        input: &[u8],
+    ) {
+        let sender_balance = self.balances.get(sender).unwrap_or_default();
+        require!(sender_balance >= value, "ERR_OUT_OF_FUND");
+        self.balances.insert(sender, &(sender_balance - value));
+        let contract_balance = self.balances.get(contract).unwrap_or_default();
+        self.balances.insert(contract, &(contract_balance + value));
+
+        if *contract == XCC_PRECOMPILE {
+            self.xcc_precompile(sender, input);
+        }
+        self.exit_precompile(contract, value, input);
+        if let Some(nep141) = self.erc20_nep141.get(contract) {
+            self.exit_erc20(nep141, input);
+        }
+    }
+
+    /// Returns `true` for the exit precompiles and the bridged ERC-20 tokens.
+    pub(crate) fn is_exit(&self, address: &[u8; 20]) -> bool {
+        *address == EXIT_TO_NEAR
+            || *address == EXIT_TO_ETHEREUM
+            || self.erc20_nep141.get(address).is_some()
+    }
+
+    /// Emulates the exit precompiles for base ETH. The value sent to the precompile is
+    /// burned on Aurora, minted to the engine on NEAR and released by a promise to the
+    /// engine itself: `ft_transfer` for `exitToNear` and `withdraw` for `exitToEthereum`.
+    pub(crate) fn exit_precompile(&mut self, precompile: &[u8; 20], value: u128, input: &[u8]) {
+        let (method, args) = match (*precompile, input.split_first()) {
+            (EXIT_TO_NEAR, Some((&FLAG_ETH, recipient))) => {
+                let receiver_id = std::str::from_utf8(recipient)
+                    .unwrap_or_else(|_| env::panic_str("ERR_INVALID_RECEIVER_ACCOUNT_ID"));
+                let args = json!({ "receiver_id": receiver_id, "amount": value.to_string() });
+                ("ft_transfer", serde_json::to_vec(&args).unwrap())
+            }
+            (EXIT_TO_ETHEREUM, Some((&FLAG_ETH, recipient))) => {
+                require!(recipient.len() == 20, "ERR_INVALID_RECIPIENT_ADDRESS");
+                let mut address = [0u8; 20];
+                address.copy_from_slice(recipient);
+                ("withdraw", (address, value).try_to_vec().unwrap())
+            }
+            _ => return,
+        };
+
+        let precompile_balance = self.balances.get(precompile).unwrap_or_default();
+        self.balances
+            .insert(precompile, &(precompile_balance - value));
+        self.total_eth_supply_on_aurora = self.total_eth_supply_on_aurora.saturating_sub(value);
+
+        let engine_id: AccountId = env::current_account_id().as_str().parse().unwrap();
+        self.release(engine_id, method, args, value);
+    }
+
+    /// Emulates `withdrawToNear` and `withdrawToEthereum` of the bridged ERC-20 tokens. There
+    /// are no token contracts in the mock, so the calldata is decoded here and the NEP-141
+    /// counterpart is released by a promise to the NEP-141 token: `ft_transfer` for
+    /// `withdrawToNear` and `withdraw` for `withdrawToEthereum`.
+    fn exit_erc20(&mut self, nep141: AccountId, input: &[u8]) {
+        let Some((selector, args)) = input.split_first_chunk::<4>() else {
+            return;
+        };
+        let word = |offset: usize| -> [u8; 32] {
+            args.get(offset..offset + 32)
+                .and_then(|word| word.try_into().ok())
+                .unwrap_or_else(|| env::panic_str("ERR_INVALID_INPUT"))
+        };
+        let (method, args, amount) = match *selector {
+            WITHDRAW_TO_NEAR => {
+                let offset = wei_to_u128(&word(0)) as usize;
+                let amount = wei_to_u128(&word(32));
+                let len = wei_to_u128(&word(offset)) as usize;
+                let receiver_id = args
+                    .get(offset + 32..offset + 32 + len)
+                    .and_then(|recipient| std::str::from_utf8(recipient).ok())
+                    .unwrap_or_else(|| env::panic_str("ERR_INVALID_RECEIVER_ACCOUNT_ID"));
+                let args = json!({ "receiver_id": receiver_id, "amount": amount.to_string() });
+                ("ft_transfer", serde_json::to_vec(&args).unwrap(), amount)
+            }
+            WITHDRAW_TO_ETHEREUM => {
+                let mut recipient = [0u8; 20];
+                recipient.copy_from_slice(&word(0)[12..]);
+                let amount = wei_to_u128(&word(32));
+                (
+                    "withdraw",
+                    (recipient, amount).try_to_vec().unwrap(),
+                    amount,
+                )
+            }
+            _ => return,
+        };
+        self.release(nep141, method, args, amount);
+    }
+
+    /// Mints the amount to the engine and releases it by a promise to the NEP-141 token.
+    /// The tests bridge the engine's own NEP-141, so the minted amount covers the release.
+    fn release(&mut self, token_id: AccountId, method: &str, args: Vec<u8>, amount: u128) {
+        let engine_id: AccountId = env::current_account_id().as_str().parse().unwrap();
+        let engine_balance = self.ft_balances.get(&engine_id).unwrap_or_default();
+        self.ft_balances
+            .insert(&engine_id, &(engine_balance + amount));
+        self.ft_total_supply += amount;
+
+        Promise::new(token_id.as_ref().parse().unwrap()).function_call(
+            method.to_string(),
+            args,
+            1,
+            EXIT_GAS,
+        );
+    }
+
+    pub(crate) fn increment_nonce(&mut self, address: &[u8; 20]) {
+        let nonce = self.nonces.get(address).unwrap_or_default();
+        self.nonces.insert(address, &(nonce + 1));
+    }
+}
@@ -0,0 +1,48 @@
+use aurora_engine_types::parameters::engine::TransactionStatus;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::env;
+
+/// Fault injected into a method of the mock to test the error handling of clients.
+#[derive(Debug, Clone, BorshSerialize, BorshDeserialize)]
+pub enum Fault {
+    /// The method panics with the given error, e.g. `ERR_NOT_ALLOWED`.
+    Panic(String),
+    /// The EVM execution reverts with the given output.
+    Revert(Vec<u8>),
+    /// The EVM execution runs out of gas.
+    OutOfGas,
+    /// The method burns the given amount of NEAR gas before executing.
+    BurnGas(u64),
+}
+
+impl Fault {
+    /// Applies the fault. Returns the status which the method must return instead of
+    /// executing if the fault is a failed EVM execution.
+    pub fn apply(self) -> Option<TransactionStatus> {
+        match self {
+            Self::Panic(error) => env::panic_str(&error),
+            Self::Revert(output) => Some(TransactionStatus::Revert(output)),
+            Self::OutOfGas => Some(TransactionStatus::OutOfGas),
+            Self::BurnGas(gas) => {
+                burn_gas(gas);
+                None
+            }
+        }
+    }
+}
+
+/// The engine error of a failed status, used by methods which don't return `SubmitResult`.
+pub fn status_error(status: &TransactionStatus) -> &'static str {
+    match status {
+        TransactionStatus::OutOfGas => "ERR_OUT_OF_GAS",
+        _ => "ERR_REVERT",
+    }
+}
+
+fn burn_gas(gas: u64) {
+    let limit = env::used_gas().0.saturating_add(gas);
+    let mut hash = env::keccak256(&[]);
+    while env::used_gas().0 < limit {
+        hash = env::keccak256(&hash);
+    }
+}
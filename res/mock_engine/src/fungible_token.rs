@@ -3,25 +3,41 @@ use aurora_engine_types::types::Yocto;
 use near_contract_standards::fungible_token::metadata::FungibleTokenMetadata;
 use near_sdk::json_types::U128;
 use near_sdk::serde_json;
-use near_sdk::PromiseOrValue;
+use near_sdk::{assert_one_yocto, PromiseOrValue};
 use serde::Serialize;
 
 #[near_bindgen]
 impl MockEngineContract {
-    #[allow(unused_variables)]
     #[payable]
     pub fn ft_transfer(&mut self, receiver_id: AccountId, amount: U128, memo: Option<String>) {
-        use std::str::FromStr;
-        assert_eq!(
-            receiver_id,
-            AccountId::from_str("some_account.test").unwrap()
-        );
-        assert_eq!(amount.0, 10);
-        assert_eq!(memo, Some("some message".to_string()));
+        self.assert_no_fault("ft_transfer");
+        assert_one_yocto();
+        let sender_id = predecessor_account_id();
+        self.internal_ft_transfer(&sender_id, &receiver_id, amount.0, memo);
     }
 
+    /// Credits the ETH balance of the recipient and the relayer when ETH is deposited,
+    /// or checks that the bridged NEP-141 token is registered.
     #[allow(unused_variables)]
     pub fn ft_on_transfer(&mut self, sender_id: AccountId, amount: U128, msg: String) -> String {
+        self.assert_no_fault("ft_on_transfer");
+        let token = env::predecessor_account_id();
+
+        if token == env::current_account_id() {
+            self.deposit_eth(amount.0, &msg);
+        } else {
+            let token: AccountId = token.as_str().parse().unwrap();
+            require!(
+                self.nep141_erc20.get(&token).is_some(),
+                "ERR_NEP141_NOT_FOUND"
+            );
+            let message = parse_message(&msg);
+            require!(
+                message.relayer.is_none(),
+                "ERR_INVALID_ON_TRANSFER_MESSAGE_FORMAT"
+            );
+        }
+
         serde_json::to_string(&0).expect("Failed to serialize message")
     }
 
@@ -34,19 +50,51 @@ impl MockEngineContract {
         memo: Option<String>,
         msg: String,
     ) -> PromiseOrValue<U128> {
+        self.assert_no_fault("ft_transfer_call");
+        assert_one_yocto();
+        let sender_id = predecessor_account_id();
+        self.internal_ft_transfer(&sender_id, &receiver_id, amount.0, memo);
+        // The transfers to the engine itself deposit the ETH to the EVM.
+        if receiver_id.as_ref() == env::current_account_id().as_str() {
+            self.deposit_eth(amount.0, &msg);
+        }
         PromiseOrValue::Value(amount)
     }
 
-    pub fn ft_total_eth_supply_on_aurora(&self) -> String {
-        "0".into()
+    /// Burns the ETH of the caller to be released on Ethereum.
+    #[payable]
+    #[result_serializer(borsh)]
+    pub fn withdraw(&mut self, #[serializer(borsh)] input: ([u8; 20], u128)) -> WithdrawResult {
+        self.assert_no_fault("withdraw");
+        assert_one_yocto();
+        let (recipient_id, amount) = input;
+        let sender_id = predecessor_account_id();
+        let balance = self.ft_balances.get(&sender_id).unwrap_or_default();
+        require!(balance >= amount, "ERR_NOT_ENOUGH_BALANCE");
+        self.ft_balances.insert(&sender_id, &(balance - amount));
+        self.ft_total_supply -= amount;
+
+        WithdrawResult {
+            amount,
+            recipient_id,
+            eth_custodian_address: [0u8; 20],
+        }
+    }
+
+    pub fn ft_total_eth_supply_on_aurora(&self) -> U128 {
+        self.total_eth_supply_on_aurora.into()
     }
 
-    pub fn ft_total_supply(&self) -> u128 {
-        0
+    pub fn ft_total_eth_supply_on_near(&self) -> U128 {
+        self.ft_total_supply.into()
     }
 
-    pub fn ft_balance_of(&self, #[serializer(borsh)] _account: String) -> u128 {
-        0
+    pub fn ft_total_supply(&self) -> U128 {
+        self.ft_total_supply.into()
+    }
+
+    pub fn ft_balance_of(&self, account_id: AccountId) -> U128 {
+        self.ft_balances.get(&account_id).unwrap_or_default().into()
     }
 
     #[result_serializer(borsh)]
@@ -62,12 +110,17 @@ impl MockEngineContract {
         }
     }
 
-    #[result_serializer(borsh)]
-    pub fn ft_balance_of_eth(&self, #[serializer(borsh)] _address: [u8; 20]) -> [u64; 4] {
-        [0u64; 4]
+    pub fn ft_balance_of_eth(&self, #[serializer(borsh)] address: Address) -> U128 {
+        self.balances
+            .get(&address.raw().0)
+            .unwrap_or_default()
+            .into()
     }
 
-    pub fn storage_balance_of(&self, #[serializer(borsh)] _account: AccountId) -> StorageBalance {
+    pub fn storage_balance_of(
+        &self,
+        #[allow(unused_variables)] account_id: AccountId,
+    ) -> StorageBalance {
         StorageBalance::default()
     }
 
@@ -76,12 +129,121 @@ impl MockEngineContract {
     }
 }
 
+impl MockEngineContract {
+    /// Credits the deposited ETH to the recipient of the message, less the fee which
+    /// is credited to the EVM address of the relayer.
+    fn deposit_eth(&mut self, amount: u128, msg: &str) {
+        let message = parse_message(msg);
+        let fee = message.relayer.as_ref().map_or(0, |(_, fee)| *fee);
+        require!(fee <= amount, "ERR_NOT_ENOUGH_BALANCE_FOR_FEE");
+
+        self.credit(message.recipient, amount - fee);
+        if let Some((relayer_id, fee)) = message.relayer {
+            self.credit(keccak_address(relayer_id.as_bytes()), fee);
+        }
+        self.total_eth_supply_on_aurora += amount;
+    }
+
+    fn credit(&mut self, address: [u8; 20], amount: u128) {
+        let balance = self.balances.get(&address).unwrap_or_default();
+        self.balances.insert(&address, &(balance + amount));
+    }
+
+    fn internal_ft_transfer(
+        &mut self,
+        sender_id: &AccountId,
+        receiver_id: &AccountId,
+        amount: u128,
+        memo: Option<String>,
+    ) {
+        require!(sender_id != receiver_id, "ERR_SENDER_EQUALS_RECEIVER");
+        let sender_balance = self.ft_balances.get(sender_id).unwrap_or_default();
+        require!(sender_balance >= amount, "ERR_NOT_ENOUGH_BALANCE");
+        let receiver_balance = self.ft_balances.get(receiver_id).unwrap_or_default();
+
+        self.ft_balances
+            .insert(sender_id, &(sender_balance - amount));
+        self.ft_balances
+            .insert(receiver_id, &(receiver_balance + amount));
+
+        let event = serde_json::json!({
+            "standard": "nep141",
+            "version": "1.0.0",
+            "event": "ft_transfer",
+            "data": [{
+                "old_owner_id": sender_id,
+                "new_owner_id": receiver_id,
+                "amount": amount.to_string(),
+                "memo": memo,
+            }],
+        });
+        env::log_str(&format!("EVENT_JSON:{event}"));
+    }
+}
+
+fn predecessor_account_id() -> AccountId {
+    env::predecessor_account_id().as_str().parse().unwrap()
+}
+
+/// Message of `ft_transfer_call` to the engine.
+struct DepositMessage {
+    recipient: [u8; 20],
+    /// Relayer account id and the fee paid to it.
+    relayer: Option<(String, u128)>,
+}
+
+/// The message is the hex encoded recipient address, or `<relayer>:<fee><recipient>`
+/// with the fee hex encoded as the little-endian `U256`.
+fn parse_message(msg: &str) -> DepositMessage {
+    let (relayer_id, data) = match msg.split_once(':') {
+        Some(parts) => parts,
+        None => {
+            return DepositMessage {
+                recipient: parse_recipient(msg),
+                relayer: None,
+            }
+        }
+    };
+    require!(
+        !relayer_id.is_empty() && data.len() == 104,
+        "ERR_INVALID_ON_TRANSFER_MESSAGE_FORMAT"
+    );
+    let mut fee = [0u8; 32];
+    hex::decode_to_slice(&data[..64], &mut fee)
+        .unwrap_or_else(|_| env::panic_str("ERR_INVALID_ON_TRANSFER_MESSAGE_FORMAT"));
+    require!(fee[16..].iter().all(|b| *b == 0), "ERR_FEE_OVERFLOW");
+
+    DepositMessage {
+        recipient: parse_recipient(&data[64..]),
+        relayer: Some((
+            relayer_id.to_string(),
+            u128::from_le_bytes(fee[..16].try_into().unwrap()),
+        )),
+    }
+}
+
+/// The recipient is the hex encoded EVM address.
+fn parse_recipient(hex: &str) -> [u8; 20] {
+    require!(hex.len() == 40, "ERR_INVALID_ON_TRANSFER_MESSAGE_FORMAT");
+    let mut recipient = [0u8; 20];
+    hex::decode_to_slice(hex, &mut recipient)
+        .unwrap_or_else(|_| env::panic_str("ERR_INVALID_RECIPIENT_ADDRESS"));
+    recipient
+}
+
 #[derive(Default, Serialize)]
 pub struct StorageBalance {
     pub total: Yocto,
     pub available: Yocto,
 }
 
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct WithdrawResult {
+    pub amount: u128,
+    pub recipient_id: [u8; 20],
+    pub eth_custodian_address: [u8; 20],
+}
+
 #[derive(BorshSerialize, BorshDeserialize)]
 pub struct IsUsedProofCallArgs {
     /// Proof data
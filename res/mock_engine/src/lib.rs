@@ -1,22 +1,63 @@
 use aurora_engine_types::account_id::AccountId;
 use aurora_engine_types::parameters::connector::InitCallArgs;
 use aurora_engine_types::parameters::engine::{
-    CallArgs, DeployErc20TokenArgs, NewCallArgs, SubmitResult, TransactionStatus,
+    CallArgs, DeployErc20TokenArgs, NewCallArgs, ResultLog, SubmitResult, TransactionStatus,
+    ViewCallArgs,
 };
+use aurora_engine_types::parameters::xcc::FundXccArgs;
 use aurora_engine_types::parameters::RefundCallArgs;
 use aurora_engine_types::types::Address;
+use faults::Fault;
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::{near_bindgen, serde, PanicOnDefault};
+use near_sdk::collections::LookupMap;
+use near_sdk::{
+    env, near_bindgen, require, serde, BorshStorageKey, IntoStorageKey, PanicOnDefault, Promise,
+    PublicKey,
+};
+use transaction::{create_address, SignedTransaction};
 
+mod exit;
+mod faults;
 mod fungible_token;
+mod transaction;
+mod xcc;
 // mod storage;
 
+/// EVM gas reported for a plain value transfer.
+const TRANSFER_GAS: u64 = 21_000;
+/// Methods which can be called with the relayer keys.
+const RELAYER_KEY_METHODS: &str = "submit,submit_with_args";
+
+#[derive(BorshSerialize, BorshStorageKey)]
+enum StorageKey {
+    Balances,
+    Nonces,
+    Codes,
+    Nep141Erc20,
+    Erc20Nep141,
+    FtBalances,
+    Faults,
+    RouterCode,
+}
+
 #[near_bindgen]
 #[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
 pub struct MockEngineContract {
     pub chain_id: [u8; 32],
     pub owner_id: AccountId,
     pub upgrade_delay_blocks: u64,
+    pub paused_precompiles: u32,
+    pub paused_flags: u8,
+    pub key_manager: Option<AccountId>,
+    balances: LookupMap<[u8; 20], u128>,
+    nonces: LookupMap<[u8; 20], u64>,
+    codes: LookupMap<[u8; 20], Vec<u8>>,
+    nep141_erc20: LookupMap<AccountId, [u8; 20]>,
+    erc20_nep141: LookupMap<[u8; 20], AccountId>,
+    ft_balances: LookupMap<AccountId, u128>,
+    ft_total_supply: u128,
+    total_eth_supply_on_aurora: u128,
+    faults: LookupMap<String, Fault>,
 }
 
 #[near_bindgen]
@@ -33,34 +74,111 @@ impl MockEngineContract {
             chain_id: input.chain_id,
             owner_id: input.owner_id,
             upgrade_delay_blocks: input.upgrade_delay_blocks,
+            paused_precompiles: 0,
+            paused_flags: 0,
+            key_manager: None,
+            balances: LookupMap::new(StorageKey::Balances),
+            nonces: LookupMap::new(StorageKey::Nonces),
+            codes: LookupMap::new(StorageKey::Codes),
+            nep141_erc20: LookupMap::new(StorageKey::Nep141Erc20),
+            erc20_nep141: LookupMap::new(StorageKey::Erc20Nep141),
+            ft_balances: LookupMap::new(StorageKey::FtBalances),
+            ft_total_supply: 0,
+            total_eth_supply_on_aurora: 0,
+            faults: LookupMap::new(StorageKey::Faults),
         }
     }
 
+    /// Deploys the code passed as raw input. The address is derived from the sender
+    /// and its nonce, which differs from the real engine but is deterministic.
     #[result_serializer(borsh)]
-    pub fn deploy_code(&mut self, #[serializer(borsh)] _input: Vec<u8>) -> SubmitResult {
-        dummy_submit_result()
+    pub fn deploy_code(&mut self) -> SubmitResult {
+        if let Some(status) = self.fault("deploy_code") {
+            return SubmitResult::new(status, 0, vec![]);
+        }
+        let code = env::input().unwrap_or_default();
+        let sender = predecessor_address();
+        let nonce = self.nonces.get(&sender).unwrap_or_default();
+        let address = keccak_address(&[&sender[..], &nonce.to_be_bytes()].concat());
+
+        self.nonces.insert(&sender, &(nonce + 1));
+        self.codes.insert(&address, &code);
+
+        SubmitResult::new(TransactionStatus::Succeed(address.to_vec()), 0, vec![])
     }
 
     #[result_serializer(borsh)]
     pub fn deploy_erc20_token(
         &mut self,
-        #[serializer(borsh)] _input: DeployErc20TokenArgs,
+        #[serializer(borsh)] input: DeployErc20TokenArgs,
     ) -> Vec<u8> {
-        Address::from_array([1; 20]).as_bytes().to_vec()
+        self.assert_no_fault("deploy_erc20_token");
+        require!(
+            self.nep141_erc20.get(&input.nep141).is_none(),
+            "ERR_NEP141_TOKEN_ALREADY_REGISTERED"
+        );
+        let address = keccak_address(input.nep141.as_bytes());
+
+        self.nep141_erc20.insert(&input.nep141, &address);
+        self.erc20_nep141.insert(&address, &input.nep141);
+
+        address.to_vec()
     }
 
+    /// Transfers the attached value from the EVM address of the caller to the contract.
+    /// Base ETH sent to the exit precompiles and bridged ERC-20 tokens withdrawn from
+    /// Aurora are released on NEAR.
     #[result_serializer(borsh)]
-    pub fn call(&mut self, #[serializer(borsh)] _input: CallArgs) -> SubmitResult {
-        dummy_submit_result()
+    pub fn call(&mut self, #[serializer(borsh)] input: CallArgs) -> SubmitResult {
+        if let Some(status) = self.fault("call") {
+            return SubmitResult::new(status, 0, vec![]);
+        }
+        let sender = predecessor_address();
+
+        if let CallArgs::V2(args) = input {
+            let value = wei_to_u128(&args.value);
+            self.execute_call(&sender, &args.contract.raw().0, value, &args.input);
+        }
+        self.increment_nonce(&sender);
+
+        SubmitResult::new(TransactionStatus::Succeed(vec![]), TRANSFER_GAS, vec![])
     }
 
+    /// Takes the raw RLP encoded transaction as input, as the real engine does.
+    /// The signed exits are executed the same way as by `call`, any other transaction
+    /// emits a single log with the hash of the input as the topic.
     #[result_serializer(borsh)]
-    pub fn submit(&mut self, #[serializer(borsh)] _input: Vec<u8>) -> SubmitResult {
-        dummy_submit_result()
+    pub fn submit(&mut self) -> SubmitResult {
+        if let Some(status) = self.fault("submit") {
+            return SubmitResult::new(status, 0, vec![]);
+        }
+        let input = env::input().unwrap_or_default();
+        if let Some(tx) = SignedTransaction::decode(&input) {
+            if let Some(to) = tx.to.filter(|to| self.is_exit(to)) {
+                self.execute_call(&tx.sender, &to, tx.value, &tx.data);
+                self.increment_nonce(&tx.sender);
+                return SubmitResult::new(TransactionStatus::Succeed(vec![]), TRANSFER_GAS, vec![]);
+            }
+            if tx.to.is_none() {
+                // Deploys the init code as is, the constructor isn't executed.
+                let nonce = self.nonces.get(&tx.sender).unwrap_or_default();
+                let address = create_address(&tx.sender, nonce);
+                self.codes.insert(&address, &tx.data);
+                self.increment_nonce(&tx.sender);
+                return SubmitResult::new(TransactionStatus::Succeed(address.to_vec()), 0, vec![]);
+            }
+        }
+        let log = ResultLog {
+            address: Address::from_array(keccak_address(&input)),
+            topics: vec![env::keccak256(&input).try_into().unwrap()],
+            data: input,
+        };
+        SubmitResult::new(TransactionStatus::Succeed(vec![]), 0, vec![log])
     }
 
     #[result_serializer(borsh)]
     pub fn register_relayer(&mut self, #[serializer(borsh)] input: [u8; 20]) {
+        self.assert_no_fault("register_relayer");
         let _ = Address::from_array(input);
     }
 
@@ -68,16 +186,73 @@ impl MockEngineContract {
     // AUTHORIZED CALL METHODS
     //
 
-    #[allow(unused_variables)]
-    pub fn pause_precompiles(&mut self, #[serializer(borsh)] paused_mask: u32) {}
+    pub fn pause_precompiles(&mut self, #[serializer(borsh)] paused_mask: u32) {
+        self.assert_owner();
+        self.paused_precompiles |= paused_mask;
+    }
+
+    /// Adds the function-call key limited to the submit methods of the engine,
+    /// the attached deposit becomes the allowance of the key.
+    #[payable]
+    pub fn add_relayer_key(&mut self, public_key: PublicKey) -> Promise {
+        self.assert_key_manager();
+        self.assert_no_fault("add_relayer_key");
+        Promise::new(env::current_account_id()).add_access_key(
+            public_key,
+            env::attached_deposit(),
+            env::current_account_id(),
+            RELAYER_KEY_METHODS.to_string(),
+        )
+    }
+
+    pub fn remove_relayer_key(&mut self, public_key: PublicKey) -> Promise {
+        self.assert_key_manager();
+        self.assert_no_fault("remove_relayer_key");
+        Promise::new(env::current_account_id()).delete_key(public_key)
+    }
 
     //
     // SELF CALL METHODS
     //
 
-    pub fn set_eth_connector_contract_data(&mut self, #[serializer(borsh)] _input: InitCallArgs) {}
+    /// Called by `EngineContractBuilder` right after `new`.
+    pub fn new_eth_connector(&mut self, #[serializer(borsh)] _input: InitCallArgs) {
+        self.assert_owner();
+    }
+
+    pub fn set_eth_connector_contract_data(&mut self, #[serializer(borsh)] _input: InitCallArgs) {
+        self.assert_owner();
+    }
 
-    pub fn set_paused_flags(&mut self, #[serializer(borsh)] _input: Vec<u8>) {}
+    pub fn set_paused_flags(&mut self, #[serializer(borsh)] paused_flags: u8) {
+        self.assert_owner();
+        self.paused_flags = paused_flags;
+    }
+
+    pub fn mint_account(&mut self, #[serializer(borsh)] input: (Address, u64, u64)) {
+        self.assert_owner();
+        self.assert_no_fault("mint_account");
+        let (address, nonce, balance) = input;
+        let address = address.raw().0;
+        let old_balance = self.balances.get(&address).unwrap_or_default();
+
+        self.nonces.insert(&address, &nonce);
+        self.balances.insert(&address, &u128::from(balance));
+        self.total_eth_supply_on_aurora =
+            self.total_eth_supply_on_aurora - old_balance + u128::from(balance);
+    }
+
+    /// Makes the method fail in the way described by the fault until it's cleared.
+    pub fn set_fault(&mut self, #[serializer(borsh)] input: (String, Fault)) {
+        self.assert_owner();
+        let (method, fault) = input;
+        self.faults.insert(&method, &fault);
+    }
+
+    pub fn clear_fault(&mut self, #[serializer(borsh)] method: String) {
+        self.assert_owner();
+        self.faults.remove(&method);
+    }
 
     //
     // CALLBACK HANDLER METHODS
@@ -100,41 +275,53 @@ impl MockEngineContract {
 
     #[result_serializer(borsh)]
     pub fn get_owner(&self) -> AccountId {
-        "owner.test.near".parse().unwrap()
+        self.owner_id.clone()
     }
 
-    pub fn get_chain_id(&self) -> String {
-        1313161556.to_string()
+    #[result_serializer(borsh)]
+    pub fn get_chain_id(&self) -> [u8; 32] {
+        self.chain_id
     }
 
+    /// There is no EVM in the mock, so the view only checks that the sender can pay the value.
     #[result_serializer(borsh)]
-    pub fn get_view(&self, #[serializer(borsh)] _input: Vec<u8>) -> TransactionStatus {
+    pub fn get_view(&self, #[serializer(borsh)] args: ViewCallArgs) -> TransactionStatus {
+        let balance = self.balances.get(&args.sender.raw().0).unwrap_or_default();
+        if balance < wei_to_u128(&args.amount) {
+            return TransactionStatus::OutOfFund;
+        }
         TransactionStatus::Succeed(vec![])
     }
 
-    #[result_serializer(borsh)]
-    pub fn get_code(&self, #[serializer(borsh)] _address: Address) -> Vec<u8> {
-        hex::decode(b"00000000000000000000000000000000000000000000000000000000000000200000000000000000000000000000000000000000000000000000000000000060000000000000000000000000000000000000000000000000000000000000000100000000000000000000000000000000000000000000000000000000000000a0000000000000000000000000000000000000000000000000000000000000000673706972616c000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000067175617361720000000000000000000000000000000000000000000000000000").unwrap()
+    /// Returns the raw code, as the real engine does.
+    pub fn get_code(&self, #[serializer(borsh)] address: Address) {
+        let code = self.codes.get(&address.raw().0).unwrap_or_default();
+        env::value_return(&code);
     }
 
+    /// There is no EVM in the mock, so the storage is always empty.
     #[result_serializer(borsh)]
-    pub fn get_storage_at(&self, #[serializer(borsh)] _input: Vec<u8>) -> [u8; 32] {
-        [1; 32]
+    pub fn get_storage_at(&self, #[serializer(borsh)] _input: (Address, [u8; 32])) -> [u8; 32] {
+        [0; 32]
     }
 
     #[result_serializer(borsh)]
-    pub fn get_erc20_from_nep141(&self, #[serializer(borsh)] _input: Vec<u8>) -> AccountId {
-        "erc20.test.near".parse().unwrap()
+    pub fn get_erc20_from_nep141(&self, #[serializer(borsh)] nep141: AccountId) -> [u8; 20] {
+        self.nep141_erc20
+            .get(&nep141)
+            .unwrap_or_else(|| env::panic_str("ERR_NEP141_NOT_FOUND"))
     }
 
     #[result_serializer(borsh)]
-    pub fn get_nep141_from_erc20(&self, #[serializer(borsh)] _input: Vec<u8>) -> AccountId {
-        "nep141.test.near".parse().unwrap()
+    pub fn get_nep141_from_erc20(&self, #[serializer(borsh)] address: Address) -> AccountId {
+        self.erc20_nep141
+            .get(&address.raw().0)
+            .unwrap_or_else(|| env::panic_str("ERR_ERC20_NOT_FOUND"))
     }
 
     #[result_serializer(borsh)]
-    pub fn get_paused_flags(&self, #[serializer(borsh)] _input: Vec<u8>) -> u8 {
-        0
+    pub fn get_paused_flags(&self) -> u8 {
+        self.paused_flags
     }
 
     #[result_serializer(borsh)]
@@ -144,7 +331,7 @@ impl MockEngineContract {
 
     #[result_serializer(borsh)]
     pub fn get_paused_precompiles(&self) -> u32 {
-        0u32
+        self.paused_precompiles
     }
 
     #[result_serializer(borsh)]
@@ -153,39 +340,128 @@ impl MockEngineContract {
     }
 
     #[result_serializer(borsh)]
-    pub fn get_balance(&self, #[serializer(borsh)] _address: Address) -> [u64; 4] {
-        [0u64; 4]
+    pub fn get_balance(&self, #[serializer(borsh)] address: Address) -> [u8; 32] {
+        u128_to_wei(self.balances.get(&address.raw().0).unwrap_or_default())
     }
 
     #[result_serializer(borsh)]
-    pub fn get_nonce(&self, #[serializer(borsh)] _address: Address) -> [u64; 4] {
-        [0u64; 4]
+    pub fn get_nonce(&self, #[serializer(borsh)] address: Address) -> [u8; 32] {
+        u128_to_wei(u128::from(
+            self.nonces.get(&address.raw().0).unwrap_or_default(),
+        ))
     }
 
     //
     // OWNER CALL METHODS
     //
 
-    #[allow(unused_variables)]
-    pub fn factory_update(&mut self, #[serializer(borsh)] bytes: Vec<u8>) {}
+    /// Stores the code which `fund_xcc_sub_account` deploys to the routers.
+    pub fn factory_update(&mut self) {
+        self.assert_owner();
+        let code = env::input().unwrap_or_default();
+        env::storage_write(&StorageKey::RouterCode.into_storage_key(), &code);
+    }
+
+    pub fn set_key_manager(&mut self, key_manager: Option<AccountId>) {
+        self.assert_owner();
+        self.key_manager = key_manager;
+    }
+
+    pub fn factory_set_wnear_address(&mut self, #[serializer(borsh)] _input: Address) {
+        self.assert_owner();
+    }
+
+    /// Creates the router sub-account of the target address funded with the deposit and
+    /// deploys the code set by `factory_update` to it.
+    #[payable]
+    pub fn fund_xcc_sub_account(&mut self, #[serializer(borsh)] input: FundXccArgs) -> Promise {
+        self.assert_no_fault("fund_xcc_sub_account");
+        let router_id = format!("{}.{}", input.target.encode(), env::current_account_id());
+        let promise = Promise::new(router_id.parse().unwrap())
+            .create_account()
+            .transfer(env::attached_deposit());
+        match env::storage_read(&StorageKey::RouterCode.into_storage_key()) {
+            Some(code) => promise.deploy_contract(code),
+            None => promise,
+        }
+    }
+
+    pub fn deploy_upgrade(&mut self) {
+        self.assert_owner();
+    }
+
+    pub fn state_migration(&mut self) {
+        self.assert_owner();
+    }
+
+    pub fn resume_precompiles(&mut self, #[serializer(borsh)] paused_mask: u32) {
+        self.assert_owner();
+        self.paused_precompiles &= !paused_mask;
+    }
+
+    pub fn stage_upgrade(&mut self) {
+        self.assert_owner();
+    }
+}
 
-    #[allow(unused_variables)]
-    pub fn factory_set_wnear_address(&mut self, #[serializer(borsh)] _input: Address) {}
+impl MockEngineContract {
+    /// The owner and the contract itself are allowed to call privileged methods.
+    fn assert_owner(&self) {
+        let predecessor = env::predecessor_account_id();
+        require!(
+            predecessor.as_str() == self.owner_id.as_ref()
+                || predecessor == env::current_account_id(),
+            "ERR_NOT_ALLOWED"
+        );
+    }
 
-    #[allow(unused_variables)]
-    pub fn deploy_upgrade(&mut self) {}
+    fn assert_key_manager(&self) {
+        let predecessor = env::predecessor_account_id();
+        require!(
+            self.key_manager
+                .as_ref()
+                .map_or(false, |manager| manager.as_ref() == predecessor.as_str()),
+            "ERR_KEY_MANAGER_IS_NOT_SET_OR_NOT_ALLOWED"
+        );
+    }
 
-    pub fn state_migration(&mut self) {}
+    /// Applies the fault set for the method. Returns the status which the method must
+    /// return instead of executing if the fault is a failed EVM execution.
+    fn fault(&self, method: &str) -> Option<TransactionStatus> {
+        self.faults.get(&method.to_string()).and_then(Fault::apply)
+    }
 
-    #[allow(unused_variables)]
-    pub fn resume_precompiles(&mut self, #[serializer(borsh)] paused_mask: u32) {}
+    /// Same as `fault` for the methods which don't return `SubmitResult`.
+    fn assert_no_fault(&self, method: &str) {
+        if let Some(status) = self.fault(method) {
+            env::panic_str(faults::status_error(&status));
+        }
+    }
+}
+
+/// EVM address of a NEAR account: the last 20 bytes of the keccak hash of the account id.
+fn predecessor_address() -> [u8; 20] {
+    keccak_address(env::predecessor_account_id().as_bytes())
+}
+
+fn keccak_address(input: &[u8]) -> [u8; 20] {
+    let hash = env::keccak256(input);
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&hash[12..]);
+    address
+}
 
-    #[allow(unused_variables)]
-    pub fn stage_upgrade(&mut self, #[serializer(borsh)] input: Vec<u8>) {}
+fn wei_to_u128(value: &[u8; 32]) -> u128 {
+    require!(value[..16].iter().all(|b| *b == 0), "ERR_VALUE_OVERFLOW");
+    let mut buf = [0u8; 16];
+    buf.copy_from_slice(&value[16..]);
+    u128::from_be_bytes(buf)
 }
 
-fn dummy_submit_result() -> SubmitResult {
-    SubmitResult::new(TransactionStatus::Succeed(vec![]), 0, vec![])
+fn u128_to_wei(value: u128) -> [u8; 32] {
+    let mut result = [0u8; 32];
+    result[16..].copy_from_slice(&value.to_be_bytes());
+    result
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, BorshDeserialize, BorshSerialize)]
@@ -0,0 +1,133 @@
+use near_sdk::env;
+
+/// Legacy transaction signed with the EIP-155 replay protection, decoded by `submit`.
+pub(crate) struct SignedTransaction {
+    pub sender: [u8; 20],
+    pub to: Option<[u8; 20]>,
+    pub value: u128,
+    pub data: Vec<u8>,
+}
+
+impl SignedTransaction {
+    /// Decodes the transaction and recovers its sender, `None` if the input isn't
+    /// a signed legacy transaction.
+    pub(crate) fn decode(raw: &[u8]) -> Option<Self> {
+        let (true, payload, []) = rlp_split(raw)? else {
+            return None;
+        };
+        let mut fields = Vec::new();
+        let mut unsigned_len = 0;
+        let mut rest = payload;
+        while !rest.is_empty() {
+            if fields.len() == 6 {
+                unsigned_len = payload.len() - rest.len();
+            }
+            let (false, field, next) = rlp_split(rest)? else {
+                return None;
+            };
+            fields.push(field);
+            rest = next;
+        }
+        let [_, _, _, to, value, data, v, r, s] = fields.as_slice() else {
+            return None;
+        };
+
+        if r.len() > 32 || s.len() > 32 {
+            return None;
+        }
+        let v = be_u128(v)?.checked_sub(35)?;
+        let chain_id = rlp_integer(v / 2);
+        let mut unsigned = payload[..unsigned_len].to_vec();
+        unsigned.extend_from_slice(&chain_id);
+        unsigned.extend_from_slice(&[0x80, 0x80]);
+        let hash = env::keccak256(&rlp_list(&unsigned));
+
+        let mut signature = [0u8; 64];
+        signature[32 - r.len()..32].copy_from_slice(r);
+        signature[64 - s.len()..].copy_from_slice(s);
+        let public_key = env::ecrecover(&hash, &signature, (v % 2) as u8, true)?;
+        let mut sender = [0u8; 20];
+        sender.copy_from_slice(&env::keccak256(&public_key)[12..]);
+
+        let to = match to.len() {
+            0 => None,
+            20 => Some((*to).try_into().ok()?),
+            _ => return None,
+        };
+        Some(Self {
+            sender,
+            to,
+            value: be_u128(value)?,
+            data: data.to_vec(),
+        })
+    }
+}
+
+/// Address of the contract created by the sender with the nonce.
+pub(crate) fn create_address(sender: &[u8; 20], nonce: u64) -> [u8; 20] {
+    let payload = [&[0x94][..], sender, &rlp_integer(u128::from(nonce))].concat();
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&env::keccak256(&rlp_list(&payload))[12..]);
+    address
+}
+
+/// Splits the RLP item at the start of the data into whether it's a list, its payload and
+/// the rest of the data.
+fn rlp_split(data: &[u8]) -> Option<(bool, &[u8], &[u8])> {
+    let prefix = *data.first()?;
+    let (is_list, offset, len) = match prefix {
+        0x00..=0x7f => return Some((false, &data[..1], &data[1..])),
+        0x80..=0xb7 => (false, 1, usize::from(prefix - 0x80)),
+        0xb8..=0xbf => {
+            let size = usize::from(prefix - 0xb7);
+            (
+                false,
+                1 + size,
+                usize::try_from(be_u128(data.get(1..=size)?)?).ok()?,
+            )
+        }
+        0xc0..=0xf7 => (true, 1, usize::from(prefix - 0xc0)),
+        0xf8..=0xff => {
+            let size = usize::from(prefix - 0xf7);
+            (
+                true,
+                1 + size,
+                usize::try_from(be_u128(data.get(1..=size)?)?).ok()?,
+            )
+        }
+    };
+    let end = offset.checked_add(len).filter(|end| *end <= data.len())?;
+    Some((is_list, &data[offset..end], &data[end..]))
+}
+
+fn rlp_integer(value: u128) -> Vec<u8> {
+    let bytes = value.to_be_bytes();
+    let start = bytes.iter().position(|b| *b != 0).unwrap_or(bytes.len());
+    match &bytes[start..] {
+        [byte] if *byte < 0x80 => vec![*byte],
+        bytes => [&[0x80 + bytes.len() as u8], bytes].concat(),
+    }
+}
+
+fn rlp_list(payload: &[u8]) -> Vec<u8> {
+    let mut encoded = if payload.len() < 56 {
+        vec![0xc0 + payload.len() as u8]
+    } else {
+        let bytes = (payload.len() as u64).to_be_bytes();
+        let start = bytes.iter().position(|b| *b != 0).unwrap_or(bytes.len());
+        [&[0xf7 + (bytes.len() - start) as u8], &bytes[start..]].concat()
+    };
+    encoded.extend_from_slice(payload);
+    encoded
+}
+
+fn be_u128(bytes: &[u8]) -> Option<u128> {
+    if bytes.len() > 16 {
+        return None;
+    }
+    Some(
+        bytes
+            .iter()
+            .fold(0, |value, byte| (value << 8) | u128::from(*byte)),
+    )
+}
@@ -0,0 +1,53 @@
+use crate::*;
+use aurora_engine_types::parameters::{CrossContractCallArgs, PromiseArgs};
+use near_sdk::Gas;
+
+/// `keccak("nearCrossContractCall")[12..]`
+pub(crate) const XCC_PRECOMPILE: [u8; 20] = [
+    0x51, 0x6c, 0xde, 0xd1, 0xd1, 0x6a, 0xf1, 0x0c, 0xad, 0x47, 0xd6, 0xd4, 0x91, 0x28, 0xe2, 0xeb,
+    0x7d, 0x27, 0xb3, 0x72,
+];
+const ROUTER_GAS: Gas = Gas(50_000_000_000_000);
+/// Key of the promise stored by `schedule` in the router.
+const SCHEDULED_KEY: &[u8] = b"scheduled";
+
+impl MockEngineContract {
+    /// Emulates the XCC precompile. The promise is passed to the router sub-account of the
+    /// caller, which executes it immediately by `execute` or stores it by `schedule`.
+    pub(crate) fn xcc_precompile(&self, sender: &[u8; 20], input: &[u8]) {
+        let args = CrossContractCallArgs::try_from_slice(input)
+            .unwrap_or_else(|_| env::panic_str("ERR_INVALID_XCC_INPUT"));
+        let (method, promise) = match args {
+            CrossContractCallArgs::Eager(promise) => ("execute", promise),
+            CrossContractCallArgs::Delayed(promise) => ("schedule", promise),
+        };
+        let router_id = format!("{}.{}", hex::encode(sender), env::current_account_id());
+        Promise::new(router_id.parse().unwrap()).function_call(
+            method.to_string(),
+            promise.try_to_vec().unwrap(),
+            0,
+            ROUTER_GAS,
+        );
+    }
+}
+
+/// Methods of the XCC router. The mock engine is deployed as the router code too, so the
+/// methods don't read the state of the engine.
+#[near_bindgen]
+impl MockEngineContract {
+    pub fn execute(#[serializer(borsh)] promise: PromiseArgs) {
+        let PromiseArgs::Create(args) = promise else {
+            env::panic_str("ERR_UNSUPPORTED_PROMISE");
+        };
+        Promise::new(args.target_account_id.as_ref().parse().unwrap()).function_call(
+            args.method,
+            args.args,
+            args.attached_balance.as_u128(),
+            Gas(args.attached_gas.as_u64()),
+        );
+    }
+
+    pub fn schedule(#[serializer(borsh)] promise: PromiseArgs) {
+        env::storage_write(SCHEDULED_KEY, &promise.try_to_vec().unwrap());
+    }
+}
@@ -1,9 +1,17 @@
 use aurora_engine_types::types::Address;
 use near_contract_standards::storage_management::StorageBalance;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::{ext_contract, json_types::U128, AccountId, PromiseOrValue};
 
 type Balance = u128;
 
+#[derive(Debug, BorshSerialize, BorshDeserialize)]
+pub struct WithdrawResult {
+    pub amount: Balance,
+    pub recipient_id: Address,
+    pub eth_custodian_address: Address,
+}
+
 #[ext_contract(ext_withdraw)]
 pub trait ConnectorWithdraw {
     #[result_serializer(borsh)]
@@ -11,7 +19,7 @@ pub trait ConnectorWithdraw {
         &mut self,
         #[serializer(borsh)] recipient_address: Address,
         #[serializer(borsh)] amount: Balance,
-    );
+    ) -> WithdrawResult;
 
     #[result_serializer(borsh)]
     fn engine_withdraw(
@@ -19,7 +27,7 @@ pub trait ConnectorWithdraw {
         #[serializer(borsh)] sender_id: AccountId,
         #[serializer(borsh)] recipient_address: Address,
         #[serializer(borsh)] amount: Balance,
-    );
+    ) -> WithdrawResult;
 }
 
 /// Engine compatible methods for NEP-141
@@ -0,0 +1,31 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::env;
+use near_sdk::serde::{Deserialize, Serialize};
+
+/// Fault injected into a method of the mock to test the error handling of clients.
+#[derive(Debug, Clone, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub enum Fault {
+    /// The method panics with the given error. Set for `ft_resolve_transfer` it makes
+    /// the callback of `ft_transfer_call` fail, so the unused amount isn't refunded.
+    Panic(String),
+    /// The method burns the given amount of NEAR gas before executing.
+    BurnGas(u64),
+}
+
+impl Fault {
+    pub fn apply(self) {
+        match self {
+            Self::Panic(error) => env::panic_str(&error),
+            Self::BurnGas(gas) => burn_gas(gas),
+        }
+    }
+}
+
+fn burn_gas(gas: u64) {
+    let limit = env::used_gas().as_gas().saturating_add(gas);
+    let mut hash = env::keccak256(&[]);
+    while env::used_gas().as_gas() < limit {
+        hash = env::keccak256(&hash);
+    }
+}
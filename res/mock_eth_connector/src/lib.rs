@@ -1,31 +1,51 @@
 #![allow(unused_variables)]
 use crate::connector::{
     ConnectorWithdraw, EngineFungibleToken, EngineStorageManagement, KnownEngineAccountsManagement,
+    WithdrawResult,
 };
+use crate::faults::Fault;
 use crate::migration::{Migration, MigrationCheckResult, MigrationInputData};
 use aurora_engine_types::types::Address;
 use near_contract_standards::fungible_token::core::FungibleTokenCore;
 use near_contract_standards::fungible_token::metadata::{
-    FungibleTokenMetadata, FungibleTokenMetadataProvider, FT_METADATA_SPEC,
+    FungibleTokenMetadata, FungibleTokenMetadataProvider,
 };
-use near_contract_standards::fungible_token::resolver::FungibleTokenResolver;
+use near_contract_standards::fungible_token::receiver::ext_ft_receiver;
+use near_contract_standards::fungible_token::resolver::{ext_ft_resolver, FungibleTokenResolver};
+use near_contract_standards::fungible_token::FungibleToken;
 use near_contract_standards::storage_management::{
     StorageBalance, StorageBalanceBounds, StorageManagement,
 };
 use near_plugins::{access_control, AccessControlRole, AccessControllable, Pausable, Upgradable};
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::{LazyOption, LookupMap, LookupSet};
 use near_sdk::json_types::U128;
 use near_sdk::serde::{Deserialize, Serialize};
 use near_sdk::{
-    assert_one_yocto, env, near, near_bindgen, AccountId, NearToken, PanicOnDefault, PromiseOrValue,
+    assert_one_yocto, env, near_bindgen, require, AccountId, BorshStorageKey, Gas, NearToken,
+    PanicOnDefault, Promise, PromiseOrValue,
 };
+use std::collections::HashMap;
 
 type Balance = u128;
 
 #[allow(dead_code)]
 mod connector;
+mod faults;
 mod migration;
 
+const GAS_FOR_RESOLVE_TRANSFER: Gas = Gas::from_tgas(5);
+const GAS_FOR_FT_TRANSFER_CALL: Gas = Gas::from_tgas(30);
+
+/// Keys of the features which could be paused by `pa_pause_feature`.
+const PAUSE_DEPOSIT_KEY: &str = "deposit";
+const PAUSE_WITHDRAW_KEY: &str = "withdraw";
+
+type PausedMask = u8;
+const UNPAUSE_ALL: PausedMask = 0;
+const PAUSE_DEPOSIT: PausedMask = 1 << 0;
+const PAUSE_WITHDRAW: PausedMask = 1 << 1;
+
 #[derive(AccessControlRole, Deserialize, Serialize, Copy, Clone)]
 #[serde(crate = "near_sdk::serde")]
 pub enum Role {
@@ -37,6 +57,14 @@ pub enum Role {
     DAO,
 }
 
+#[derive(BorshSerialize, BorshStorageKey)]
+enum StorageKey {
+    FungibleToken,
+    Metadata,
+    EngineAccounts,
+    Faults,
+}
+
 #[near_bindgen]
 #[derive(BorshDeserialize, BorshSerialize, PanicOnDefault, Pausable, Upgradable)]
 #[access_control(role_type(Role))]
@@ -48,7 +76,13 @@ pub enum Role {
     duration_update_stagers(Role::DAO),
     duration_update_appliers(Role::DAO),
 ))]
-pub struct EthConnectorContract;
+pub struct EthConnectorContract {
+    ft: FungibleToken,
+    metadata: LazyOption<FungibleTokenMetadata>,
+    engine_accounts: LookupSet<AccountId>,
+    aurora_engine_account_id: AccountId,
+    faults: LookupMap<String, Fault>,
+}
 
 #[near_bindgen]
 impl EthConnectorContract {
@@ -59,25 +93,149 @@ impl EthConnectorContract {
         owner_id: &AccountId,
         controller: AccountId,
     ) -> Self {
-        let mut this = Self {};
+        let mut this = Self {
+            ft: FungibleToken::new(StorageKey::FungibleToken),
+            metadata: LazyOption::new(StorageKey::Metadata, Some(metadata)),
+            engine_accounts: LookupSet::new(StorageKey::EngineAccounts),
+            aurora_engine_account_id: aurora_engine_account_id.clone(),
+            faults: LookupMap::new(StorageKey::Faults),
+        };
 
         this.acl_init_super_admin(env::predecessor_account_id());
         this.acl_grant_role("PauseManager".to_string(), env::predecessor_account_id());
+        this.acl_grant_role("Owner".to_string(), owner_id.clone());
+        this.engine_accounts.insert(&aurora_engine_account_id);
 
         this
     }
 
-    pub fn set_aurora_engine_account_id(&mut self, new_aurora_engine_account_id: AccountId) {}
+    pub fn set_aurora_engine_account_id(&mut self, new_aurora_engine_account_id: AccountId) {
+        self.assert_owner();
+        self.aurora_engine_account_id = new_aurora_engine_account_id;
+    }
 
     pub fn get_aurora_engine_account_id(&self) -> AccountId {
-        env::current_account_id()
+        self.aurora_engine_account_id.clone()
+    }
+
+    /// Mints tokens to the account, registering it if needed. Works as a deposit,
+    /// so it's paused with `PAUSE_DEPOSIT`.
+    pub fn mint(&mut self, account_id: AccountId, amount: U128) {
+        self.apply_fault("mint");
+        self.assert_owner();
+        self.assert_not_paused(PAUSE_DEPOSIT_KEY);
+        if !self.ft.accounts.contains_key(&account_id) {
+            self.ft.internal_register_account(&account_id);
+        }
+        self.ft.internal_deposit(&account_id, amount.0);
+    }
+
+    #[result_serializer(borsh)]
+    pub fn get_paused_flags(&self) -> PausedMask {
+        let mut flags = UNPAUSE_ALL;
+        if self.pa_is_paused(PAUSE_DEPOSIT_KEY.to_string()) {
+            flags |= PAUSE_DEPOSIT;
+        }
+        if self.pa_is_paused(PAUSE_WITHDRAW_KEY.to_string()) {
+            flags |= PAUSE_WITHDRAW;
+        }
+        flags
+    }
+
+    /// Makes the method fail in the way described by the fault until it's cleared.
+    pub fn set_fault(&mut self, method: String, fault: Fault) {
+        self.assert_owner();
+        self.faults.insert(&method, &fault);
+    }
+
+    pub fn clear_fault(&mut self, method: String) {
+        self.assert_owner();
+        self.faults.remove(&method);
+    }
+}
+
+impl EthConnectorContract {
+    /// The owner and the contract itself are allowed to call privileged methods.
+    fn assert_owner(&self) {
+        let predecessor = env::predecessor_account_id();
+        require!(
+            predecessor == env::current_account_id()
+                || self.acl_has_role("Owner".to_string(), predecessor),
+            "ERR_NOT_ALLOWED"
+        );
+    }
+
+    fn apply_fault(&self, method: &str) {
+        if let Some(fault) = self.faults.get(&method.to_string()) {
+            fault.apply();
+        }
+    }
+
+    fn assert_engine_account(&self) {
+        require!(
+            self.engine_accounts
+                .contains(&env::predecessor_account_id()),
+            "Method can be called only by aurora engine"
+        );
+    }
+
+    fn assert_not_paused(&self, key: &str) {
+        require!(!self.pa_is_paused(key.to_string()), "ERR_PAUSED");
+    }
+
+    fn internal_withdraw(
+        &mut self,
+        sender_id: &AccountId,
+        recipient_address: Address,
+        amount: Balance,
+    ) -> WithdrawResult {
+        self.assert_not_paused(PAUSE_WITHDRAW_KEY);
+        self.ft.internal_withdraw(sender_id, amount);
+
+        WithdrawResult {
+            amount,
+            recipient_id: recipient_address,
+            eth_custodian_address: Address::zero(),
+        }
+    }
+
+    fn internal_ft_transfer_call(
+        &mut self,
+        sender_id: AccountId,
+        receiver_id: AccountId,
+        amount: U128,
+        memo: Option<String>,
+        msg: String,
+    ) -> PromiseOrValue<U128> {
+        require!(
+            env::prepaid_gas() > GAS_FOR_FT_TRANSFER_CALL,
+            "More gas is required"
+        );
+        self.ft
+            .internal_transfer(&sender_id, &receiver_id, amount.0, memo);
+        let receiver_gas = env::prepaid_gas()
+            .checked_sub(GAS_FOR_FT_TRANSFER_CALL)
+            .unwrap_or_else(|| env::panic_str("Prepaid gas overflow"));
+
+        ext_ft_receiver::ext(receiver_id.clone())
+            .with_static_gas(receiver_gas)
+            .ft_on_transfer(sender_id.clone(), amount, msg)
+            .then(
+                ext_ft_resolver::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_RESOLVE_TRANSFER)
+                    .ft_resolve_transfer(sender_id, receiver_id, amount),
+            )
+            .into()
     }
 }
 
 #[near_bindgen]
 impl FungibleTokenCore for EthConnectorContract {
     #[payable]
-    fn ft_transfer(&mut self, receiver_id: AccountId, amount: U128, memo: Option<String>) {}
+    fn ft_transfer(&mut self, receiver_id: AccountId, amount: U128, memo: Option<String>) {
+        self.apply_fault("ft_transfer");
+        self.ft.ft_transfer(receiver_id, amount, memo)
+    }
 
     #[payable]
     fn ft_transfer_call(
@@ -87,16 +245,16 @@ impl FungibleTokenCore for EthConnectorContract {
         memo: Option<String>,
         msg: String,
     ) -> PromiseOrValue<U128> {
-        assert_one_yocto();
-        PromiseOrValue::Value(amount)
+        self.apply_fault("ft_transfer_call");
+        self.ft.ft_transfer_call(receiver_id, amount, memo, msg)
     }
 
     fn ft_total_supply(&self) -> U128 {
-        U128::from(100)
+        self.ft.ft_total_supply()
     }
 
     fn ft_balance_of(&self, account_id: AccountId) -> U128 {
-        U128::from(200)
+        self.ft.ft_balance_of(account_id)
     }
 }
 
@@ -110,6 +268,11 @@ impl EngineFungibleToken for EthConnectorContract {
         amount: U128,
         memo: Option<String>,
     ) {
+        self.apply_fault("engine_ft_transfer");
+        self.assert_engine_account();
+        assert_one_yocto();
+        self.ft
+            .internal_transfer(&sender_id, &receiver_id, amount.0, memo);
     }
 
     #[payable]
@@ -121,19 +284,27 @@ impl EngineFungibleToken for EthConnectorContract {
         memo: Option<String>,
         msg: String,
     ) -> PromiseOrValue<U128> {
+        self.apply_fault("engine_ft_transfer_call");
+        self.assert_engine_account();
         assert_one_yocto();
-        PromiseOrValue::Value(amount)
+        self.internal_ft_transfer_call(sender_id, receiver_id, amount, memo, msg)
     }
 }
 
 #[near_bindgen]
 impl KnownEngineAccountsManagement for EthConnectorContract {
-    fn set_engine_account(&mut self, engine_account: &AccountId) {}
+    fn set_engine_account(&mut self, engine_account: &AccountId) {
+        self.assert_owner();
+        self.engine_accounts.insert(engine_account);
+    }
 
-    fn remove_engine_account(&mut self, engine_account: &AccountId) {}
+    fn remove_engine_account(&mut self, engine_account: &AccountId) {
+        self.assert_owner();
+        self.engine_accounts.remove(engine_account);
+    }
 
     fn is_engine_account_exist(&self, engine_account: &AccountId) -> bool {
-        engine_account.as_str() == "test.root"
+        self.engine_accounts.contains(engine_account)
     }
 }
 
@@ -146,10 +317,10 @@ impl EngineStorageManagement for EthConnectorContract {
         account_id: Option<AccountId>,
         registration_only: Option<bool>,
     ) -> StorageBalance {
-        StorageBalance {
-            total: NearToken::from_yoctonear(100),
-            available: NearToken::from_yoctonear(200),
-        }
+        self.apply_fault("engine_storage_deposit");
+        self.assert_engine_account();
+        self.ft
+            .storage_deposit(account_id.or(Some(sender_id)), registration_only)
     }
 
     #[payable]
@@ -158,14 +329,36 @@ impl EngineStorageManagement for EthConnectorContract {
         sender_id: AccountId,
         amount: Option<U128>,
     ) -> StorageBalance {
-        StorageBalance {
-            total: NearToken::from_yoctonear(100),
-            available: NearToken::from_yoctonear(200),
-        }
+        self.assert_engine_account();
+        assert_one_yocto();
+        let balance = self
+            .ft
+            .storage_balance_of(sender_id.clone())
+            .unwrap_or_else(|| {
+                env::panic_str(&format!("The account {sender_id} is not registered"))
+            });
+        // The storage balance of a fungible token account never has an available part.
+        require!(
+            amount.map_or(true, |amount| amount.0 == 0),
+            "The amount is greater than the available storage balance"
+        );
+        balance
     }
 
     #[payable]
     fn engine_storage_unregister(&mut self, sender_id: AccountId, force: Option<bool>) -> bool {
+        self.assert_engine_account();
+        assert_one_yocto();
+        let Some(balance) = self.ft.accounts.get(&sender_id) else {
+            return false;
+        };
+        require!(
+            balance == 0 || force.unwrap_or(false),
+            "Can't unregister the account with the positive balance without force"
+        );
+        self.ft.accounts.remove(&sender_id);
+        self.ft.total_supply -= balance;
+        Promise::new(sender_id).transfer(self.ft.storage_balance_bounds().min);
         true
     }
 }
@@ -179,22 +372,15 @@ impl FungibleTokenResolver for EthConnectorContract {
         receiver_id: AccountId,
         amount: U128,
     ) -> U128 {
-        U128::from(10)
+        self.apply_fault("ft_resolve_transfer");
+        self.ft.ft_resolve_transfer(sender_id, receiver_id, amount)
     }
 }
 
 #[near_bindgen]
 impl FungibleTokenMetadataProvider for EthConnectorContract {
     fn ft_metadata(&self) -> FungibleTokenMetadata {
-        FungibleTokenMetadata {
-            spec: FT_METADATA_SPEC.to_string(),
-            name: "Ether".to_string(),
-            symbol: "ETH".to_string(),
-            icon: Some("data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAGQAAABkCAYAAABw4pVUAAAAAXNSR0IArs4c6QAAAARnQU1BAACxjwv8YQUAAAAJcEhZcwAADsQAAA7EAZUrDhsAAAs3SURBVHhe7Z1XqBQ9FMdFsYu999577wUfbCiiPoggFkQsCKJP9t57V7AgimLBjg8qKmLBXrD33hVUEAQ1H7+QXMb9Zndnd+/MJJf7h8Pu3c3Mzua3yTk5SeZmEZkySplADFMmEMOUCcQwZQggHz58EHfu3FF/2a0MAWTjxo2iWbNm6i+7ZT2QW7duiUWLFolixYqJQ4cOqVftlfVAZs6cKdauXSuqV68uKlWqpF61V1YDoUXMmTNHrFu3TtSoUUNCmTBhgnrXTlkL5Nu3b2Ly5MmyuwJIzZo1RaNGjUTx4sXFu3fvVCn7ZC2QVatWiQULFvwPSL169USnTp1UKftkJZCbN2+KGTNmSBiLFy/+BwhWoUIFsX//flXaLlkJZPr06WkwIoE0btxYNGzYUFSsWFGVtkvWATlw4IB05BqGGxAMBz9u3Dh1lD2yCsjXr1/THHk8IDwvVaqUeP36tTraDlkFZOXKldKRO2HEAoKD79ixozraDlkD5Pr16/848nhANBQc/N69e9VZzJc1QCIduRcgGA4eKLbICiD79u37nyN3WiwgvMZ7Y8eOVWczW8YDwZFPmTIlauvA4gHhsUSJEuLFixfqrObKeCArVqxwdeROiwUE43UcfNu2bdVZzZXRQK5duyYduRsEp8UDog1fsnPnTnV2M2U0kFiO3GlegeDgy5cvr85upowFQqg6d+5cVwCR5hUI71NuzJgx6lPMk5FAPn365Doij2ZegWCUIUX/9OlT9WlmyUggy5Yti+vInZYIEAwH37JlS/VpZsk4IJcvX5bTsl5bB5YoEMqRDd62bZv6VHNkHJBp06YlBANLFAiGgy9btqz6VHNkFJBdu3Z5duROSwYIxjEjRoxQn26GjAHy8ePHuCPyaJYsEMozgn/48KG6ivBlDJAlS5Yk5MidlgqQ+vXri+bNm6urCF9GALl48aJ05G6V7cWSBYJxDOu5Nm/erK4mXBkBJBlH7rRUgGAmOfjQgZBbSsaROy1VIBjHDxs2TF1VeAoVyPv37+WI3K2SE7H0AMKxJUuWFHfv3lVXF45CBZKKI3daegDBcPBNmzZVVxeOQgNy/vz5hEfkbsbxAGFtb6pAOL5y5cpye0NYCg1Iqo5c29KlS2WEVKdOHdGkSZOUoeDgS5cura4yeIUCZMeOHWLevHkpASEBScvAB/Xs2VMUKVJE1K1bV44pUgHDcbVq1RJDhgxRVxusAgfy5s0bMXXq1IRgOMsuX75c7gcZP368aN++vez3W7VqJfLnzy8KFCggU+tUKNncZMFwDA6eNcRBK3AgCxculOas8HiG82duffXq1WLkyJGiRYsWokGDBrI1UPHMlQOjaNGisqUUKlRIPrKclLKA0RUdWfnRDNCUD1qBAjl79qyYNWuWa6VHGq0CEGw7oHsaNGiQrCBMg9DmBKJNgylYsKAciQOFfYhUtlcwHEe3GKQCA/Lnzx/PyUMc9Zo1a+SAsV+/fvLXSgXxa3eCiAXECaZw4cISDPPpGijniweG93HwXHtQCgwIk0E4cjcAGhItAf8AuG7dukknzbgAENFgYLGAaNNgKMcibGYNdXdGxUeDgz8aOHCg+hb+KxAgr169kpUcCUKb01GzOJrKonuJB0KbFyBOAw4thgCgdu3aaWAA4AYGB8/a4iAUCBBG405Hrv2Dm6MGhFulx7JEgWjTYHisVq2a/GxapBMGgLguLAj5DuTMmTP/OHLtqPETdAW6u4h01IlYskC06e6MIICROlA0GH19vM51+y1fgfz+/TvNkWtHjR/p27ev7JboJrx2S7EsVSAYUDCgcC4CAEbtXJsGg4PnO/kpX4Fs3bpVwiB0BEz37t09O+pELD2AOE23GM5ZpkwZGeVxraRnBgwYoL6dP/INCCNyfAeOukOHDmmZVLcKTdXSG4jTNBidAaDlXLlyRX3L9JdvQPr06SObvHbU6dUa3MxPINp0d5Y3b16RJ08e9S3TX74Befz4sejcubOoWrWqdNi2AgEEj8DIkiWLdO4PHjxQ3zL95asPQQcPHpSTR/gOv6D4BUQ7+uzZs4usWbOK7du3q2/ln3wHosU+j3LlysmIxa1SUzG/gOTLl0+2ilGjRqlv4b8CA4K+fPkievXqJZt9MgPAaJbeQHT3hA9kJX6QChSI1smTJ+U4RKct3Co5EUsvIHRP2bJlEzlz5hRHjhxRVxusfANy4cIF9Sy6GLnrAZhbRXu1VIEAguiJVuHlfltbtmxRz9JfvgHhxpQMBt++fatecdfPnz/lYIvtAcmOU1IBQi4LEG3atJHXEkssEWK0fvv2bfVK+svXLosJKW4AQ3QSb07h6tWr0uEz+Eq0G0sGCAM+IieOI98WS3///hVDhw4VOXLkkAlRP+W7D9mwYYNMLtJa4n1xRBqe3bIMKL2CSQQI3VPu3Lllq+C64olsNPMnBCJdunRRr/qnQJw6IS/pdypg/vz5cff38YscPny49C9eujGvQCgDiB49eqhPii4WgJPuAQQ+Lqi1v4EAefToUVrWFzCsyWIx2q9fv1QJd92/f1+0bt1aLlaINdqPB4TuCRD80rmtbCzhR8hG66SizvKeOHFClfBXgQBBe/bskfcr0dO1pOFZU3Xs2DFVIrqY/q1SpUpa1tUrELqnXLlySRhe5jKYw2d2kHBcz4OwIjLIXVaBAUF0V5Ezh7Nnz5Z27949VSq6CBDoOphHiQYECDyyTgsQ/fv3V0dH1/Hjx2V6h7wbEAguMH4ABBlBKlAgbneE090Yd21Yv369+P79uyrtrpcvX/6TtIwEorsnlvA8efJEHeUuRuFdu3aVKR2CCCcMnpNyf/78uSodjAIFgk6fPh11txQtCGBebhlO0pLuhKSlBkISEBhMjMXTxIkTZYVzvBOEhgFQriloBQ4EEUrGWhKEryEyu3HjhjoiuggWqDxAeOnrufcW5QkUIkFoGEBiUi0MhQKEeel4q995DyjcZ/Hz58/qSHfRrcTbSUuZdu3ayTEOYawbDIz3iLDiRYB+KRQgiP/3waJrNxjagMI0MK2AKC1ZjR49Wm5/JqEZDQTGe8A4fPiwOjJ4hQYEsS3By/5CwFCOVsWAzatIAhKVed3MQznWEIepUIEg/IUzFI5lgCEgYG1XrKQlyT9CY3wFXZBb5UcaURZ+JWyFDoSs8KRJk2L6E6dRDoB0YyQtneukSGAOHjxYDu70KNut8iONckRcJvzbpNCBIAZmXrcpYBoekRpgyBQzhiE1wkDOKwiMsuSr6BJNkBFAENEU45DIyo9nwGGxNs44ERAY5QlxmQsxRcYAIcxMdKubtmS3RVOe7u3Hjx/qKsKXMUAQA0EiKbdKj2XJAiEC2717t/p0M2QUEETaw0so7LREgVCO8l4Sj0HLOCAIB+81FMYSAUIZQmGSkybKSCAs1I7MCseyRIEwaveSJwtDRgJBR48e9RwKewXC+0x0AdtUGQsEMSL3cnMaL0B4j1wWc/Qmy2ggzG/ruXg3ENq8AmHgyCSZyTIaCLp06VLce8DHA8LrrGDxMnEVtowHgjZt2hR1QguLB4R0Su/evdXZzJYVQJBe25UoELK4Nv1PQ2uAPHv2LKo/iQaEv0mNeFn4bYqsAYL4p5IsGfIChOfMb7Dp1CZZBQTRQiJDYTcgerrWNlkHhHVbkV1XJBAemXDirqe2yTog6Ny5c9LJayhOIBgrS1h1b6OsBIKocB0KO4FwtwVu7WSrrAWC9NouDYQsLstCbZbVQNjmwCwjQFjCwzTuqVOn1Lt2ymogiBk/PafOfbdsl/VAEEBs+gfEsZQhgDChxVKgjKAMASQjKROIYcoEYpgygRglIf4D6lp/+XognSwAAAAASUVORK5CYII=".to_string()),
-            reference: None,
-            reference_hash: None,
-            decimals: 18,
-        }
+        self.metadata.get().expect("Metadata isn't set")
     }
 }
 
@@ -206,7 +392,11 @@ impl ConnectorWithdraw for EthConnectorContract {
         &mut self,
         #[serializer(borsh)] recipient_address: Address,
         #[serializer(borsh)] amount: Balance,
-    ) {
+    ) -> WithdrawResult {
+        self.apply_fault("withdraw");
+        assert_one_yocto();
+        let sender_id = env::predecessor_account_id();
+        self.internal_withdraw(&sender_id, recipient_address, amount)
     }
 
     #[payable]
@@ -216,7 +406,11 @@ impl ConnectorWithdraw for EthConnectorContract {
         #[serializer(borsh)] sender_id: AccountId,
         #[serializer(borsh)] recipient_address: Address,
         #[serializer(borsh)] amount: Balance,
-    ) {
+    ) -> WithdrawResult {
+        self.apply_fault("engine_withdraw");
+        self.assert_engine_account();
+        assert_one_yocto();
+        self.internal_withdraw(&sender_id, recipient_address, amount)
     }
 }
 
@@ -228,51 +422,70 @@ impl StorageManagement for EthConnectorContract {
         account_id: Option<AccountId>,
         registration_only: Option<bool>,
     ) -> StorageBalance {
-        StorageBalance {
-            total: NearToken::from_yoctonear(100),
-            available: NearToken::from_yoctonear(200),
-        }
+        self.apply_fault("storage_deposit");
+        self.ft.storage_deposit(account_id, registration_only)
     }
 
     #[payable]
     fn storage_withdraw(&mut self, amount: Option<NearToken>) -> StorageBalance {
-        StorageBalance {
-            total: NearToken::from_yoctonear(100),
-            available: NearToken::from_yoctonear(200),
-        }
+        self.ft.storage_withdraw(amount)
     }
 
     #[payable]
     fn storage_unregister(&mut self, force: Option<bool>) -> bool {
-        true
+        self.ft.internal_storage_unregister(force).is_some()
     }
 
     fn storage_balance_bounds(&self) -> StorageBalanceBounds {
-        StorageBalanceBounds {
-            min: NearToken::from_yoctonear(100),
-            max: Some(NearToken::from_yoctonear(200)),
-        }
+        self.ft.storage_balance_bounds()
     }
 
     fn storage_balance_of(&self, account_id: AccountId) -> Option<StorageBalance> {
-        Some(StorageBalance {
-            total: NearToken::from_yoctonear(10),
-            available: NearToken::from_yoctonear(20),
-        })
+        self.ft.storage_balance_of(account_id)
     }
 }
 
 #[near_bindgen]
 impl Migration for EthConnectorContract {
-    /// Migrate contract data
+    /// Registers the accounts which balances are going to be migrated.
     #[private]
-    fn migrate(&mut self, #[serializer(borsh)] accounts: Vec<AccountId>) {}
+    fn migrate(&mut self, #[serializer(borsh)] accounts: Vec<AccountId>) {
+        for account_id in accounts {
+            if !self.ft.accounts.contains_key(&account_id) {
+                self.ft.internal_register_account(&account_id);
+            }
+        }
+    }
 
     #[result_serializer(borsh)]
     fn check_migration_correctness(
         &self,
         #[serializer(borsh)] data: MigrationInputData,
     ) -> MigrationCheckResult {
-        MigrationCheckResult::Success
+        let mut not_exist = Vec::new();
+        let mut wrong_amount = HashMap::new();
+
+        for (account_id, amount) in data.accounts {
+            match self.ft.accounts.get(&account_id) {
+                None => not_exist.push(account_id),
+                Some(balance) if balance != amount => {
+                    wrong_amount.insert(account_id, balance);
+                }
+                Some(_) => {}
+            }
+        }
+
+        if !not_exist.is_empty() {
+            return MigrationCheckResult::AccountNotExist(not_exist);
+        }
+        if !wrong_amount.is_empty() {
+            return MigrationCheckResult::AccountAmount(wrong_amount);
+        }
+        match data.total_supply {
+            Some(total_supply) if total_supply != self.ft.total_supply => {
+                MigrationCheckResult::TotalSupply(self.ft.total_supply)
+            }
+            _ => MigrationCheckResult::Success,
+        }
     }
 }
@@ -0,0 +1,278 @@
+use crate::transaction::RawTransaction;
+use crate::types::{
+    encode_address, encode_data, encode_hash, encode_quantity, parse_address, parse_data,
+    parse_hash, parse_log_filter, parse_quantity, parse_state_block, BlockId, CallRequest, Request,
+    Response, RpcError,
+};
+use aurora_engine_types::parameters::engine::TransactionStatus;
+use aurora_engine_types::types::Address;
+use aurora_engine_types::{H256, U256};
+use aurora_workspace_engine::hash::keccak;
+use aurora_workspace_engine::store::{transaction_hash, EvmLog, TransactionStore};
+use aurora_workspace_engine::EngineContract;
+use serde_json::{json, Value};
+use std::sync::Mutex;
+
+pub use server::RpcServer;
+
+pub mod server;
+pub mod transaction;
+pub mod types;
+
+/// Gas limit reported for the blocks.
+const BLOCK_GAS_LIMIT: u64 = 30_000_000;
+const CLIENT_VERSION: &str = concat!("aurora-workspace-rpc/", env!("CARGO_PKG_VERSION"));
+
+/// Translates the Ethereum JSON-RPC methods to the calls and views of the engine.
+///
+/// The transactions sent by `eth_sendRawTransaction` are recorded in the [`TransactionStore`],
+/// which reports every transaction in its own block.
+#[derive(Debug)]
+pub struct EthRpc {
+    engine: EngineContract,
+    store: Mutex<TransactionStore>,
+}
+
+impl EthRpc {
+    pub fn new(engine: EngineContract) -> Self {
+        Self {
+            engine,
+            store: Mutex::new(TransactionStore::new()),
+        }
+    }
+
+    pub fn engine(&self) -> &EngineContract {
+        &self.engine
+    }
+
+    /// Returns the store of the transactions sent by `eth_sendRawTransaction`.
+    pub fn store(&self) -> std::sync::MutexGuard<'_, TransactionStore> {
+        self.store.lock().expect("poisoned store")
+    }
+
+    pub async fn handle(&self, request: Request) -> Response {
+        let result = self.dispatch(&request).await;
+        Response::new(request.id, result)
+    }
+
+    /// Handles the single request or the batch of requests.
+    pub async fn handle_value(&self, body: Value) -> Value {
+        match body {
+            Value::Array(requests) => {
+                let mut responses = Vec::with_capacity(requests.len());
+                for request in requests {
+                    responses.push(self.handle_single(request).await);
+                }
+                Value::Array(responses)
+            }
+            request => self.handle_single(request).await,
+        }
+    }
+
+    async fn handle_single(&self, request: Value) -> Value {
+        let response = match serde_json::from_value::<Request>(request) {
+            Ok(request) => self.handle(request).await,
+            Err(e) => Response::new(Value::Null, Err(RpcError::invalid_request(e))),
+        };
+        serde_json::to_value(response).expect("serializable response")
+    }
+
+    async fn dispatch(&self, request: &Request) -> Result<Value, RpcError> {
+        match request.method.as_str() {
+            "web3_clientVersion" => Ok(CLIENT_VERSION.into()),
+            "net_version" => Ok(self.chain_id().await?.to_string().into()),
+            "eth_chainId" => Ok(encode_quantity(self.chain_id().await?)),
+            "eth_gasPrice" | "eth_maxPriorityFeePerGas" => Ok(encode_quantity(0u64)),
+            "eth_accounts" => Ok(json!([])),
+            "eth_blockNumber" => Ok(encode_quantity(self.block_number())),
+            "eth_getBalance" => {
+                let address = parse_address(request.param(0))?;
+                parse_state_block(request.param(1))?;
+                let balance = self.engine.get_balance(address).await?.result;
+                Ok(encode_quantity(balance))
+            }
+            "eth_getTransactionCount" => {
+                let address = parse_address(request.param(0))?;
+                parse_state_block(request.param(1))?;
+                let nonce = self.engine.get_nonce(address).await?.result;
+                Ok(encode_quantity(nonce))
+            }
+            "eth_getCode" => {
+                let address = parse_address(request.param(0))?;
+                parse_state_block(request.param(1))?;
+                let code = self.engine.get_code(address).await?.result;
+                Ok(encode_data(&code))
+            }
+            "eth_getStorageAt" => {
+                let address = parse_address(request.param(0))?;
+                let key = H256(parse_quantity(request.param(1))?.to_big_endian());
+                parse_state_block(request.param(2))?;
+                let value = self.engine.get_storage_at(address, key).await?.result;
+                Ok(encode_hash(&value))
+            }
+            "eth_call" => {
+                let call = CallRequest::parse(request.param(0))?;
+                parse_state_block(request.param(1))?;
+                let output = self.view(&call).await?;
+                Ok(encode_data(&output))
+            }
+            "eth_estimateGas" => {
+                let call = CallRequest::parse(request.param(0))?;
+                parse_state_block(request.param(1))?;
+                self.view(&call).await?;
+                Ok(encode_quantity(self.estimate_gas(&call).await?))
+            }
+            "eth_sendRawTransaction" => {
+                let raw = parse_data(request.param(0))?;
+                let hash = self.send_raw_transaction(raw).await?;
+                Ok(encode_hash(&hash))
+            }
+            "eth_getTransactionReceipt" => {
+                let hash = parse_hash(request.param(0))?;
+                Ok(self.receipt(&hash).unwrap_or(Value::Null))
+            }
+            "eth_getLogs" => {
+                let latest = self.block_number();
+                let filter = parse_log_filter(request.param(0), latest)?;
+                let store = self.store();
+                let logs = store.logs(&filter).into_iter().map(encode_log).collect();
+                Ok(Value::Array(logs))
+            }
+            "eth_getBlockByNumber" => {
+                let number = BlockId::parse(request.param(0))?.resolve(self.block_number());
+                Ok(self.block(number).unwrap_or(Value::Null))
+            }
+            method => Err(RpcError::method_not_found(method)),
+        }
+    }
+
+    async fn chain_id(&self) -> Result<U256, RpcError> {
+        Ok(self.engine.get_chain_id().await?.result)
+    }
+
+    /// Executes the call by `get_view` and returns the output of the successful execution.
+    async fn view(&self, call: &CallRequest) -> Result<Vec<u8>, RpcError> {
+        let status = self
+            .engine
+            .get_view(call.from()?, call.to()?, call.value()?, call.input()?)
+            .await?
+            .result;
+        match status {
+            TransactionStatus::Succeed(output) => Ok(output),
+            TransactionStatus::Revert(output) => Err(RpcError::reverted(&output)),
+            status => Err(RpcError::server(format!("{status:?}"))),
+        }
+    }
+
+    /// Returns the EVM gas used by the call simulated in-process on a snapshot of the engine.
+    /// The simulated call is sent by the signer of the engine contract, the balance of `from`
+    /// is checked by the dry run of [`EthRpc::view`].
+    async fn estimate_gas(&self, call: &CallRequest) -> Result<u64, RpcError> {
+        let estimate = self
+            .engine
+            .call(call.to()?, call.value()?, call.input()?)
+            .estimate_gas()
+            .await
+            .map_err(|e| RpcError::server(format!("gas estimation is unavailable: {e}")))?;
+        Ok(estimate.evm_gas)
+    }
+
+    async fn send_raw_transaction(&self, raw: Vec<u8>) -> Result<H256, RpcError> {
+        // Rejects the payloads which can't be reported before sending them to the engine.
+        RawTransaction::decode(&raw)?;
+        if self.store().receipt(&transaction_hash(&raw)).is_some() {
+            return Err(RpcError::server("already known"));
+        }
+        let result = self
+            .engine
+            .submit(raw.clone())
+            .max_gas()
+            .transact()
+            .await
+            .map_err(RpcError::server)?;
+        let mut store = self.store();
+        let receipt = store.record(raw, &result).map_err(RpcError::server)?;
+        Ok(receipt.tx_hash)
+    }
+
+    fn block_number(&self) -> u64 {
+        self.store().block_height()
+    }
+
+    fn receipt(&self, hash: &H256) -> Option<Value> {
+        let store = self.store();
+        let receipt = store.receipt(hash)?;
+        let to = RawTransaction::decode(&receipt.transaction).ok()?.to;
+        let (status, contract_address) = match &receipt.result.status {
+            TransactionStatus::Succeed(output) if to.is_none() && output.len() == 20 => {
+                (1u64, encode_data(output))
+            }
+            TransactionStatus::Succeed(_) => (1, Value::Null),
+            _ => (0, Value::Null),
+        };
+
+        Some(json!({
+            "transactionHash": encode_hash(hash),
+            "transactionIndex": encode_quantity(0u64),
+            "blockHash": encode_hash(&block_hash(receipt.block_height)),
+            "blockNumber": encode_quantity(receipt.block_height),
+            "to": to.as_ref().map_or(Value::Null, encode_address),
+            "contractAddress": contract_address,
+            "gasUsed": encode_quantity(receipt.result.gas_used),
+            "cumulativeGasUsed": encode_quantity(receipt.result.gas_used),
+            "effectiveGasPrice": encode_quantity(0u64),
+            "status": encode_quantity(status),
+            "logs": receipt.logs.iter().map(encode_log).collect::<Vec<_>>(),
+            "logsBloom": encode_data(&[0u8; 256]),
+            "type": encode_quantity(0u64),
+        }))
+    }
+
+    fn block(&self, number: u64) -> Option<Value> {
+        let store = self.store();
+        let receipt = store.receipt_by_block(number);
+        let transactions = match (number, receipt) {
+            (0, _) => Vec::new(),
+            (_, Some(receipt)) => vec![encode_hash(&receipt.tx_hash)],
+            (_, None) => return None,
+        };
+        let gas_used = receipt.map_or(0, |receipt| receipt.result.gas_used);
+        let parent_hash = number.checked_sub(1).map_or(H256::zero(), block_hash);
+
+        Some(json!({
+            "number": encode_quantity(number),
+            "hash": encode_hash(&block_hash(number)),
+            "parentHash": encode_hash(&parent_hash),
+            "timestamp": encode_quantity(number),
+            "gasLimit": encode_quantity(BLOCK_GAS_LIMIT),
+            "gasUsed": encode_quantity(gas_used),
+            "baseFeePerGas": encode_quantity(0u64),
+            "miner": encode_address(&Address::zero()),
+            "difficulty": encode_quantity(0u64),
+            "extraData": "0x",
+            "nonce": encode_data(&[0u8; 8]),
+            "logsBloom": encode_data(&[0u8; 256]),
+            "transactions": transactions,
+            "uncles": [],
+        }))
+    }
+}
+
+/// Blocks are synthetic, so their hashes are derived from the numbers.
+fn block_hash(number: u64) -> H256 {
+    keccak(&number.to_be_bytes())
+}
+
+fn encode_log(log: &EvmLog) -> Value {
+    json!({
+        "address": encode_address(&log.address),
+        "topics": log.topics.iter().map(encode_hash).collect::<Vec<_>>(),
+        "data": encode_data(&log.data),
+        "logIndex": encode_quantity(log.log_index),
+        "transactionHash": encode_hash(&log.tx_hash),
+        "transactionIndex": encode_quantity(0u64),
+        "blockHash": encode_hash(&block_hash(log.block_height)),
+        "blockNumber": encode_quantity(log.block_height),
+        "removed": false,
+    })
+}
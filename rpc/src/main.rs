@@ -0,0 +1,29 @@
+//! Deploys the engine to a new sandbox and serves the Ethereum JSON-RPC for it.
+//!
+//! Usage: `rpc-server <ENGINE_WASM> [ADDRESS]`, the address defaults to `127.0.0.1:8545`.
+
+use aurora_workspace_engine::EngineContractBuilder;
+use aurora_workspace_rpc::RpcServer;
+use std::net::SocketAddr;
+
+const DEFAULT_ADDRESS: &str = "127.0.0.1:8545";
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let mut args = std::env::args().skip(1);
+    let wasm_path = args
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("usage: rpc-server <ENGINE_WASM> [ADDRESS]"))?;
+    let addr: SocketAddr = args.next().as_deref().unwrap_or(DEFAULT_ADDRESS).parse()?;
+
+    let code = std::fs::read(&wasm_path)
+        .map_err(|e| anyhow::anyhow!("failed read wasm file {wasm_path}: {e}"))?;
+    let engine = EngineContractBuilder::new()?
+        .with_code(code)
+        .deploy_and_init()
+        .await?;
+
+    let (addr, server) = RpcServer::new(engine).spawn(addr).await?;
+    println!("Serving Ethereum JSON-RPC on http://{addr}");
+    server.await?
+}
@@ -0,0 +1,55 @@
+use crate::EthRpc;
+use aurora_workspace_engine::EngineContract;
+use axum::extract::State;
+use axum::routing::post;
+use axum::{Json, Router};
+use serde_json::Value;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+/// HTTP server of the Ethereum JSON-RPC, e.g. for ethers, Foundry or Hardhat scripts.
+#[derive(Debug, Clone)]
+pub struct RpcServer {
+    rpc: Arc<EthRpc>,
+}
+
+impl RpcServer {
+    pub fn new(engine: EngineContract) -> Self {
+        Self {
+            rpc: Arc::new(EthRpc::new(engine)),
+        }
+    }
+
+    pub fn rpc(&self) -> &EthRpc {
+        &self.rpc
+    }
+
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/", post(handle))
+            .with_state(self.rpc.clone())
+    }
+
+    /// Serves the requests until the task is aborted.
+    pub async fn serve(self, listener: TcpListener) -> anyhow::Result<()> {
+        axum::serve(listener, self.router()).await?;
+        Ok(())
+    }
+
+    /// Binds the address, e.g. `127.0.0.1:0` for a random port, and serves the requests
+    /// in the background. Returns the bound address and the handle of the server task.
+    pub async fn spawn(
+        self,
+        addr: SocketAddr,
+    ) -> anyhow::Result<(SocketAddr, JoinHandle<anyhow::Result<()>>)> {
+        let listener = TcpListener::bind(addr).await?;
+        let addr = listener.local_addr()?;
+        Ok((addr, tokio::spawn(self.serve(listener))))
+    }
+}
+
+async fn handle(State(rpc): State<Arc<EthRpc>>, Json(body): Json<Value>) -> Json<Value> {
+    Json(rpc.handle_value(body).await)
+}
@@ -0,0 +1,85 @@
+use crate::types::RpcError;
+use aurora_engine_types::types::Address;
+use aurora_engine_types::H256;
+use aurora_workspace_engine::store::transaction_hash;
+
+const EIP_2930_TYPE: u8 = 0x01;
+const EIP_1559_TYPE: u8 = 0x02;
+/// The RLP lists start with the byte greater than or equal to the prefix.
+const LIST_PREFIX: u8 = 0xc0;
+
+/// Fields of the signed transaction which the server needs to report without running
+/// the signature recovery: the hash and the recipient.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawTransaction {
+    pub hash: H256,
+    /// `None` for the contract deployments.
+    pub to: Option<Address>,
+}
+
+impl RawTransaction {
+    pub fn decode(raw: &[u8]) -> Result<Self, RpcError> {
+        let (to_index, payload) = match raw.first() {
+            Some(&EIP_2930_TYPE) => (4, &raw[1..]),
+            Some(&EIP_1559_TYPE) => (5, &raw[1..]),
+            Some(byte) if *byte >= LIST_PREFIX => (3, raw),
+            _ => return Err(RpcError::invalid_params("unsupported transaction type")),
+        };
+        let invalid = || RpcError::invalid_params("invalid RLP of the transaction");
+
+        let (is_list, fields, _) = split_item(payload).ok_or_else(invalid)?;
+        if !is_list {
+            return Err(invalid());
+        }
+        let to = list_items(fields)
+            .and_then(|items| items.get(to_index).copied())
+            .ok_or_else(invalid)?;
+        let to = match to.len() {
+            0 => None,
+            20 => Some(Address::from_array(to.try_into().map_err(|_| invalid())?)),
+            _ => return Err(invalid()),
+        };
+
+        Ok(Self {
+            hash: transaction_hash(raw),
+            to,
+        })
+    }
+}
+
+/// Splits the first RLP item of the data into its kind, payload and the rest of the data.
+fn split_item(data: &[u8]) -> Option<(bool, &[u8], &[u8])> {
+    let prefix = *data.first()?;
+    let (is_list, offset, len) = match prefix {
+        0x00..=0x7f => return Some((false, &data[..1], &data[1..])),
+        0x80..=0xb7 => (false, 1, usize::from(prefix - 0x80)),
+        0xb8..=0xbf => {
+            let len_of_len = usize::from(prefix - 0xb7);
+            (false, 1 + len_of_len, read_len(data.get(1..=len_of_len)?)?)
+        }
+        0xc0..=0xf7 => (true, 1, usize::from(prefix - 0xc0)),
+        0xf8..=0xff => {
+            let len_of_len = usize::from(prefix - 0xf7);
+            (true, 1 + len_of_len, read_len(data.get(1..=len_of_len)?)?)
+        }
+    };
+    let end = offset.checked_add(len)?;
+    Some((is_list, data.get(offset..end)?, data.get(end..)?))
+}
+
+fn list_items(mut payload: &[u8]) -> Option<Vec<&[u8]>> {
+    let mut items = Vec::new();
+    while !payload.is_empty() {
+        let (_, item, rest) = split_item(payload)?;
+        items.push(item);
+        payload = rest;
+    }
+    Some(items)
+}
+
+fn read_len(bytes: &[u8]) -> Option<usize> {
+    if bytes.len() > std::mem::size_of::<usize>() {
+        return None;
+    }
+    Some(bytes.iter().fold(0, |len, b| (len << 8) | usize::from(*b)))
+}
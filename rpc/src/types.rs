@@ -0,0 +1,308 @@
+use aurora_engine_types::types::Address;
+use aurora_engine_types::{H256, U256};
+use aurora_workspace_engine::store::LogFilter;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt::{Display, Formatter};
+
+const JSON_RPC_VERSION: &str = "2.0";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Request {
+    #[serde(default)]
+    pub id: Value,
+    pub method: String,
+    #[serde(default)]
+    pub params: Vec<Value>,
+}
+
+impl Request {
+    pub fn new(id: u64, method: &str, params: Vec<Value>) -> Self {
+        Self {
+            id: id.into(),
+            method: method.to_string(),
+            params,
+        }
+    }
+
+    /// Returns the parameter at the index, `Value::Null` if it's omitted.
+    pub fn param(&self, index: usize) -> &Value {
+        self.params.get(index).unwrap_or(&Value::Null)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Response {
+    pub jsonrpc: String,
+    pub id: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<RpcError>,
+}
+
+impl Response {
+    pub fn new(id: Value, result: Result<Value, RpcError>) -> Self {
+        let (result, error) = match result {
+            Ok(result) => (Some(result), None),
+            Err(error) => (None, Some(error)),
+        };
+        Self {
+            jsonrpc: JSON_RPC_VERSION.to_string(),
+            id,
+            result,
+            error,
+        }
+    }
+}
+
+/// Error object of the JSON-RPC response.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+impl RpcError {
+    pub fn invalid_request(message: impl Display) -> Self {
+        Self::new(-32600, message)
+    }
+
+    pub fn method_not_found(method: &str) -> Self {
+        Self::new(
+            -32601,
+            format!("the method {method} does not exist/is not available"),
+        )
+    }
+
+    pub fn invalid_params(message: impl Display) -> Self {
+        Self::new(-32602, message)
+    }
+
+    /// Failure of the engine or of the NEAR transaction.
+    pub fn server(message: impl Display) -> Self {
+        Self::new(-32000, message)
+    }
+
+    /// Reverted EVM execution with the revert data, as returned by geth.
+    pub fn reverted(output: &[u8]) -> Self {
+        Self {
+            code: 3,
+            message: "execution reverted".to_string(),
+            data: Some(encode_data(output)),
+        }
+    }
+
+    fn new(code: i64, message: impl Display) -> Self {
+        Self {
+            code,
+            message: message.to_string(),
+            data: None,
+        }
+    }
+}
+
+impl Display for RpcError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.message, self.code)
+    }
+}
+
+impl std::error::Error for RpcError {}
+
+impl From<anyhow::Error> for RpcError {
+    fn from(e: anyhow::Error) -> Self {
+        Self::server(e)
+    }
+}
+
+pub fn encode_quantity(value: impl Into<U256>) -> Value {
+    Value::String(format!("{:#x}", value.into()))
+}
+
+pub fn encode_data(data: &[u8]) -> Value {
+    Value::String(format!("0x{}", hex::encode(data)))
+}
+
+pub fn encode_address(address: &Address) -> Value {
+    Value::String(format!("0x{}", address.encode()))
+}
+
+pub fn encode_hash(hash: &H256) -> Value {
+    encode_data(hash.as_bytes())
+}
+
+fn parse_str(value: &Value) -> Result<&str, RpcError> {
+    let s = value
+        .as_str()
+        .ok_or_else(|| RpcError::invalid_params(format!("expected hex string, got {value}")))?;
+    s.strip_prefix("0x")
+        .ok_or_else(|| RpcError::invalid_params(format!("missing 0x prefix: {s}")))
+}
+
+pub fn parse_quantity(value: &Value) -> Result<U256, RpcError> {
+    let s = parse_str(value)?;
+    U256::from_str_radix(s, 16).map_err(|e| RpcError::invalid_params(format!("{e:?}: {s}")))
+}
+
+pub fn parse_data(value: &Value) -> Result<Vec<u8>, RpcError> {
+    let s = parse_str(value)?;
+    hex::decode(s).map_err(|e| RpcError::invalid_params(format!("{e}: {s}")))
+}
+
+pub fn parse_address(value: &Value) -> Result<Address, RpcError> {
+    let s = parse_str(value)?;
+    Address::decode(s).map_err(|e| RpcError::invalid_params(format!("{e:?}: {s}")))
+}
+
+pub fn parse_hash(value: &Value) -> Result<H256, RpcError> {
+    let data = parse_data(value)?;
+    if data.len() != 32 {
+        return Err(RpcError::invalid_params(format!(
+            "expected 32 bytes hash, got {}",
+            data.len()
+        )));
+    }
+    Ok(H256::from_slice(&data))
+}
+
+/// Block tag or number of the block parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockId {
+    Latest,
+    Earliest,
+    Pending,
+    Number(u64),
+}
+
+impl BlockId {
+    /// Parses the block parameter, which defaults to `latest` if it's omitted.
+    pub fn parse(value: &Value) -> Result<Self, RpcError> {
+        match value {
+            Value::Null => Ok(Self::Latest),
+            Value::String(tag) if tag == "latest" || tag == "safe" || tag == "finalized" => {
+                Ok(Self::Latest)
+            }
+            Value::String(tag) if tag == "earliest" => Ok(Self::Earliest),
+            Value::String(tag) if tag == "pending" => Ok(Self::Pending),
+            _ => Ok(Self::Number(parse_quantity(value)?.low_u64())),
+        }
+    }
+
+    /// Returns the number of the block, the tags are resolved by the latest block number.
+    pub fn resolve(self, latest: u64) -> u64 {
+        match self {
+            Self::Latest | Self::Pending => latest,
+            Self::Earliest => 0,
+            Self::Number(number) => number,
+        }
+    }
+}
+
+/// Parses the block parameter of the methods reading the state of the engine, which is only
+/// available at the latest block. The parameter defaults to `latest` if it's omitted.
+pub fn parse_state_block(value: &Value) -> Result<(), RpcError> {
+    match value {
+        Value::Null => Ok(()),
+        Value::String(tag) if tag == "latest" || tag == "pending" => Ok(()),
+        block => Err(RpcError::invalid_params(format!(
+            "the state is only available at the latest block, got {block}"
+        ))),
+    }
+}
+
+/// Call object of `eth_call` and `eth_estimateGas`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct CallRequest {
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub value: Option<String>,
+    pub data: Option<String>,
+    pub input: Option<String>,
+}
+
+impl CallRequest {
+    pub fn parse(value: &Value) -> Result<Self, RpcError> {
+        serde_json::from_value(value.clone()).map_err(RpcError::invalid_params)
+    }
+
+    pub fn from(&self) -> Result<Address, RpcError> {
+        self.from.as_ref().map_or(Ok(Address::zero()), |from| {
+            parse_address(&from.as_str().into())
+        })
+    }
+
+    pub fn to(&self) -> Result<Address, RpcError> {
+        let to = self
+            .to
+            .as_ref()
+            .ok_or_else(|| RpcError::invalid_params("contract creation calls are not supported"))?;
+        parse_address(&to.as_str().into())
+    }
+
+    pub fn value(&self) -> Result<U256, RpcError> {
+        self.value.as_ref().map_or(Ok(U256::zero()), |value| {
+            parse_quantity(&value.as_str().into())
+        })
+    }
+
+    /// Returns `input`, or `data` used by the older clients.
+    pub fn input(&self) -> Result<Vec<u8>, RpcError> {
+        self.input
+            .as_ref()
+            .or(self.data.as_ref())
+            .map_or(Ok(Vec::new()), |input| parse_data(&input.as_str().into()))
+    }
+}
+
+/// Parses the filter object of `eth_getLogs`, the block tags are resolved by the latest height.
+pub fn parse_log_filter(value: &Value, latest: u64) -> Result<LogFilter, RpcError> {
+    let object = value
+        .as_object()
+        .ok_or_else(|| RpcError::invalid_params(format!("expected filter object, got {value}")))?;
+    let block = |key: &str| -> Result<Option<u64>, RpcError> {
+        object.get(key).map_or(Ok(None), |value| {
+            Ok(Some(BlockId::parse(value)?.resolve(latest)))
+        })
+    };
+    let mut filter = LogFilter {
+        from_block: block("fromBlock")?,
+        to_block: block("toBlock")?,
+        ..LogFilter::default()
+    };
+
+    match object.get("address") {
+        None | Some(Value::Null) => {}
+        Some(Value::Array(addresses)) => {
+            for address in addresses {
+                filter = filter.address(parse_address(address)?);
+            }
+        }
+        Some(address) => filter = filter.address(parse_address(address)?),
+    }
+
+    let topics = match object.get("topics") {
+        None | Some(Value::Null) => &[][..],
+        Some(Value::Array(topics)) => topics.as_slice(),
+        Some(topics) => {
+            return Err(RpcError::invalid_params(format!(
+                "expected array of topics, got {topics}"
+            )))
+        }
+    };
+    for (position, topic) in topics.iter().enumerate() {
+        match topic {
+            Value::Null => {}
+            Value::Array(alternatives) => {
+                for topic in alternatives {
+                    filter = filter.topic(position, parse_hash(topic)?);
+                }
+            }
+            topic => filter = filter.topic(position, parse_hash(topic)?),
+        }
+    }
+
+    Ok(filter)
+}
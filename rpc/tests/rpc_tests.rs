@@ -0,0 +1,206 @@
+use aurora_engine_types::types::Address;
+use aurora_workspace_engine::store::transaction_hash;
+use aurora_workspace_engine::{EngineContract, EngineContractBuilder};
+use aurora_workspace_rpc::transaction::RawTransaction;
+use aurora_workspace_rpc::types::{Request, Response};
+use aurora_workspace_rpc::RpcServer;
+use aurora_workspace_utils::compile::compile_project;
+use serde_json::{json, Value};
+use std::net::SocketAddr;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+const TO: [u8; 20] = [0x11; 20];
+
+async fn deploy_engine() -> EngineContract {
+    let code = std::fs::read(compile_project("../res/mock_engine")).unwrap();
+    EngineContractBuilder::new()
+        .unwrap()
+        .with_code(code)
+        .deploy_and_init()
+        .await
+        .unwrap()
+}
+
+/// Legacy transaction: `[nonce, gas_price, gas, to, value, data, v, r, s]`.
+fn legacy_transaction() -> Vec<u8> {
+    let mut fields = vec![0x80, 0x80, 0x82, 0x52, 0x08, 0x94];
+    fields.extend_from_slice(&TO);
+    fields.extend_from_slice(&[0x80, 0x80, 0x1b, 0x80, 0x80]);
+    let mut raw = vec![0xc0 + fields.len() as u8];
+    raw.extend(fields);
+    raw
+}
+
+async fn post(addr: SocketAddr, body: &Value) -> Value {
+    let body = body.to_string();
+    let request = format!(
+        "POST / HTTP/1.1\r\nHost: {addr}\r\nContent-Type: application/json\r\n\
+         Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    stream.write_all(request.as_bytes()).await.unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    let (_, body) = response.split_once("\r\n\r\n").unwrap();
+    serde_json::from_str(body).unwrap()
+}
+
+#[test]
+fn test_decode_raw_transaction() {
+    let raw = legacy_transaction();
+    let tx = RawTransaction::decode(&raw).unwrap();
+    assert_eq!(tx.hash, transaction_hash(&raw));
+    assert_eq!(tx.to, Some(Address::from_array(TO)));
+
+    // EIP-1559 deployment: `[chain_id, nonce, tip, fee, gas, to, value, data, access_list, ...]`.
+    let raw = [
+        0x02, 0xcc, 0x01, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0xc0, 0x80, 0x80, 0x80,
+    ];
+    let tx = RawTransaction::decode(&raw).unwrap();
+    assert_eq!(tx.to, None);
+
+    assert!(RawTransaction::decode(&[0x05, 0xc0]).is_err());
+    assert!(RawTransaction::decode(&[0xc5, 0x80]).is_err());
+}
+
+#[tokio::test]
+async fn test_rpc_views() {
+    let engine = deploy_engine().await;
+    let address = Address::from_array([7u8; 20]);
+    engine
+        .mint_account(address, 5, 1_000)
+        .transact()
+        .await
+        .unwrap();
+    let server = RpcServer::new(engine);
+    let rpc = server.rpc();
+    let address = format!("0x{}", address.encode());
+
+    let call = |method: &str, params: Vec<Value>| rpc.handle(Request::new(1, method, params));
+    let result = |response: Response| response.result.unwrap();
+
+    assert_eq!(result(call("eth_chainId", vec![]).await), "0x4e454154");
+    assert_eq!(result(call("net_version", vec![]).await), "1313161556");
+    assert_eq!(
+        result(call("eth_getBalance", vec![json!(address), json!("latest")]).await),
+        "0x3e8"
+    );
+    assert_eq!(
+        result(call("eth_getTransactionCount", vec![json!(address)]).await),
+        "0x5"
+    );
+    assert_eq!(
+        result(call("eth_getCode", vec![json!(address)]).await),
+        "0x"
+    );
+    let call_params = json!({ "from": address, "to": address, "data": "0x01" });
+    assert_eq!(
+        result(call("eth_call", vec![call_params.clone()]).await),
+        "0x"
+    );
+    // The gas reported by the simulated call of the mock engine.
+    assert_eq!(
+        result(call("eth_estimateGas", vec![call_params]).await),
+        "0x5208"
+    );
+    let call_params = json!({ "from": address, "to": address, "value": "0x3e9" });
+    let error = call("eth_estimateGas", vec![call_params.clone()])
+        .await
+        .error
+        .unwrap();
+    assert_eq!(error.code, -32000);
+
+    for (method, params) in [
+        ("eth_getBalance", vec![json!(address), json!("earliest")]),
+        (
+            "eth_getTransactionCount",
+            vec![json!(address), json!("0x0")],
+        ),
+        ("eth_getCode", vec![json!(address), json!("safe")]),
+        ("eth_call", vec![call_params.clone(), json!("0x1")]),
+    ] {
+        let error = call(method, params).await.error.unwrap();
+        assert_eq!(error.code, -32602, "{method}");
+    }
+    let error = call("eth_getBalance", vec![json!("0x01")])
+        .await
+        .error
+        .unwrap();
+    assert_eq!(error.code, -32602);
+    let error = call("eth_mining", vec![]).await.error.unwrap();
+    assert_eq!(error.code, -32601);
+}
+
+#[tokio::test]
+async fn test_rpc_send_raw_transaction() {
+    let engine = deploy_engine().await;
+    let (addr, server) = RpcServer::new(engine)
+        .spawn("127.0.0.1:0".parse().unwrap())
+        .await
+        .unwrap();
+
+    let raw = legacy_transaction();
+    let hash = format!("0x{}", hex::encode(transaction_hash(&raw)));
+    let response = post(
+        addr,
+        &json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "eth_sendRawTransaction",
+            "params": [format!("0x{}", hex::encode(&raw))],
+        }),
+    )
+    .await;
+    assert_eq!(response["result"], hash);
+
+    let responses = post(
+        addr,
+        &json!([
+            { "jsonrpc": "2.0", "id": 2, "method": "eth_blockNumber" },
+            { "jsonrpc": "2.0", "id": 3, "method": "eth_getTransactionReceipt", "params": [hash] },
+            { "jsonrpc": "2.0", "id": 4, "method": "eth_getBlockByNumber", "params": ["latest", false] },
+            { "jsonrpc": "2.0", "id": 5, "method": "eth_getLogs", "params": [{ "topics": [hash] }] },
+            { "jsonrpc": "2.0", "id": 6, "method": "eth_getLogs", "params": [{ "fromBlock": "0x2" }] },
+        ]),
+    )
+    .await;
+    assert_eq!(responses[0]["result"], "0x1");
+    let receipt = &responses[1]["result"];
+    assert_eq!(receipt["status"], "0x1");
+    assert_eq!(receipt["blockNumber"], "0x1");
+    assert_eq!(receipt["to"], format!("0x{}", hex::encode(TO)));
+    assert_eq!(receipt["contractAddress"], Value::Null);
+    let block = &responses[2]["result"];
+    assert_eq!(block["number"], "0x1");
+    assert_eq!(block["transactions"], json!([hash]));
+    // The mock engine emits a log with the hash of the transaction as the topic.
+    let logs = responses[3]["result"].as_array().unwrap();
+    assert_eq!(logs.len(), 1);
+    assert_eq!(logs[0]["transactionHash"], hash);
+    assert_eq!(logs[0]["logIndex"], "0x0");
+    assert_eq!(receipt["logs"], responses[3]["result"]);
+    assert_eq!(responses[4]["result"], json!([]));
+
+    // Sending the same transaction again doesn't add a block.
+    let response = post(
+        addr,
+        &json!({
+            "jsonrpc": "2.0",
+            "id": 7,
+            "method": "eth_sendRawTransaction",
+            "params": [format!("0x{}", hex::encode(&raw))],
+        }),
+    )
+    .await;
+    assert_eq!(response["error"]["message"], "already known");
+    let response = post(
+        addr,
+        &json!({ "jsonrpc": "2.0", "id": 8, "method": "eth_blockNumber" }),
+    )
+    .await;
+    assert_eq!(response["result"], "0x1");
+
+    server.abort();
+}
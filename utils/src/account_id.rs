@@ -0,0 +1,22 @@
+//! Conversions between the account ids of NEAR and of the Aurora engine types.
+//!
+//! Both types validate the ids by the same NEAR rules, so a valid id of one of them is
+//! a valid id of the other one and the conversions can't fail.
+use aurora_engine_types::account_id::AccountId as AuroraAccountId;
+use near_workspaces::AccountId;
+
+/// Converts the NEAR account id to the account id of the engine types.
+pub fn to_aurora_account_id(account_id: &AccountId) -> AuroraAccountId {
+    account_id
+        .as_str()
+        .parse()
+        .expect("NEAR account id is a valid Aurora account id")
+}
+
+/// Converts the account id of the engine types to the NEAR account id.
+pub fn to_near_account_id(account_id: &AuroraAccountId) -> AccountId {
+    account_id
+        .as_ref()
+        .parse()
+        .expect("Aurora account id is a valid NEAR account id")
+}
@@ -0,0 +1,227 @@
+use crate::results::ExecutionResult;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::io::{BufRead, Write};
+use std::path::Path;
+
+/// Environment variable with the path of the file where the gas samples of all executed
+/// transactions are appended. Recording is disabled if the variable isn't set.
+pub const GAS_REPORT_ENV: &str = "AURORA_WORKSPACE_GAS_REPORT";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GasKind {
+    /// NEAR gas burnt by the whole transaction.
+    Transaction,
+    /// NEAR gas burnt by a single receipt of the transaction.
+    Receipt,
+    /// EVM gas used, taken from [`ExecutionResult::evm_gas`].
+    Evm,
+}
+
+impl Display for GasKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Transaction => write!(f, "transaction"),
+            Self::Receipt => write!(f, "receipt"),
+            Self::Evm => write!(f, "evm"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GasSample {
+    pub kind: GasKind,
+    pub method: String,
+    pub gas: u64,
+}
+
+impl GasSample {
+    /// Collects the samples of the transaction, its receipts and its EVM execution.
+    pub fn from_result<T>(result: &ExecutionResult<T>) -> Vec<Self> {
+        let method = result.method().unwrap_or("unknown").to_string();
+        let mut samples = vec![Self {
+            kind: GasKind::Transaction,
+            method: method.clone(),
+            gas: result.total_gas_burnt().as_gas(),
+        }];
+
+        let tree = result.receipt_tree();
+        // Skip the root: it's the conversion of the transaction into a receipt. The refunds
+        // of the unused gas burn nothing, so they are skipped as well.
+        for node in tree
+            .iter()
+            .skip(1)
+            .filter(|node| node.gas_burnt.as_gas() > 0)
+        {
+            let receipt = node.method.as_deref().unwrap_or(node.executor_id.as_str());
+            samples.push(Self {
+                kind: GasKind::Receipt,
+                method: format!("{method}/{receipt}"),
+                gas: node.gas_burnt.as_gas(),
+            });
+        }
+
+        if let Some(gas) = result.evm_gas() {
+            samples.push(Self {
+                kind: GasKind::Evm,
+                method,
+                gas,
+            });
+        }
+
+        samples
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GasStats {
+    pub count: u64,
+    pub min: u64,
+    pub max: u64,
+    pub total: u128,
+}
+
+impl GasStats {
+    fn new(gas: u64) -> Self {
+        Self {
+            count: 1,
+            min: gas,
+            max: gas,
+            total: u128::from(gas),
+        }
+    }
+
+    fn add(&mut self, gas: u64) {
+        self.count += 1;
+        self.min = self.min.min(gas);
+        self.max = self.max.max(gas);
+        self.total += u128::from(gas);
+    }
+
+    pub fn avg(&self) -> u64 {
+        u64::try_from(self.total / u128::from(self.count)).unwrap_or(u64::MAX)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GasReportRow {
+    pub kind: GasKind,
+    pub method: String,
+    pub count: u64,
+    pub min: u64,
+    pub avg: u64,
+    pub max: u64,
+}
+
+/// Aggregates gas usage per contract method across many transactions.
+#[derive(Debug, Clone, Default)]
+pub struct GasReport {
+    stats: BTreeMap<(GasKind, String), GasStats>,
+}
+
+impl GasReport {
+    pub fn record<T>(&mut self, result: &ExecutionResult<T>) {
+        for sample in GasSample::from_result(result) {
+            self.add_sample(sample);
+        }
+    }
+
+    pub fn add_sample(&mut self, sample: GasSample) {
+        self.stats
+            .entry((sample.kind, sample.method))
+            .and_modify(|stats| stats.add(sample.gas))
+            .or_insert_with(|| GasStats::new(sample.gas));
+    }
+
+    pub fn get(&self, kind: GasKind, method: &str) -> Option<&GasStats> {
+        self.stats.get(&(kind, method.to_string()))
+    }
+
+    /// Loads the samples appended to the file by [`record`].
+    pub fn from_samples_file<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let file = std::fs::File::open(path)?;
+        let mut report = Self::default();
+        for line in std::io::BufReader::new(file).lines() {
+            let line = line?;
+            if !line.trim().is_empty() {
+                report.add_sample(serde_json::from_str(&line)?);
+            }
+        }
+        Ok(report)
+    }
+
+    pub fn rows(&self) -> Vec<GasReportRow> {
+        self.stats
+            .iter()
+            .map(|((kind, method), stats)| GasReportRow {
+                kind: *kind,
+                method: method.clone(),
+                count: stats.count,
+                min: stats.min,
+                avg: stats.avg(),
+                max: stats.max,
+            })
+            .collect()
+    }
+
+    pub fn to_json(&self) -> anyhow::Result<String> {
+        Ok(serde_json::to_string_pretty(&self.rows())?)
+    }
+
+    pub fn write_json<P: AsRef<Path>>(&self, path: P) -> anyhow::Result<()> {
+        Ok(std::fs::write(path, self.to_json()?)?)
+    }
+
+    pub fn write_table<P: AsRef<Path>>(&self, path: P) -> anyhow::Result<()> {
+        Ok(std::fs::write(path, self.to_string())?)
+    }
+}
+
+impl Display for GasReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "| kind | method | calls | min | avg | max |")?;
+        writeln!(f, "|------|--------|------:|----:|----:|----:|")?;
+        for row in self.rows() {
+            writeln!(
+                f,
+                "| {} | {} | {} | {} | {} | {} |",
+                row.kind, row.method, row.count, row.min, row.avg, row.max
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Appends the gas samples of the result to the file set by [`GAS_REPORT_ENV`].
+///
+/// Every sample is written as a single JSON line, so several test binaries running
+/// at the same time can share one file. The report is a side channel: a failure to
+/// write it is logged as a `tracing` warning and doesn't affect the result of the
+/// transaction.
+pub fn record<T>(result: &ExecutionResult<T>) {
+    let Some(path) = std::env::var_os(GAS_REPORT_ENV) else {
+        return;
+    };
+    if let Err(e) = append_samples(&path, GasSample::from_result(result)) {
+        tracing::warn!(
+            "error while writing the gas report to {}: {e}",
+            Path::new(&path).display()
+        );
+    }
+}
+
+fn append_samples(path: impl AsRef<Path>, samples: Vec<GasSample>) -> anyhow::Result<()> {
+    let mut buf = Vec::new();
+    for sample in samples {
+        serde_json::to_writer(&mut buf, &sample)?;
+        buf.push(b'\n');
+    }
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?
+        .write_all(&buf)?;
+    Ok(())
+}
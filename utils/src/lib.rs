@@ -1,18 +1,45 @@
-use crate::transactions::{CallTransaction, ViewTransaction};
+use crate::trace::TraceRecorder;
+use crate::transactions::{BatchTransaction, CallSigner, CallTransaction, ViewTransaction};
 use near_workspaces::network::NetworkClient;
 use near_workspaces::types::{KeyType, NearToken, SecretKey};
 use near_workspaces::{Account, AccountId, Worker};
+use std::collections::HashMap;
 
+pub mod account_id;
 pub mod compile;
+pub mod gas;
+#[cfg(feature = "local")]
+pub mod local;
 pub mod macros;
+pub mod receipts;
 pub mod results;
+pub mod trace;
 pub mod transactions;
 
-pub trait ContractId {
-    fn as_contract(&self) -> &Contract;
+/// Gives access to the contract which the bindings send their calls to. The contract is a
+/// [`Contract`] in the sandbox unless another backend is chosen, e.g. `local::LocalContract`.
+pub trait ContractId<C = Contract> {
+    fn as_contract(&self) -> &C;
     fn id(&self) -> &AccountId;
 }
 
+/// Contract of one of the backends, which the generated bindings send their calls to.
+pub trait ContractBackend: Clone + std::fmt::Debug {
+    /// Account which signs the transactions sent to the contract.
+    type Account: Clone + std::fmt::Debug;
+
+    fn near_call<F: AsRef<str>>(&self, function: &F) -> CallTransaction;
+    fn near_view<F: AsRef<str>>(&self, function_name: &F) -> ViewTransaction<'_>;
+    fn id(&self) -> &AccountId;
+    /// Returns another contract which is signed by the same account, e.g. to call
+    /// the token contracts on behalf of the signer.
+    fn with_contract_id(&self, contract_id: AccountId) -> Self;
+    /// Returns the same contract which appends the calls and views issued through it to
+    /// the trace of the recorder, or doesn't record them if the recorder is `None`.
+    fn with_recorder(&self, recorder: Option<TraceRecorder>) -> Self;
+    fn recorder(&self) -> Option<&TraceRecorder>;
+}
+
 #[derive(Debug, Clone)]
 pub enum AccountKind {
     Account {
@@ -23,37 +50,61 @@ pub enum AccountKind {
 }
 
 impl AccountKind {
-    fn call<'a, F: AsRef<str>>(&'a self, function: &'a F) -> CallTransaction {
-        let transaction = match self {
+    fn call<F: AsRef<str>>(&self, function: &F) -> CallTransaction {
+        CallTransaction::new(
+            CallSigner::Sandbox(self.signer().clone()),
+            self.id().clone(),
+            function.as_ref(),
+        )
+    }
+
+    fn batch(&self) -> BatchTransaction {
+        match self {
             AccountKind::Account { contract_id, inner } => {
-                inner.call(contract_id, function.as_ref())
+                BatchTransaction::new(inner.batch(contract_id))
             }
-            AccountKind::Contract(con) => con.call(function.as_ref()),
-        };
-        CallTransaction::new(transaction)
+            AccountKind::Contract(con) => BatchTransaction::new(con.as_account().batch(con.id())),
+        }
     }
 
     fn view<F: AsRef<str>>(&self, function: &F) -> ViewTransaction {
-        let transaction = match self {
-            AccountKind::Account { contract_id, inner } => {
-                inner.view(contract_id, function.as_ref())
-            }
-            AccountKind::Contract(con) => con.view(function.as_ref()),
-        };
-        ViewTransaction::new(transaction)
+        match self {
+            AccountKind::Account { contract_id, inner } => ViewTransaction::new(
+                inner.view(contract_id, function.as_ref()),
+                contract_id.clone(),
+                function.as_ref(),
+            ),
+            AccountKind::Contract(con) => ViewTransaction::new(
+                con.view(function.as_ref()),
+                con.id().clone(),
+                function.as_ref(),
+            ),
+        }
     }
 
     fn id(&self) -> &AccountId {
         match self {
-            AccountKind::Account { inner, .. } => inner.id(),
+            AccountKind::Account { contract_id, .. } => contract_id,
             AccountKind::Contract(con) => con.id(),
         }
     }
+
+    fn signer(&self) -> &Account {
+        match self {
+            AccountKind::Account { inner, .. } => inner,
+            AccountKind::Contract(con) => con.as_account(),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct Contract {
     account: AccountKind,
+    /// Deployed contract, kept when the contract is signed by another account to read
+    /// its code and state.
+    deployed: Option<near_workspaces::Contract>,
+    /// Recorder of the calls and views, the one of [`trace::TRACE_ENV`] by default.
+    recorder: Option<TraceRecorder>,
 }
 
 impl Contract {
@@ -63,6 +114,8 @@ impl Contract {
                 contract_id,
                 inner: account,
             },
+            deployed: None,
+            recorder: TraceRecorder::from_env(),
         }
     }
 
@@ -70,12 +123,58 @@ impl Contract {
         &self.account
     }
 
+    /// Returns a view of the same contract which signs transactions with the given account.
+    pub fn with_signer(&self, account: &Account) -> Self {
+        Self {
+            deployed: self.deployed.clone(),
+            recorder: self.recorder.clone(),
+            ..Self::new(self.id().clone(), account.clone())
+        }
+    }
+
+    /// Returns another contract which is signed by the same account, e.g. to call
+    /// the token contracts on behalf of the signer.
+    pub fn with_contract_id(&self, contract_id: AccountId) -> Self {
+        Self {
+            recorder: self.recorder.clone(),
+            ..Self::new(contract_id, self.signer().clone())
+        }
+    }
+
+    /// Returns the same contract which appends the calls and views issued through it to
+    /// the trace of the recorder, or doesn't record them if the recorder is `None`.
+    pub fn with_recorder(&self, recorder: Option<TraceRecorder>) -> Self {
+        Self {
+            recorder,
+            ..self.clone()
+        }
+    }
+
+    pub fn recorder(&self) -> Option<&TraceRecorder> {
+        self.recorder.as_ref()
+    }
+
+    /// Returns the account which signs transactions sent to the contract.
+    pub fn signer(&self) -> &Account {
+        self.account.signer()
+    }
+
     pub fn near_call<'a, F: AsRef<str>>(&'a self, function: &'a F) -> CallTransaction {
-        self.account.call(function)
+        self.account
+            .call(function)
+            .with_deployed(self.deployed.clone())
+            .with_recorder(self.recorder.clone())
+    }
+
+    /// Starts a transaction which may contain several actions sent to the contract.
+    pub fn batch(&self) -> BatchTransaction {
+        self.account.batch()
     }
 
     pub fn near_view<F: AsRef<str>>(&self, function_name: &F) -> ViewTransaction {
-        self.account.view(function_name)
+        self.account
+            .view(function_name)
+            .with_recorder(self.recorder.clone())
     }
 
     pub fn id(&self) -> &AccountId {
@@ -84,9 +183,7 @@ impl Contract {
 
     pub async fn deploy(account: &Account, wasm: Vec<u8>) -> anyhow::Result<Self> {
         let contract = account.deploy(&wasm).await?.into_result()?;
-        Ok(Self {
-            account: AccountKind::Contract(contract),
-        })
+        Ok(contract.into())
     }
 
     pub async fn create_account_from_random_seed(account_id: AccountId) -> anyhow::Result<Account> {
@@ -173,3 +270,73 @@ impl Contract {
         )
     }
 }
+
+impl From<near_workspaces::Contract> for Contract {
+    fn from(contract: near_workspaces::Contract) -> Self {
+        Self {
+            account: AccountKind::Contract(contract.clone()),
+            deployed: Some(contract),
+            recorder: TraceRecorder::from_env(),
+        }
+    }
+}
+
+impl ContractBackend for Contract {
+    type Account = Account;
+
+    fn near_call<F: AsRef<str>>(&self, function: &F) -> CallTransaction {
+        Contract::near_call(self, function)
+    }
+
+    fn near_view<F: AsRef<str>>(&self, function_name: &F) -> ViewTransaction<'_> {
+        Contract::near_view(self, function_name)
+    }
+
+    fn id(&self) -> &AccountId {
+        self.account.id()
+    }
+
+    fn with_contract_id(&self, contract_id: AccountId) -> Self {
+        Contract::with_contract_id(self, contract_id)
+    }
+
+    fn with_recorder(&self, recorder: Option<TraceRecorder>) -> Self {
+        Contract::with_recorder(self, recorder)
+    }
+
+    fn recorder(&self) -> Option<&TraceRecorder> {
+        Contract::recorder(self)
+    }
+}
+
+/// Code, state and balance of a deployed contract, e.g. to simulate the calls to it
+/// in-process.
+#[derive(Debug, Clone)]
+pub struct ContractSnapshot {
+    pub account_id: AccountId,
+    pub code: Vec<u8>,
+    pub state: HashMap<Vec<u8>, Vec<u8>>,
+    pub balance: NearToken,
+}
+
+impl ContractSnapshot {
+    /// Reads the snapshot of the contract from the node.
+    pub async fn read(contract: &near_workspaces::Contract) -> anyhow::Result<Self> {
+        let code = contract
+            .view_code()
+            .await
+            .map_err(|e| anyhow::anyhow!("error while reading contract code: {e}"))?;
+        let state = contract
+            .view_state()
+            .await
+            .map_err(|e| anyhow::anyhow!("error while reading contract state: {e}"))?;
+        let balance = contract.view_account().await?.balance;
+
+        Ok(Self {
+            account_id: contract.id().clone(),
+            code,
+            state,
+            balance,
+        })
+    }
+}
@@ -0,0 +1,611 @@
+//! In-process backend which executes the contract wasm without a sandbox node.
+//!
+//! The runtime keeps the state of all accounts in memory and runs every function call
+//! with the NEAR VM runner. Only the called method is executed: promises created by the
+//! contract aren't scheduled, so cross-contract flows still require the sandbox.
+//!
+//! The contracts deployed to the runtime are [`LocalContract`]s. The bindings send calls
+//! to them with `transact_local`, which returns a [`LocalExecutionResult`].
+use crate::receipts::ReceiptNode;
+use crate::results::address_from_borsh;
+use crate::trace::TraceRecorder;
+use crate::transactions::{CallSigner, CallTransaction, ViewTransaction};
+use crate::{ContractBackend, ContractSnapshot};
+use aurora_engine_types::borsh::BorshDeserialize;
+use aurora_engine_types::parameters::engine::SubmitResult;
+use aurora_engine_types::types::Address;
+use near_parameters::{ExtCosts, RuntimeConfig, RuntimeConfigStore};
+use near_primitives_core::version::PROTOCOL_VERSION;
+use near_sdk::json_types::U128;
+use near_sdk::PromiseOrValue;
+use near_vm_runner::logic::mocks::mock_external::MockedExternal;
+use near_vm_runner::logic::{ReturnData, VMContext, ViewConfig};
+use near_vm_runner::ContractCode;
+use near_workspaces::result::ViewResultDetails;
+use near_workspaces::types::{CryptoHash, Gas, NearToken};
+use near_workspaces::AccountId;
+use serde::de::DeserializeOwned;
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Storage operations which touch the trie nodes of the contract state on the node.
+const STORAGE_OPERATIONS: [ExtCosts; 4] = [
+    ExtCosts::storage_read_base,
+    ExtCosts::storage_write_base,
+    ExtCosts::storage_remove_base,
+    ExtCosts::storage_has_key_base,
+];
+/// Gas available to view calls, the same as the RPC node limit.
+const VIEW_GAS: Gas = Gas::from_tgas(200);
+/// Milliseconds between the blocks produced by the runtime.
+const BLOCK_TIME_MS: u64 = 1_000;
+
+/// In-memory blockchain which executes the contracts in the current process.
+#[derive(Debug, Clone)]
+pub struct LocalRuntime {
+    state: Arc<Mutex<State>>,
+    config: Arc<RuntimeConfig>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct State {
+    accounts: HashMap<AccountId, AccountState>,
+    block_height: u64,
+    nonce: u64,
+}
+
+#[derive(Debug, Clone, Default)]
+struct AccountState {
+    balance: u128,
+    code: Option<Arc<Vec<u8>>>,
+    storage: HashMap<Vec<u8>, Vec<u8>>,
+}
+
+/// Account of the [`LocalRuntime`] which signs transactions.
+#[derive(Debug, Clone)]
+pub struct LocalAccount {
+    id: AccountId,
+    runtime: LocalRuntime,
+}
+
+/// Outcome of a function call executed by the [`LocalRuntime`].
+#[derive(Debug, Clone)]
+pub struct LocalOutcome {
+    pub id: CryptoHash,
+    pub executor_id: AccountId,
+    pub gas_burnt: Gas,
+    /// Gas burnt by the call and attached to the promises created by it.
+    pub gas_used: Gas,
+    /// Number of storage reads, writes, removals and key checks done by the call. The node
+    /// charges the trie nodes touched by them, which the runtime doesn't.
+    pub storage_operations: u64,
+    pub logs: Vec<String>,
+    /// Returned value or the reason of the failure.
+    pub status: Result<Vec<u8>, String>,
+}
+
+impl LocalOutcome {
+    pub fn is_success(&self) -> bool {
+        self.status.is_ok()
+    }
+
+    /// Returned value, empty if the call has failed.
+    pub fn value(&self) -> &[u8] {
+        self.status.as_deref().unwrap_or_default()
+    }
+}
+
+/// Function call sent to the [`LocalRuntime`].
+struct LocalCall<'a> {
+    signer_id: &'a AccountId,
+    contract_id: &'a AccountId,
+    method: &'a str,
+    args: Vec<u8>,
+    gas: Gas,
+    deposit: NearToken,
+    is_view: bool,
+}
+
+impl Default for LocalRuntime {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LocalRuntime {
+    pub fn new() -> Self {
+        let config = RuntimeConfigStore::test()
+            .get_config(PROTOCOL_VERSION)
+            .clone();
+        Self {
+            state: Arc::default(),
+            config,
+        }
+    }
+
+    /// Creates a new account with the given balance.
+    pub fn create_account(
+        &self,
+        account_id: &str,
+        balance: NearToken,
+    ) -> anyhow::Result<LocalAccount> {
+        let id: AccountId = account_id.parse()?;
+        self.lock()?.insert_account(&id, balance.as_yoctonear())?;
+
+        Ok(LocalAccount {
+            id,
+            runtime: self.clone(),
+        })
+    }
+
+    /// Deploys the contract code to the account.
+    pub fn deploy(&self, account: &LocalAccount, wasm: Vec<u8>) -> anyhow::Result<()> {
+        let mut state = self.lock()?;
+        let account = state.account_mut(&account.id)?;
+        account.code = Some(Arc::new(wasm));
+        Ok(())
+    }
+
+    /// Creates the runtime with the contract copied from the snapshot, e.g. to simulate
+    /// the calls to a contract deployed to the sandbox.
+    pub fn from_snapshot(snapshot: ContractSnapshot) -> anyhow::Result<Self> {
+        let runtime = Self::new();
+        let mut state = runtime.lock()?;
+        state.insert_account(&snapshot.account_id, snapshot.balance.as_yoctonear())?;
+        let account = state.account_mut(&snapshot.account_id)?;
+        account.code = Some(Arc::new(snapshot.code));
+        account.storage = snapshot.state;
+        drop(state);
+
+        Ok(runtime)
+    }
+
+    /// Executes the function call on a copy of the state, so the state isn't changed even
+    /// if the call succeeds. The signer is created with the attached deposit if it doesn't
+    /// exist in the runtime.
+    pub fn simulate(
+        &self,
+        signer_id: &AccountId,
+        contract_id: &AccountId,
+        method: &str,
+        args: Vec<u8>,
+        gas: Gas,
+        deposit: NearToken,
+    ) -> anyhow::Result<LocalOutcome> {
+        let mut state = self.lock()?.clone();
+        if !state.accounts.contains_key(signer_id) {
+            state.insert_account(signer_id, deposit.as_yoctonear())?;
+        }
+        let copy = Self {
+            state: Arc::new(Mutex::new(state)),
+            config: self.config.clone(),
+        };
+        copy.execute(LocalCall {
+            signer_id,
+            contract_id,
+            method,
+            args,
+            gas,
+            deposit,
+            is_view: false,
+        })
+    }
+
+    pub fn balance(&self, account_id: &AccountId) -> anyhow::Result<NearToken> {
+        let mut state = self.lock()?;
+        Ok(NearToken::from_yoctonear(
+            state.account_mut(account_id)?.balance,
+        ))
+    }
+
+    /// Executes the function call. The state is changed only if the call succeeds.
+    pub fn call(
+        &self,
+        signer: &LocalAccount,
+        contract_id: &AccountId,
+        method: &str,
+        args: Vec<u8>,
+        gas: Gas,
+        deposit: NearToken,
+    ) -> anyhow::Result<LocalOutcome> {
+        self.execute(LocalCall {
+            signer_id: &signer.id,
+            contract_id,
+            method,
+            args,
+            gas,
+            deposit,
+            is_view: false,
+        })
+    }
+
+    /// Executes the view call without changing the state.
+    pub fn view(
+        &self,
+        contract_id: &AccountId,
+        method: &str,
+        args: Vec<u8>,
+    ) -> anyhow::Result<ViewResultDetails> {
+        let outcome = self.execute(LocalCall {
+            signer_id: contract_id,
+            contract_id,
+            method,
+            args,
+            gas: VIEW_GAS,
+            deposit: NearToken::from_yoctonear(0),
+            is_view: true,
+        })?;
+
+        Ok(ViewResultDetails {
+            result: outcome.status.map_err(|e| anyhow::anyhow!(e))?,
+            logs: outcome.logs,
+        })
+    }
+
+    fn execute(&self, call: LocalCall) -> anyhow::Result<LocalOutcome> {
+        let mut state = self.lock()?;
+        let deposit = call.deposit.as_yoctonear();
+        let signer = state.account_mut(call.signer_id)?;
+        if signer.balance < deposit {
+            anyhow::bail!("Account `{}` doesn't have enough balance", call.signer_id);
+        }
+        let contract = state.account_mut(call.contract_id)?.clone();
+        let code = contract
+            .code
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Account `{}` has no contract", call.contract_id))?;
+
+        state.block_height += 1;
+        state.nonce += 1;
+        let mut id = [0u8; 32];
+        id[..8].copy_from_slice(&state.nonce.to_be_bytes());
+
+        let mut ext = MockedExternal::with_code(ContractCode::new(code.to_vec(), None));
+        ext.fake_trie = contract.storage.clone();
+        let context = VMContext {
+            current_account_id: call.contract_id.as_str().parse()?,
+            signer_account_id: call.signer_id.as_str().parse()?,
+            signer_account_pk: vec![0; 33],
+            predecessor_account_id: call.signer_id.as_str().parse()?,
+            input: call.args.into(),
+            promise_results: Vec::new().into(),
+            block_height: state.block_height,
+            block_timestamp: state.block_height * BLOCK_TIME_MS * 1_000_000,
+            epoch_height: 1,
+            account_balance: contract.balance,
+            account_locked_balance: 0,
+            storage_usage: 0,
+            attached_deposit: deposit,
+            prepaid_gas: call.gas.as_gas(),
+            random_seed: id.to_vec(),
+            view_config: call.is_view.then(|| ViewConfig {
+                max_gas_burnt: VIEW_GAS.as_gas(),
+            }),
+            output_data_receivers: Vec::new(),
+        };
+
+        let gas_counter = context.make_gas_counter(&self.config.wasm_config);
+        let prepared = near_vm_runner::prepare(
+            &ext,
+            self.config.wasm_config.clone(),
+            None,
+            gas_counter,
+            call.method,
+        );
+        let outcome = near_vm_runner::run(prepared, &mut ext, &context, self.config.fees.clone())
+            .map_err(|e| anyhow::anyhow!("VM error: {e:?}"))?;
+
+        let status = match (outcome.aborted, outcome.return_data) {
+            (Some(err), _) => Err(err.to_string()),
+            (None, ReturnData::Value(value)) => Ok(value),
+            (None, ReturnData::None) => Ok(Vec::new()),
+            (None, ReturnData::ReceiptIndex(_)) => {
+                Err("the call returned a promise which can't be executed in-process".to_string())
+            }
+        };
+
+        if status.is_ok() && !call.is_view {
+            state.account_mut(call.signer_id)?.balance -= deposit;
+            let contract = state.account_mut(call.contract_id)?;
+            contract.balance += deposit;
+            contract.storage = ext.fake_trie;
+        }
+
+        let ext_costs = &self.config.wasm_config.ext_costs;
+        let storage_operations = STORAGE_OPERATIONS
+            .iter()
+            .map(|cost| outcome.profile.get_ext_cost(*cost) / ext_costs.gas_cost(*cost).max(1))
+            .sum();
+
+        Ok(LocalOutcome {
+            id: CryptoHash(id),
+            executor_id: call.contract_id.clone(),
+            gas_burnt: Gas::from_gas(outcome.burnt_gas),
+            gas_used: Gas::from_gas(outcome.used_gas),
+            storage_operations,
+            logs: outcome.logs,
+            status,
+        })
+    }
+
+    fn lock(&self) -> anyhow::Result<MutexGuard<'_, State>> {
+        self.state
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Local runtime state is poisoned"))
+    }
+}
+
+impl State {
+    fn insert_account(&mut self, account_id: &AccountId, balance: u128) -> anyhow::Result<()> {
+        if self.accounts.contains_key(account_id) {
+            anyhow::bail!("Account `{account_id}` already exists");
+        }
+        self.accounts.insert(
+            account_id.clone(),
+            AccountState {
+                balance,
+                ..Default::default()
+            },
+        );
+        Ok(())
+    }
+
+    fn account_mut(&mut self, account_id: &AccountId) -> anyhow::Result<&mut AccountState> {
+        self.accounts
+            .get_mut(account_id)
+            .ok_or_else(|| anyhow::anyhow!("Account `{account_id}` doesn't exist"))
+    }
+}
+
+impl LocalAccount {
+    pub fn id(&self) -> &AccountId {
+        &self.id
+    }
+
+    pub fn runtime(&self) -> &LocalRuntime {
+        &self.runtime
+    }
+
+    pub fn balance(&self) -> anyhow::Result<NearToken> {
+        self.runtime.balance(&self.id)
+    }
+
+    /// Creates the `<name>.<account_id>` account funded from this account.
+    pub fn create_subaccount(&self, name: &str, balance: NearToken) -> anyhow::Result<Self> {
+        let id: AccountId = format!("{name}.{}", self.id).parse()?;
+        let balance = balance.as_yoctonear();
+        let mut state = self.runtime.lock()?;
+        let parent = state.account_mut(&self.id)?;
+        if parent.balance < balance {
+            anyhow::bail!("Account `{}` doesn't have enough balance", self.id);
+        }
+        state.insert_account(&id, balance)?;
+        state.account_mut(&self.id)?.balance -= balance;
+        drop(state);
+
+        Ok(Self {
+            id,
+            runtime: self.runtime.clone(),
+        })
+    }
+}
+
+/// Contract deployed to the [`LocalRuntime`], the in-process counterpart of [`crate::Contract`].
+#[derive(Debug, Clone)]
+pub struct LocalContract {
+    contract_id: AccountId,
+    signer: LocalAccount,
+    recorder: Option<TraceRecorder>,
+}
+
+impl LocalContract {
+    /// Deploys the contract to the account of the in-process runtime.
+    pub fn deploy(account: &LocalAccount, wasm: Vec<u8>) -> anyhow::Result<Self> {
+        account.runtime().deploy(account, wasm)?;
+        Ok(Self {
+            contract_id: account.id().clone(),
+            signer: account.clone(),
+            recorder: TraceRecorder::from_env(),
+        })
+    }
+
+    /// Returns a view of the same contract which signs transactions with the given account.
+    pub fn with_signer(&self, account: &LocalAccount) -> Self {
+        Self {
+            contract_id: self.contract_id.clone(),
+            signer: account.clone(),
+            recorder: self.recorder.clone(),
+        }
+    }
+
+    /// Returns the account which signs transactions sent to the contract.
+    pub fn signer(&self) -> &LocalAccount {
+        &self.signer
+    }
+
+    pub fn id(&self) -> &AccountId {
+        &self.contract_id
+    }
+}
+
+impl ContractBackend for LocalContract {
+    type Account = LocalAccount;
+
+    fn near_call<F: AsRef<str>>(&self, function: &F) -> CallTransaction {
+        CallTransaction::new(
+            CallSigner::Local(self.signer.clone()),
+            self.contract_id.clone(),
+            function.as_ref(),
+        )
+        .with_recorder(self.recorder.clone())
+    }
+
+    fn near_view<F: AsRef<str>>(&self, function_name: &F) -> ViewTransaction<'_> {
+        ViewTransaction::local(
+            self.signer.runtime().clone(),
+            self.contract_id.clone(),
+            function_name.as_ref(),
+        )
+        .with_recorder(self.recorder.clone())
+    }
+
+    fn id(&self) -> &AccountId {
+        &self.contract_id
+    }
+
+    fn with_contract_id(&self, contract_id: AccountId) -> Self {
+        Self {
+            contract_id,
+            signer: self.signer.clone(),
+            recorder: self.recorder.clone(),
+        }
+    }
+
+    fn with_recorder(&self, recorder: Option<TraceRecorder>) -> Self {
+        Self {
+            recorder,
+            ..self.clone()
+        }
+    }
+
+    fn recorder(&self) -> Option<&TraceRecorder> {
+        self.recorder.as_ref()
+    }
+}
+
+/// Result of a call executed by the [`LocalRuntime`], the in-process counterpart of
+/// [`crate::results::ExecutionResult`]. Failed calls are returned as errors.
+#[derive(Debug)]
+pub struct LocalExecutionResult<T> {
+    outcome: LocalOutcome,
+    value: T,
+    method: Option<String>,
+    evm_gas: Option<u64>,
+}
+
+impl<T: DeserializeOwned> LocalExecutionResult<T> {
+    pub fn json(outcome: LocalOutcome) -> anyhow::Result<Self> {
+        let value = serde_json::from_slice(success_value(&outcome)?)?;
+        Ok(Self::new(outcome, value))
+    }
+}
+
+impl TryFrom<LocalOutcome> for LocalExecutionResult<PromiseOrValue<U128>> {
+    type Error = anyhow::Error;
+
+    fn try_from(outcome: LocalOutcome) -> Result<Self, Self::Error> {
+        let res: U128 = serde_json::from_slice(success_value(&outcome)?)?;
+        Ok(Self::new(outcome, PromiseOrValue::Value(res)))
+    }
+}
+
+impl<T: BorshDeserialize> LocalExecutionResult<T> {
+    pub fn borsh(outcome: LocalOutcome) -> anyhow::Result<Self> {
+        let value = T::try_from_slice(success_value(&outcome)?)?;
+        Ok(Self::new(outcome, value))
+    }
+}
+
+impl LocalExecutionResult<Address> {
+    pub fn borsh_address(outcome: LocalOutcome) -> anyhow::Result<Self> {
+        let bytes = Vec::<u8>::try_from_slice(success_value(&outcome)?)?;
+        let value = address_from_borsh(&bytes)?;
+        Ok(Self::new(outcome, value))
+    }
+}
+
+impl LocalExecutionResult<SubmitResult> {
+    /// Decodes the `SubmitResult` and keeps its EVM gas.
+    pub fn borsh_submit(outcome: LocalOutcome) -> anyhow::Result<Self> {
+        let mut res = Self::borsh(outcome)?;
+        res.evm_gas = Some(res.value.gas_used);
+        Ok(res)
+    }
+}
+
+impl LocalExecutionResult<()> {
+    /// Checks that the call has succeeded, ignoring the returned value.
+    pub fn empty(outcome: LocalOutcome) -> anyhow::Result<Self> {
+        success_value(&outcome)?;
+        Ok(Self::new(outcome, ()))
+    }
+}
+
+impl<T> LocalExecutionResult<T> {
+    fn new(outcome: LocalOutcome, value: T) -> Self {
+        Self {
+            outcome,
+            value,
+            method: None,
+            evm_gas: None,
+        }
+    }
+
+    /// Sets the name of the contract method which has been called.
+    pub fn with_method(mut self, method: &str) -> Self {
+        self.method = Some(method.to_string());
+        self
+    }
+
+    pub fn method(&self) -> Option<&str> {
+        self.method.as_deref()
+    }
+
+    /// EVM gas used by the transaction, if the result has been decoded from a `SubmitResult`.
+    pub fn evm_gas(&self) -> Option<u64> {
+        self.evm_gas
+    }
+
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    pub fn into_value(self) -> T {
+        self.value
+    }
+
+    pub fn outcome(&self) -> &LocalOutcome {
+        &self.outcome
+    }
+
+    pub fn total_gas_burnt(&self) -> Gas {
+        self.outcome.gas_burnt
+    }
+
+    pub fn logs(&self) -> Vec<&str> {
+        self.outcome.logs.iter().map(String::as_str).collect()
+    }
+
+    /// Returns the single node tree of the call: the runtime doesn't execute promises.
+    pub fn receipt_tree(&self) -> ReceiptNode {
+        ReceiptNode::from_local(&self.outcome, self.method.as_deref())
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.outcome.is_success()
+    }
+
+    pub fn is_failure(&self) -> bool {
+        !self.is_success()
+    }
+}
+
+impl<T> AsRef<T> for LocalExecutionResult<T> {
+    fn as_ref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> Borrow<T> for LocalExecutionResult<T> {
+    fn borrow(&self) -> &T {
+        &self.value
+    }
+}
+
+fn success_value(outcome: &LocalOutcome) -> anyhow::Result<&[u8]> {
+    outcome
+        .status
+        .as_deref()
+        .map_err(|e| anyhow::anyhow!("{e}"))
+}
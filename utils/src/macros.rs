@@ -4,8 +4,8 @@ macro_rules! impl_view_return  {
         use aurora_engine_types::borsh;
         $(pub struct $name<'a>(ViewTransaction<'a>);
         impl<'a> $name<'a> {
-            pub(crate) fn view(contract: &'a Contract) -> Self {
-                Self(contract.near_view(&$fn_name))
+            pub(crate) fn view<C: $crate::ContractBackend>(contract: &'a C) -> Self {
+                Self($crate::ContractBackend::near_view(contract, &$fn_name))
             }
             pub(crate) fn args(mut self, args: Vec<u8>) -> Self {
                 self.0 = self.0.args(args);
@@ -37,8 +37,8 @@ macro_rules! impl_call_return  {
     ($(($name:ident => $return:ty, $fn_name:expr, $deser_fn:ident)),* $(,)?) => {
         $(pub struct $name(CallTransaction);
         impl $name {
-            pub(crate) fn call(contract: &Contract) -> Self {
-                Self(contract.near_call(&$fn_name))
+            pub(crate) fn call(contract: &impl $crate::ContractBackend) -> Self {
+                Self($crate::ContractBackend::near_call(contract, &$fn_name))
             }
             pub fn gas(mut self, gas: Gas) -> Self {
                 self.0 = self.0.gas(gas);
@@ -65,15 +65,48 @@ macro_rules! impl_call_return  {
                 self
             }
             pub async fn transact(self) -> anyhow::Result<ExecutionResult<$return>> {
-                ExecutionResult::$deser_fn(self.0.transact().await?)
+                let result = self.0.transact().await?;
+                <Self as $crate::transactions::BatchCall>::decode(result)
+            }
+            pub async fn transact_async(
+                self,
+            ) -> anyhow::Result<$crate::transactions::PendingTransaction<$return>> {
+                Ok($crate::transactions::PendingTransaction::new(
+                    self.0.transact_async().await?,
+                    <Self as $crate::transactions::BatchCall>::decode,
+                ))
+            }
+            #[cfg(feature = "local")]
+            pub fn transact_local(
+                self,
+            ) -> anyhow::Result<$crate::local::LocalExecutionResult<$return>> {
+                let outcome = self.0.transact_local()?;
+                Ok($crate::local::LocalExecutionResult::$deser_fn(outcome)?
+                    .with_method($fn_name.as_ref()))
+            }
+        }
+
+        impl $crate::transactions::BatchCall for $name {
+            type Output = $return;
+
+            fn into_transaction(self) -> CallTransaction {
+                self.0
+            }
+
+            fn decode(
+                result: near_workspaces::result::ExecutionFinalResult,
+            ) -> anyhow::Result<ExecutionResult<$return>> {
+                let res = ExecutionResult::$deser_fn(result)?.with_method($fn_name.as_ref());
+                $crate::gas::record(&res);
+                Ok(res)
             }
         })*
     };
     ($(($name:ident, $fn_name:expr)),* $(,)?) => {
         $(pub struct $name(CallTransaction);
         impl $name {
-            pub(crate) fn call(contract: &Contract) -> Self {
-                Self(contract.near_call(&$fn_name))
+            pub(crate) fn call(contract: &impl $crate::ContractBackend) -> Self {
+                Self($crate::ContractBackend::near_call(contract, &$fn_name))
             }
             pub fn gas(mut self, gas: Gas) -> Self {
                 self.0 = self.0.gas(gas);
@@ -102,9 +135,39 @@ macro_rules! impl_call_return  {
             }
             pub async fn transact(self) -> anyhow::Result<ExecutionResult<()>> {
                 let result = self.0.transact().await?;
+                <Self as $crate::transactions::BatchCall>::decode(result)
+            }
+            pub async fn transact_async(
+                self,
+            ) -> anyhow::Result<$crate::transactions::PendingTransaction<()>> {
+                Ok($crate::transactions::PendingTransaction::new(
+                    self.0.transact_async().await?,
+                    <Self as $crate::transactions::BatchCall>::decode,
+                ))
+            }
+            #[cfg(feature = "local")]
+            pub fn transact_local(self) -> anyhow::Result<$crate::local::LocalExecutionResult<()>> {
+                let outcome = self.0.transact_local()?;
+                Ok($crate::local::LocalExecutionResult::empty(outcome)?
+                    .with_method($fn_name.as_ref()))
+            }
+        }
+
+        impl $crate::transactions::BatchCall for $name {
+            type Output = ();
+
+            fn into_transaction(self) -> CallTransaction {
+                self.0
+            }
+
+            fn decode(
+                result: near_workspaces::result::ExecutionFinalResult,
+            ) -> anyhow::Result<ExecutionResult<()>> {
                 let success = result.is_success();
                 let inner = result.into_result()?;
-                Ok(ExecutionResult::new(inner, (), success))
+                let res = ExecutionResult::new(inner, (), success).with_method($fn_name.as_ref());
+                $crate::gas::record(&res);
+                Ok(res)
             }
         })*
     };
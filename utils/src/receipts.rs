@@ -0,0 +1,201 @@
+#[cfg(feature = "local")]
+use crate::local::LocalOutcome;
+use near_workspaces::network::Sandbox;
+use near_workspaces::result::ExecutionOutcome;
+use near_workspaces::types::{CryptoHash, Gas};
+use near_workspaces::{AccountId, Worker};
+use serde_json::json;
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReceiptStatus {
+    Success,
+    Failure(String),
+}
+
+/// Node of the receipt tree produced by a transaction.
+#[derive(Debug, Clone)]
+pub struct ReceiptNode {
+    pub id: CryptoHash,
+    pub executor_id: AccountId,
+    pub method: Option<String>,
+    pub gas_burnt: Gas,
+    pub logs: Vec<String>,
+    pub status: ReceiptStatus,
+    pub children: Vec<ReceiptNode>,
+}
+
+impl ReceiptNode {
+    /// Builds the tree from the transaction outcome and the outcomes of all its receipts.
+    /// The `method` is the method called by the transaction, if known. The outcomes don't
+    /// contain the actions of the receipts, so the methods of the receipts spawned by the
+    /// contracts are `None` until they are read by `resolve_methods`.
+    pub fn from_outcomes(
+        transaction: &ExecutionOutcome,
+        receipts: &[ExecutionOutcome],
+        method: Option<&str>,
+    ) -> Self {
+        let mut root = Self::new(transaction, None);
+        root.children = transaction
+            .receipt_ids
+            .iter()
+            .filter_map(|id| find_outcome(receipts, id))
+            .map(|outcome| Self::build(outcome, receipts, method))
+            .collect();
+        root
+    }
+
+    /// Builds the single node tree of the call executed by the in-process backend.
+    #[cfg(feature = "local")]
+    pub fn from_local(outcome: &LocalOutcome, method: Option<&str>) -> Self {
+        Self {
+            id: outcome.id,
+            executor_id: outcome.executor_id.clone(),
+            method: method.map(ToString::to_string),
+            gas_burnt: outcome.gas_burnt,
+            logs: outcome.logs.clone(),
+            status: match &outcome.status {
+                Ok(_) => ReceiptStatus::Success,
+                Err(reason) => ReceiptStatus::Failure(reason.clone()),
+            },
+            children: Vec::new(),
+        }
+    }
+
+    fn build(
+        outcome: &ExecutionOutcome,
+        receipts: &[ExecutionOutcome],
+        method: Option<&str>,
+    ) -> Self {
+        let mut node = Self::new(outcome, method);
+        node.children = outcome
+            .receipt_ids
+            .iter()
+            .filter_map(|id| find_outcome(receipts, id))
+            .map(|child| Self::build(child, receipts, None))
+            .collect();
+        node
+    }
+
+    fn new(outcome: &ExecutionOutcome, method: Option<&str>) -> Self {
+        let status = if outcome.is_success() {
+            ReceiptStatus::Success
+        } else {
+            let reason = outcome
+                .clone()
+                .into_result()
+                .err()
+                .map_or_else(String::new, |e| e.to_string());
+            ReceiptStatus::Failure(reason)
+        };
+
+        Self {
+            id: outcome.transaction_hash,
+            executor_id: outcome.executor_id.clone(),
+            method: method.map(ToString::to_string),
+            gas_burnt: outcome.gas_burnt,
+            logs: outcome.logs.clone(),
+            status,
+            children: Vec::new(),
+        }
+    }
+
+    /// Reads the receipts of the tree from the sandbox and sets the method of every receipt
+    /// to the method of its `FunctionCall` action, or `None` if it has no such action,
+    /// e.g. the refunds. The root is the transaction, so it's left as is.
+    pub async fn resolve_methods(&mut self, worker: &Worker<Sandbox>) -> anyhow::Result<()> {
+        let mut stack: Vec<&mut ReceiptNode> = self.children.iter_mut().collect();
+        while let Some(node) = stack.pop() {
+            node.method = receipt_method(worker, &node.id).await?;
+            stack.extend(node.children.iter_mut());
+        }
+        Ok(())
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.status == ReceiptStatus::Success
+    }
+
+    /// Iterates over the tree nodes in depth-first order.
+    pub fn iter(&self) -> impl Iterator<Item = &ReceiptNode> {
+        let mut stack = vec![self];
+        std::iter::from_fn(move || {
+            let node = stack.pop()?;
+            stack.extend(node.children.iter().rev());
+            Some(node)
+        })
+    }
+
+    /// Returns the first node executed by the account with the given method.
+    pub fn find(&self, executor_id: &AccountId, method: &str) -> Option<&ReceiptNode> {
+        self.iter()
+            .find(|node| &node.executor_id == executor_id && node.method.as_deref() == Some(method))
+    }
+
+    pub fn failures(&self) -> Vec<&ReceiptNode> {
+        self.iter().filter(|node| !node.is_success()).collect()
+    }
+
+    /// Total gas burnt by the node and all its descendants.
+    pub fn total_gas_burnt(&self) -> Gas {
+        Gas::from_gas(self.iter().map(|node| node.gas_burnt.as_gas()).sum())
+    }
+
+    fn fmt_with_indent(&self, f: &mut Formatter<'_>, depth: usize) -> std::fmt::Result {
+        let indent = "  ".repeat(depth);
+        let method = self.method.as_deref().unwrap_or("-");
+        let status = match &self.status {
+            ReceiptStatus::Success => "SUCCESS".to_string(),
+            ReceiptStatus::Failure(reason) => format!("FAILURE: {reason}"),
+        };
+        writeln!(
+            f,
+            "{indent}{} {}::{method} [{}] {status}",
+            self.id, self.executor_id, self.gas_burnt
+        )?;
+        for log in &self.logs {
+            writeln!(f, "{indent}  > {log}")?;
+        }
+        for child in &self.children {
+            child.fmt_with_indent(f, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for ReceiptNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.fmt_with_indent(f, 0)
+    }
+}
+
+fn find_outcome<'a>(
+    receipts: &'a [ExecutionOutcome],
+    id: &CryptoHash,
+) -> Option<&'a ExecutionOutcome> {
+    receipts
+        .iter()
+        .find(|outcome| &outcome.transaction_hash == id)
+}
+
+/// Returns the method of the last `FunctionCall` action of the receipt. The receipt view is
+/// read as JSON, so it doesn't depend on the version of the NEAR primitives.
+async fn receipt_method(
+    worker: &Worker<Sandbox>,
+    receipt_id: &CryptoHash,
+) -> anyhow::Result<Option<String>> {
+    let reference = serde_json::from_value(json!({ "receipt_id": receipt_id.to_string() }))?;
+    let receipt = worker
+        .receipt(reference)
+        .await
+        .map_err(|e| anyhow::anyhow!("error while reading receipt {receipt_id}: {e}"))?;
+    let receipt = serde_json::to_value(receipt)?;
+    let method = receipt["receipt"]["Action"]["actions"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|action| action["FunctionCall"]["method_name"].as_str())
+        .last()
+        .map(ToString::to_string);
+    Ok(method)
+}
@@ -1,8 +1,10 @@
+use crate::receipts::ReceiptNode;
 use aurora_engine_types::borsh::BorshDeserialize;
+use aurora_engine_types::parameters::engine::SubmitResult;
 use aurora_engine_types::types::Address;
 use aurora_engine_types::{H256, U256};
 use near_sdk::{json_types::U128, PromiseOrValue};
-use near_workspaces::result::{ExecutionFinalResult, ExecutionOutcome};
+use near_workspaces::result::{ExecutionFinalResult, ExecutionOutcome, ExecutionSuccess};
 use near_workspaces::types::Gas;
 use serde::de::DeserializeOwned;
 use std::borrow::Borrow;
@@ -66,9 +68,11 @@ impl ViewResult<H256> {
 
 #[derive(Debug)]
 pub struct ExecutionResult<T> {
-    inner: near_workspaces::result::ExecutionSuccess,
+    inner: ExecutionSuccess,
     value: T,
     success: bool,
+    method: Option<String>,
+    evm_gas: Option<u64>,
 }
 
 impl<T: DeserializeOwned> ExecutionResult<T> {
@@ -106,21 +110,46 @@ impl ExecutionResult<Address> {
         let success = result.is_success();
         let inner = result.into_result()?;
         let bytes: Vec<u8> = inner.borsh()?;
-        let value = Address::try_from_slice(&bytes)
-            .map_err(|e| anyhow::anyhow!("Creating address from slice error: {e}"))?;
+        let value = address_from_borsh(&bytes)?;
         Ok(Self::new(inner, value, success))
     }
 }
 
+impl ExecutionResult<SubmitResult> {
+    /// Decodes the `SubmitResult` and keeps its EVM gas for the gas report.
+    pub fn borsh_submit(result: ExecutionFinalResult) -> anyhow::Result<Self> {
+        let mut res = Self::borsh(result)?;
+        res.evm_gas = Some(res.value.gas_used);
+        Ok(res)
+    }
+}
+
 impl<T> ExecutionResult<T> {
-    pub fn new(inner: near_workspaces::result::ExecutionSuccess, value: T, success: bool) -> Self {
+    pub fn new(inner: ExecutionSuccess, value: T, success: bool) -> Self {
         Self {
             inner,
             value,
             success,
+            method: None,
+            evm_gas: None,
         }
     }
 
+    /// Sets the name of the contract method which has been called by the transaction.
+    pub fn with_method(mut self, method: &str) -> Self {
+        self.method = Some(method.to_string());
+        self
+    }
+
+    pub fn method(&self) -> Option<&str> {
+        self.method.as_deref()
+    }
+
+    /// EVM gas used by the transaction, if the result has been decoded from a `SubmitResult`.
+    pub fn evm_gas(&self) -> Option<u64> {
+        self.evm_gas
+    }
+
     pub fn value(&self) -> &T {
         &self.value
     }
@@ -157,6 +186,15 @@ impl<T> ExecutionResult<T> {
         self.inner.logs()
     }
 
+    /// Reconstructs the tree of receipts produced by the transaction.
+    pub fn receipt_tree(&self) -> ReceiptNode {
+        ReceiptNode::from_outcomes(
+            self.inner.outcome(),
+            self.inner.receipt_outcomes(),
+            self.method.as_deref(),
+        )
+    }
+
     pub fn is_success(&self) -> bool {
         self.success
     }
@@ -177,3 +215,9 @@ impl<T> Borrow<T> for ExecutionResult<T> {
         &self.value
     }
 }
+
+/// Decodes the address returned by the engine as a borsh-serialized vector.
+pub(crate) fn address_from_borsh(bytes: &[u8]) -> anyhow::Result<Address> {
+    Address::try_from_slice(bytes)
+        .map_err(|e| anyhow::anyhow!("Creating address from slice error: {e}"))
+}
@@ -0,0 +1,312 @@
+#[cfg(feature = "local")]
+use crate::local::LocalOutcome;
+use crate::Contract;
+use near_sdk::json_types::{Base64VecU8, U128};
+use near_workspaces::result::{ExecutionFinalResult, ViewResultDetails};
+use near_workspaces::types::{Gas, NearToken};
+use near_workspaces::{Account, AccountId};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+
+/// Environment variable with the path of the trace file of the contracts created without
+/// an explicit recorder, see [`TraceRecorder::from_env`].
+pub const TRACE_ENV: &str = "AURORA_WORKSPACE_TRACE";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CallKind {
+    Call,
+    View,
+}
+
+/// Call or view issued to a contract.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceCall {
+    pub kind: CallKind,
+    pub contract_id: AccountId,
+    pub method: String,
+    /// Account which signed the call, `None` for views.
+    pub signer_id: Option<AccountId>,
+    pub args: Base64VecU8,
+    pub gas: u64,
+    pub deposit: U128,
+}
+
+impl TraceCall {
+    pub fn call(
+        contract_id: &AccountId,
+        method: &str,
+        signer_id: &AccountId,
+        args: Vec<u8>,
+        gas: Gas,
+        deposit: NearToken,
+    ) -> Self {
+        Self {
+            kind: CallKind::Call,
+            contract_id: contract_id.clone(),
+            method: method.to_string(),
+            signer_id: Some(signer_id.clone()),
+            args: args.into(),
+            gas: gas.as_gas(),
+            deposit: U128(deposit.as_yoctonear()),
+        }
+    }
+
+    pub fn view(contract_id: &AccountId, method: &str, args: Vec<u8>) -> Self {
+        Self {
+            kind: CallKind::View,
+            contract_id: contract_id.clone(),
+            method: method.to_string(),
+            signer_id: None,
+            args: args.into(),
+            gas: 0,
+            deposit: U128(0),
+        }
+    }
+}
+
+/// Result of the call as seen by the caller.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum TraceOutcome {
+    Success {
+        value: Base64VecU8,
+        logs: Vec<String>,
+    },
+    Failure {
+        error: String,
+    },
+}
+
+impl TraceOutcome {
+    pub fn from_result(result: &ExecutionFinalResult) -> Self {
+        match result.clone().into_result() {
+            Ok(success) => match success.raw_bytes() {
+                Ok(value) => Self::Success {
+                    value: value.into(),
+                    logs: success
+                        .logs()
+                        .into_iter()
+                        .map(ToString::to_string)
+                        .collect(),
+                },
+                Err(e) => Self::Failure {
+                    error: e.to_string(),
+                },
+            },
+            Err(failure) => Self::Failure {
+                error: failure.to_string(),
+            },
+        }
+    }
+
+    #[cfg(feature = "local")]
+    pub fn from_local(outcome: &LocalOutcome) -> Self {
+        match &outcome.status {
+            Ok(value) => Self::Success {
+                value: value.clone().into(),
+                logs: outcome.logs.clone(),
+            },
+            Err(error) => Self::Failure {
+                error: error.clone(),
+            },
+        }
+    }
+
+    pub fn from_view(view: &anyhow::Result<ViewResultDetails>) -> Self {
+        match view {
+            Ok(view) => Self::Success {
+                value: view.result.clone().into(),
+                logs: view.logs.clone(),
+            },
+            Err(e) => Self::Failure {
+                error: e.to_string(),
+            },
+        }
+    }
+
+    pub fn is_success(&self) -> bool {
+        matches!(self, Self::Success { .. })
+    }
+}
+
+/// Single line of the trace file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceEntry {
+    #[serde(flatten)]
+    pub call: TraceCall,
+    pub outcome: TraceOutcome,
+}
+
+impl TraceEntry {
+    pub fn new(call: TraceCall, outcome: TraceOutcome) -> Self {
+        Self { call, outcome }
+    }
+}
+
+/// Appends the calls and views issued through a contract to the trace file.
+///
+/// The recorder is attached to the contract with `with_recorder`. Contracts created
+/// without one use the recorder of [`TRACE_ENV`] if the variable is set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceRecorder {
+    path: PathBuf,
+}
+
+impl TraceRecorder {
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Returns the recorder of the file set by [`TRACE_ENV`], `None` if it isn't set.
+    pub fn from_env() -> Option<Self> {
+        std::env::var_os(TRACE_ENV).map(Self::new)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Appends the entry as a JSON line to the trace file.
+    pub fn record(&self, entry: &TraceEntry) -> anyhow::Result<()> {
+        let mut buf = serde_json::to_vec(entry)?;
+        buf.push(b'\n');
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?
+            .write_all(&buf)?;
+        Ok(())
+    }
+}
+
+/// Loads the entries appended to the file by [`TraceRecorder::record`].
+pub fn load<P: AsRef<Path>>(path: P) -> anyhow::Result<Vec<TraceEntry>> {
+    let file = std::fs::File::open(path)?;
+    let mut entries = Vec::new();
+    for line in std::io::BufReader::new(file).lines() {
+        let line = line?;
+        if !line.trim().is_empty() {
+            entries.push(serde_json::from_str(&line)?);
+        }
+    }
+    Ok(entries)
+}
+
+/// Re-executes a recorded trace against a contract and compares the outcomes.
+///
+/// All entries are sent to the given contract regardless of the recorded contract id,
+/// so the trace of one contract can be replayed against a fresh deployment with another
+/// account. Calls are signed by the registered account with the recorded id, or by the
+/// signer of the contract otherwise. The replayed calls aren't recorded.
+#[derive(Debug, Clone)]
+pub struct Replayer {
+    contract: Contract,
+    signers: HashMap<AccountId, Account>,
+}
+
+impl Replayer {
+    pub fn new(contract: &Contract) -> Self {
+        Self {
+            contract: contract.with_recorder(None),
+            signers: HashMap::new(),
+        }
+    }
+
+    /// Signs the calls recorded with the id of the account by this account.
+    pub fn with_signer(mut self, account: &Account) -> Self {
+        self.signers.insert(account.id().clone(), account.clone());
+        self
+    }
+
+    pub async fn replay(&self, entries: &[TraceEntry]) -> anyhow::Result<ReplayReport> {
+        let mut diffs = Vec::new();
+        for (index, entry) in entries.iter().enumerate() {
+            let actual = self.execute(&entry.call).await?;
+            if actual != entry.outcome {
+                diffs.push(ReplayDiff {
+                    index,
+                    call: entry.call.clone(),
+                    expected: entry.outcome.clone(),
+                    actual,
+                });
+            }
+        }
+
+        Ok(ReplayReport {
+            replayed: entries.len(),
+            diffs,
+        })
+    }
+
+    async fn execute(&self, call: &TraceCall) -> anyhow::Result<TraceOutcome> {
+        let args = call.args.0.clone();
+        match call.kind {
+            CallKind::View => {
+                let view = self.contract.near_view(&call.method).args(args).await;
+                Ok(TraceOutcome::from_view(&view))
+            }
+            CallKind::Call => {
+                let contract = match call.signer_id.as_ref().and_then(|id| self.signers.get(id)) {
+                    Some(signer) => self.contract.with_signer(signer),
+                    None => self.contract.clone(),
+                };
+                let result = contract
+                    .near_call(&call.method)
+                    .args(args)
+                    .gas(Gas::from_gas(call.gas))
+                    .deposit(NearToken::from_yoctonear(call.deposit.0))
+                    .transact()
+                    .await?;
+                Ok(TraceOutcome::from_result(&result))
+            }
+        }
+    }
+}
+
+/// Entry whose replayed outcome differs from the recorded one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplayDiff {
+    /// Index of the entry in the trace.
+    pub index: usize,
+    pub call: TraceCall,
+    pub expected: TraceOutcome,
+    pub actual: TraceOutcome,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplayReport {
+    pub replayed: usize,
+    pub diffs: Vec<ReplayDiff>,
+}
+
+impl ReplayReport {
+    /// Returns `true` if all replayed outcomes match the recorded ones.
+    pub fn is_clean(&self) -> bool {
+        self.diffs.is_empty()
+    }
+}
+
+impl Display for ReplayReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "replayed {} entries, {} differ",
+            self.replayed,
+            self.diffs.len()
+        )?;
+        for diff in &self.diffs {
+            writeln!(
+                f,
+                "#{} {:?} {}::{}",
+                diff.index, diff.call.kind, diff.call.contract_id, diff.call.method
+            )?;
+            writeln!(f, "  expected: {:?}", diff.expected)?;
+            writeln!(f, "  actual:   {:?}", diff.actual)?;
+        }
+        Ok(())
+    }
+}
@@ -1,84 +1,502 @@
-use aurora_engine_types::borsh::BorshSerialize;
-use near_workspaces::result::ExecutionFinalResult;
+#[cfg(feature = "local")]
+use crate::local::{LocalAccount, LocalOutcome, LocalRuntime};
+use crate::results::ExecutionResult;
+use crate::trace::{TraceCall, TraceEntry, TraceOutcome, TraceRecorder};
+use crate::ContractSnapshot;
+use aurora_engine_types::borsh::{self, BorshSerialize};
+use near_workspaces::operations::{Function, Transaction, TransactionStatus};
+use near_workspaces::result::{ExecutionFinalResult, ViewResultDetails};
 use near_workspaces::rpc::query::{Query, ViewFunction};
 use near_workspaces::rpc::BoxFuture;
-use near_workspaces::types::{Gas, NearToken};
+use near_workspaces::types::{AccessKey, CryptoHash, Gas, NearToken, PublicKey};
+use near_workspaces::{Account, AccountId};
 use std::future::IntoFuture;
+use std::task::Poll;
 
+/// Gas attached to a function call by default, the same as in `near_workspaces`.
+const DEFAULT_CALL_GAS: Gas = Gas::from_tgas(10);
+/// Maximum gas which can be attached to all actions of a transaction.
+const MAX_GAS: Gas = Gas::from_tgas(300);
 pub struct ViewTransaction<'a> {
-    inner: Query<'a, ViewFunction>,
+    inner: ViewKind<'a>,
+    contract_id: AccountId,
+    method: String,
+    args: Args,
+    recorder: Option<TraceRecorder>,
+}
+
+enum ViewKind<'a> {
+    Sandbox(Query<'a, ViewFunction>),
+    #[cfg(feature = "local")]
+    Local(LocalRuntime),
 }
 
 impl<'a> ViewTransaction<'a> {
-    pub(crate) fn new(view_tx: Query<'a, ViewFunction>) -> Self {
-        Self { inner: view_tx }
+    pub(crate) fn new(
+        view_tx: Query<'a, ViewFunction>,
+        contract_id: AccountId,
+        method: &str,
+    ) -> Self {
+        Self {
+            inner: ViewKind::Sandbox(view_tx),
+            contract_id,
+            method: method.to_string(),
+            args: Ok(Vec::new()),
+            recorder: None,
+        }
+    }
+
+    #[cfg(feature = "local")]
+    pub(crate) fn local(runtime: LocalRuntime, contract_id: AccountId, method: &str) -> Self {
+        Self {
+            inner: ViewKind::Local(runtime),
+            contract_id,
+            method: method.to_string(),
+            args: Ok(Vec::new()),
+            recorder: None,
+        }
+    }
+
+    /// Sets the recorder which the view is appended to.
+    pub(crate) fn with_recorder(mut self, recorder: Option<TraceRecorder>) -> Self {
+        self.recorder = recorder;
+        self
     }
 
     pub fn args(mut self, args: Vec<u8>) -> Self {
-        self.inner = self.inner.args(args);
+        self.args = Ok(args);
         self
     }
 
     pub fn args_json<U: serde::Serialize>(mut self, args: U) -> Self {
-        self.inner = self.inner.args_json(args);
+        self.args = json_args(args);
         self
     }
 
     pub fn args_borsh<U: BorshSerialize>(mut self, args: U) -> Self {
-        self.inner = self.inner.args_borsh(args);
+        self.args = borsh_args(args);
         self
     }
 }
 
 impl<'a> IntoFuture for ViewTransaction<'a> {
-    type Output = anyhow::Result<near_workspaces::result::ViewResultDetails>;
+    type Output = anyhow::Result<ViewResultDetails>;
     type IntoFuture = BoxFuture<'a, Self::Output>;
 
     fn into_future(self) -> Self::IntoFuture {
-        Box::pin(async { Ok(self.inner.await?) }.into_future())
+        Box::pin(
+            async {
+                let args = self.args.map_err(anyhow::Error::msg)?;
+                let call = self
+                    .recorder
+                    .as_ref()
+                    .map(|_| TraceCall::view(&self.contract_id, &self.method, args.clone()));
+                let result = match self.inner {
+                    ViewKind::Sandbox(query) => query.args(args).await.map_err(Into::into),
+                    #[cfg(feature = "local")]
+                    ViewKind::Local(runtime) => runtime.view(&self.contract_id, &self.method, args),
+                };
+                if let (Some(recorder), Some(call)) = (&self.recorder, call) {
+                    recorder.record(&TraceEntry::new(call, TraceOutcome::from_view(&result)))?;
+                }
+                result
+            }
+            .into_future(),
+        )
+    }
+}
+
+/// Serialized arguments of a call or the serialization error.
+type Args = Result<Vec<u8>, String>;
+
+fn json_args<S: serde::Serialize>(args: S) -> Args {
+    serde_json::to_vec(&args).map_err(|e| e.to_string())
+}
+
+fn borsh_args<B: BorshSerialize>(args: B) -> Args {
+    borsh::to_vec(&args).map_err(|e| e.to_string())
+}
+
+/// Arguments which failed to serialize. Passing them to `Function` defers the error
+/// until the transaction is sent, the same as `Function::args_json` does.
+struct InvalidArgs(String);
+
+impl serde::Serialize for InvalidArgs {
+    fn serialize<S: serde::Serializer>(&self, _: S) -> Result<S::Ok, S::Error> {
+        Err(serde::ser::Error::custom(&self.0))
     }
 }
 
+/// Account which signs the call, together with the backend which executes it.
+#[derive(Debug, Clone)]
+pub(crate) enum CallSigner {
+    Sandbox(Account),
+    #[cfg(feature = "local")]
+    Local(LocalAccount),
+}
+
 pub struct CallTransaction {
-    inner: near_workspaces::operations::CallTransaction,
+    signer: CallSigner,
+    contract_id: AccountId,
+    method: String,
+    args: Args,
+    gas: Gas,
+    deposit: NearToken,
+    deployed: Option<near_workspaces::Contract>,
+    recorder: Option<TraceRecorder>,
 }
 
 impl CallTransaction {
-    pub(crate) fn new(call_tx: near_workspaces::operations::CallTransaction) -> Self {
-        Self { inner: call_tx }
+    pub(crate) fn new(signer: CallSigner, contract_id: AccountId, function: &str) -> Self {
+        Self {
+            signer,
+            contract_id,
+            method: function.to_string(),
+            args: Ok(Vec::new()),
+            gas: DEFAULT_CALL_GAS,
+            deposit: NearToken::from_yoctonear(0),
+            deployed: None,
+            recorder: None,
+        }
+    }
+
+    /// Sets the deployed contract which the snapshot of the called contract is read from.
+    pub(crate) fn with_deployed(mut self, deployed: Option<near_workspaces::Contract>) -> Self {
+        self.deployed = deployed;
+        self
+    }
+
+    /// Sets the recorder which the call is appended to.
+    pub(crate) fn with_recorder(mut self, recorder: Option<TraceRecorder>) -> Self {
+        self.recorder = recorder;
+        self
     }
 
     pub fn args(mut self, args: Vec<u8>) -> Self {
-        self.inner = self.inner.args(args);
+        self.args = Ok(args);
         self
     }
 
     pub fn args_json<S: serde::Serialize>(mut self, args: S) -> Self {
-        self.inner = self.inner.args_json(args);
+        self.args = json_args(args);
         self
     }
 
     pub fn args_borsh<B: BorshSerialize>(mut self, args: B) -> Self {
-        self.inner = self.inner.args_borsh(args);
+        self.args = borsh_args(args);
         self
     }
 
     pub fn gas(mut self, gas: Gas) -> Self {
-        self.inner = self.inner.gas(gas);
+        self.gas = gas;
         self
     }
 
     pub fn max_gas(mut self) -> Self {
-        self.inner = self.inner.max_gas();
+        self.gas = MAX_GAS;
         self
     }
 
     pub fn deposit(mut self, deposit: NearToken) -> Self {
-        self.inner = self.inner.deposit(deposit);
+        self.deposit = deposit;
         self
     }
 
+    /// Returns the account which signs the call.
+    pub fn signer_id(&self) -> &AccountId {
+        match &self.signer {
+            CallSigner::Sandbox(signer) => signer.id(),
+            #[cfg(feature = "local")]
+            CallSigner::Local(signer) => signer.id(),
+        }
+    }
+
+    pub fn contract_id(&self) -> &AccountId {
+        &self.contract_id
+    }
+
+    /// Returns the serialized arguments of the call.
+    pub fn arguments(&self) -> anyhow::Result<&[u8]> {
+        self.args
+            .as_deref()
+            .map_err(|e| anyhow::anyhow!("error while serializing arguments: {e}"))
+    }
+
+    /// Reads the code, state and balance of the called contract from the sandbox. The
+    /// contract must have been deployed by [`crate::Contract::deploy`].
+    pub async fn snapshot(&self) -> anyhow::Result<ContractSnapshot> {
+        let deployed = self.deployed.as_ref().ok_or_else(|| {
+            anyhow::anyhow!(
+                "the state of `{}` is unknown to the caller",
+                self.contract_id
+            )
+        })?;
+        ContractSnapshot::read(deployed).await
+    }
+
+    /// Simulates the call in-process without sending a transaction, with the maximum gas
+    /// attached. Calls to the sandbox are simulated on the snapshot of the called contract.
+    #[cfg(feature = "local")]
+    pub async fn simulate(&self) -> anyhow::Result<LocalOutcome> {
+        let runtime = match &self.signer {
+            CallSigner::Sandbox(_) => LocalRuntime::from_snapshot(self.snapshot().await?)?,
+            CallSigner::Local(signer) => signer.runtime().clone(),
+        };
+        runtime.simulate(
+            self.signer_id(),
+            &self.contract_id,
+            &self.method,
+            self.arguments()?.to_vec(),
+            MAX_GAS,
+            self.deposit,
+        )
+    }
+
+    /// Returns the function call action without sending it.
+    pub fn into_function(self) -> Function {
+        let function = Function::new(&self.method)
+            .gas(self.gas)
+            .deposit(self.deposit);
+        match self.args {
+            Ok(args) => function.args(args),
+            Err(e) => function.args_json(InvalidArgs(e)),
+        }
+    }
+
     pub async fn transact(self) -> anyhow::Result<ExecutionFinalResult> {
-        Ok(self.inner.transact().await?)
+        let trace = self.trace_call();
+        let result = self
+            .sandbox_signer()?
+            .batch(&self.contract_id)
+            .call(self.into_function())
+            .transact()
+            .await?;
+        if let Some((recorder, call)) = trace {
+            recorder.record(&TraceEntry::new(call, TraceOutcome::from_result(&result)))?;
+        }
+        Ok(result)
+    }
+
+    /// Sends the transaction without waiting for its execution.
+    pub async fn transact_async(self) -> anyhow::Result<SentTransaction> {
+        let trace = self.trace_call();
+        let status = self
+            .sandbox_signer()?
+            .batch(&self.contract_id)
+            .call(self.into_function())
+            .transact_async()
+            .await?;
+        Ok(SentTransaction { status, trace })
+    }
+
+    /// Executes the call signed by an account of the in-process runtime.
+    #[cfg(feature = "local")]
+    pub fn transact_local(self) -> anyhow::Result<LocalOutcome> {
+        let trace = self.trace_call();
+        let CallSigner::Local(signer) = &self.signer else {
+            anyhow::bail!("the call is signed by a sandbox account, send it with `transact`");
+        };
+        let outcome = signer.runtime().call(
+            signer,
+            &self.contract_id,
+            &self.method,
+            self.args.clone().map_err(anyhow::Error::msg)?,
+            self.gas,
+            self.deposit,
+        )?;
+        if let Some((recorder, call)) = trace {
+            recorder.record(&TraceEntry::new(call, TraceOutcome::from_local(&outcome)))?;
+        }
+        Ok(outcome)
+    }
+
+    /// Returns the sandbox account which signs the call.
+    fn sandbox_signer(&self) -> anyhow::Result<Account> {
+        match &self.signer {
+            CallSigner::Sandbox(signer) => Ok(signer.clone()),
+            #[cfg(feature = "local")]
+            CallSigner::Local(_) => {
+                anyhow::bail!(
+                    "the call is signed by a local account, send it with `transact_local`"
+                )
+            }
+        }
+    }
+
+    /// Describes the call for the trace, if the call has a recorder.
+    fn trace_call(&self) -> Option<(TraceRecorder, TraceCall)> {
+        let recorder = self.recorder.clone()?;
+        let signer_id = match &self.signer {
+            CallSigner::Sandbox(signer) => signer.id(),
+            #[cfg(feature = "local")]
+            CallSigner::Local(signer) => signer.id(),
+        };
+        let args = self.args.as_ref().ok()?;
+        let call = TraceCall::call(
+            &self.contract_id,
+            &self.method,
+            signer_id,
+            args.clone(),
+            self.gas,
+            self.deposit,
+        );
+        Some((recorder, call))
+    }
+}
+
+/// Transaction which has been sent to the sandbox.
+#[derive(Debug)]
+pub struct SentTransaction {
+    status: TransactionStatus,
+    trace: Option<(TraceRecorder, TraceCall)>,
+}
+
+/// Transaction which has been sent but may not have been executed yet.
+pub struct PendingTransaction<T> {
+    inner: SentTransaction,
+    decode: fn(ExecutionFinalResult) -> anyhow::Result<ExecutionResult<T>>,
+}
+
+impl<T> PendingTransaction<T> {
+    pub fn new(
+        inner: SentTransaction,
+        decode: fn(ExecutionFinalResult) -> anyhow::Result<ExecutionResult<T>>,
+    ) -> Self {
+        Self { inner, decode }
+    }
+
+    /// Hash of the sent transaction.
+    pub fn id(&self) -> &CryptoHash {
+        self.inner.status.id()
+    }
+
+    /// Checks the current status of the transaction without blocking.
+    pub async fn status(&self) -> anyhow::Result<Poll<ExecutionResult<T>>> {
+        match self.inner.status.status().await? {
+            Poll::Ready(result) => (self.decode)(result).map(Poll::Ready),
+            Poll::Pending => Ok(Poll::Pending),
+        }
+    }
+
+    /// Waits until the transaction is executed.
+    ///
+    /// The call is added to the trace here, so transactions which are only polled
+    /// with [`PendingTransaction::status`] aren't recorded.
+    pub async fn wait(self) -> anyhow::Result<ExecutionResult<T>> {
+        let result = self.inner.status.await?;
+        if let Some((recorder, call)) = self.inner.trace {
+            recorder.record(&TraceEntry::new(call, TraceOutcome::from_result(&result)))?;
+        }
+        (self.decode)(result)
+    }
+}
+
+/// Waits for all pending transactions and returns their results in the same order.
+///
+/// The transactions are already sent, so they are executed concurrently regardless
+/// of the order in which they are awaited here.
+pub async fn wait_all<T>(
+    pending: Vec<PendingTransaction<T>>,
+) -> anyhow::Result<Vec<ExecutionResult<T>>> {
+    let mut results = Vec::with_capacity(pending.len());
+    for tx in pending {
+        results.push(tx.wait().await?);
+    }
+    Ok(results)
+}
+
+/// Sends all calls without waiting for each other and collects their results.
+pub async fn transact_concurrently<C: BatchCall>(
+    calls: impl IntoIterator<Item = C>,
+) -> anyhow::Result<Vec<ExecutionResult<C::Output>>> {
+    let mut pending = Vec::new();
+    for call in calls {
+        pending.push(PendingTransaction::new(
+            call.into_transaction().transact_async().await?,
+            C::decode,
+        ));
+    }
+    wait_all(pending).await
+}
+
+/// Call builder generated by the binding macros.
+///
+/// It can be added as an action to a [`BatchTransaction`] or sent together with
+/// other calls by [`transact_concurrently`]. Only the calls without a value can be
+/// added to a batch before its last action.
+pub trait BatchCall {
+    type Output;
+
+    fn into_transaction(self) -> CallTransaction;
+
+    fn into_function(self) -> Function
+    where
+        Self: Sized,
+    {
+        self.into_transaction().into_function()
+    }
+
+    fn decode(result: ExecutionFinalResult) -> anyhow::Result<ExecutionResult<Self::Output>>;
+}
+
+/// Collects several actions into a single transaction to the same receiver.
+///
+/// All actions are executed within one receipt, so either all of them succeed
+/// or the whole transaction is reverted. The receipt has a single outcome: its
+/// status and logs cover the batch as a whole, and NEAR returns the value of the
+/// last action only. So only the calls without a value can be added by
+/// [`BatchTransaction::call`], the call whose value is needed is the last action
+/// appended by [`BatchTransaction::transact_last`].
+pub struct BatchTransaction {
+    inner: Transaction,
+}
+
+impl BatchTransaction {
+    pub(crate) fn new(transaction: Transaction) -> Self {
+        Self { inner: transaction }
+    }
+
+    /// Appends the call which doesn't return a value. The values of all actions but the last
+    /// one are discarded, so the calls returning a value can't be added here, use
+    /// [`BatchTransaction::transact_last`] for the last one instead.
+    pub fn call<C: BatchCall<Output = ()>>(self, call: C) -> Self {
+        self.map(|tx| tx.call(call.into_function()))
+    }
+
+    pub fn transfer(self, deposit: NearToken) -> Self {
+        self.map(|tx| tx.transfer(deposit))
+    }
+
+    pub fn add_key(self, pk: PublicKey, access_key: AccessKey) -> Self {
+        self.map(|tx| tx.add_key(pk, access_key))
+    }
+
+    pub fn delete_key(self, pk: PublicKey) -> Self {
+        self.map(|tx| tx.delete_key(pk))
+    }
+
+    pub async fn transact(self) -> anyhow::Result<ExecutionResult<()>> {
+        let result = self.inner.transact().await?;
+        let success = result.is_success();
+        let inner = result.into_result()?;
+        Ok(ExecutionResult::new(inner, (), success))
+    }
+
+    /// Appends the call as the last action and decodes its return value.
+    ///
+    /// The status and logs of the result belong to the whole batch; the return
+    /// values of the preceding actions are discarded by NEAR and can't be recovered.
+    pub async fn transact_last<C: BatchCall>(
+        self,
+        call: C,
+    ) -> anyhow::Result<ExecutionResult<C::Output>> {
+        let result = self.inner.call(call.into_function()).transact().await?;
+        C::decode(result)
+    }
+
+    fn map(self, f: impl FnOnce(Transaction) -> Transaction) -> Self {
+        Self {
+            inner: f(self.inner),
+        }
     }
 }